@@ -0,0 +1,191 @@
+use super::board::Board;
+use super::color::{BLACK, COLORS, ColorTrait, WHITE};
+use super::piece::*;
+use crate::engine::attacks::bishop::get_bishop_mask;
+use crate::engine::attacks::king::get_king_mask;
+use crate::engine::attacks::knight::get_knight_mask;
+use crate::engine::attacks::pawn::get_pawn_att_mask;
+use crate::engine::attacks::queen::get_queen_mask;
+use crate::engine::attacks::rook::get_rook_mask;
+use crate::engine::misc::bitboard::Iterator;
+
+// NOTE: Attack maps
+//
+// For every occupied square the attacks of its piece, as used by the evaluation (bishops see
+// through queens, rooks through queens and own rooks), and for both sides the attacked and the
+// attacked twice squares.
+//
+// make_move and undo_move only mark the squares they change. When the maps are needed, the
+// attacks of the marked squares and of the sliders that reached them are recomputed: a ray
+// can only change if one of the squares it covered did.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackMaps {
+    pub mask: [u64; 64],
+    pub side: [u64; 2],
+    pub side_2: [u64; 2],
+    // Squares changed since the last update
+    pub dirty: u64,
+}
+
+impl AttackMaps {
+    pub const fn init() -> Self {
+        Self { mask: [0; 64], side: [0; 2], side_2: [0; 2], dirty: 0 }
+    }
+}
+
+///
+/// Attacks of the piece from the square, seeing through the pieces as the evaluation does
+///
+#[inline(always)]
+pub fn x_ray_mask(board: &Board, piece: Piece, sq: usize) -> u64 {
+    let clr = piece.color();
+    let (mut own, mut enemy) = board.both_occ_bb(clr);
+    match piece.kind() {
+        PAWN => get_pawn_att_mask(sq, own, enemy, clr),
+        KNIGHT => get_knight_mask(sq, own, enemy, clr),
+        BISHOP => {
+            own &= !(board.queen_bb(clr));
+            enemy &= !board.queen_bb(clr.opp());
+            get_bishop_mask(sq, own, enemy, clr)
+        }
+        ROOK => {
+            own &= !(board.queen_bb(clr) | board.rook_bb(clr));
+            enemy &= !board.queen_bb(clr.opp());
+            get_rook_mask(sq, own, enemy, clr)
+        }
+        QUEEN => get_queen_mask(sq, own, enemy, clr),
+        KING => get_king_mask(sq, own, enemy, clr),
+        _ => panic!("Invalid Peace Type"),
+    }
+}
+
+pub trait AttackMapTrait {
+    fn att_refresh(&mut self);
+    fn att_update(&mut self);
+    fn att_set(&mut self, sq: usize);
+}
+
+impl AttackMapTrait for Board {
+    ///
+    /// Recomputes the attack maps of the position from scratch
+    ///
+    fn att_refresh(&mut self) {
+        self.attacks.dirty = self.occ_bb(WHITE) | self.occ_bb(BLACK);
+        self.attacks.mask = [0; 64];
+        self.att_update();
+    }
+
+    ///
+    /// Brings the attack maps up to date with the squares changed since the last update
+    ///
+    #[inline(always)]
+    fn att_update(&mut self) {
+        let changed = self.attacks.dirty;
+        if changed == 0 {
+            return;
+        }
+        self.attacks.dirty = 0;
+
+        let mut bb = changed;
+        while let Some(sq) = bb.next() {
+            self.att_set(sq);
+        }
+
+        // Sliders that reached a changed square see a different board
+        let mut sliders = 0;
+        for clr in COLORS {
+            sliders |= self.bishop_bb(clr) | self.rook_bb(clr) | self.queen_bb(clr);
+        }
+        let mut bb = sliders & !changed;
+        while let Some(sq) = bb.next() {
+            if self.attacks.mask[sq] & changed != 0 {
+                self.att_set(sq);
+            }
+        }
+
+        for clr in COLORS {
+            let (mut side, mut side_2) = (0, 0);
+            let mut bb = self.occ_bb(clr);
+            while let Some(sq) = bb.next() {
+                side_2 |= side & self.attacks.mask[sq];
+                side |= self.attacks.mask[sq];
+            }
+            self.attacks.side[clr.idx()] = side;
+            self.attacks.side_2[clr.idx()] = side_2;
+        }
+    }
+
+    ///
+    /// Replaces the attacks of the square with the attacks of the piece on it (if any)
+    ///
+    #[inline(always)]
+    fn att_set(&mut self, sq: usize) {
+        let piece = self.squares[sq];
+        self.attacks.mask[sq] = if piece == 0 { 0 } else { x_ray_mask(self, piece, sq) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::misc::const_utility::{
+        FEN_CASTLE_ONE, FEN_POS_FIVE, FEN_POS_FOUR, FEN_POS_THREE,
+    };
+    use crate::engine::move_generator::make_move::BoardMoveTrait;
+    use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+    fn refreshed(board: &Board) -> AttackMaps {
+        let mut board = board.clone();
+        board.att_refresh();
+        board.attacks
+    }
+
+    fn updated(board: &mut Board) -> AttackMaps {
+        board.att_update();
+        board.attacks
+    }
+
+    #[test]
+    fn test_attack_maps() {
+        let mut board = Board::read_fen("4k3/8/8/8/8/8/1N6/R2R2K1 w - - 0 1");
+        let attacks = updated(&mut board);
+
+        // The rook sees through the other rook, d1 is attacked by it and by the knight
+        assert_eq!(attacks.mask[0] & (1 << 5), 1 << 5);
+        assert_eq!(attacks.side_2[WHITE.idx()] & (1 << 3), 1 << 3);
+        assert_eq!(attacks.side_2[WHITE.idx()] & (1 << 8), 0);
+        assert_eq!(attacks.side[BLACK.idx()], get_king_mask(60, 0, 0, BLACK));
+        assert_eq!(attacks.side_2[BLACK.idx()], 0);
+    }
+
+    #[test]
+    fn test_incremental_attacks() {
+        for fen in [FEN_CASTLE_ONE, FEN_POS_THREE, FEN_POS_FOUR, FEN_POS_FIVE] {
+            let mut board = Board::read_fen(fen);
+            let root = updated(&mut board);
+            assert_eq!(root, refreshed(&board));
+
+            // Every move (and one reply), including captures, castling and promotions. The maps
+            // are updated after every move, or only once after a few of them.
+            for (idx, (mv, _)) in board.gen_moves().into_iter().enumerate() {
+                if !board.make_move(&mv) {
+                    continue;
+                }
+                if idx % 2 == 0 {
+                    assert_eq!(updated(&mut board), refreshed(&board), "{:?}", mv);
+                }
+
+                if let Some(&(reply, _)) = board.gen_moves().last()
+                    && board.make_move(&reply)
+                {
+                    assert_eq!(updated(&mut board), refreshed(&board), "{:?} {:?}", mv, reply);
+                    board.undo_move();
+                }
+                board.undo_move();
+                assert_eq!(updated(&mut board), root);
+            }
+        }
+    }
+}
@@ -1,18 +1,27 @@
+use super::attack_map::AttackMaps;
 use super::color::{Color, ColorTrait};
 use super::piece::{BISHOP, KING, KNIGHT, Piece, PieceTrait, QUEEN, ROOK};
 use super::state::BoardState;
+use super::zobrist::ZobristKeysTrait;
 use super::{moves::Move, piece::PAWN};
-use crate::engine::board::castling::Castling;
+use crate::engine::board::castling::{CASTLING_WKINGSIDE, CASTLING_WQUEENSIDE, Castling};
 use crate::engine::evaluation::common_eval::CLR_SQ;
+use crate::engine::evaluation::eval_params::{EVAL_PARAMS, SharedEvalParams};
 use crate::engine::evaluation::evaluation::Evaluation;
+use crate::engine::evaluation::nnue::Nnue;
 use crate::engine::misc::bitboard::BitboardTrait;
-use crate::engine::search::pawn_hash_table::PawnHashTable;
-use crate::engine::search::transposition_table::TTTable;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::search::eval_hash_table::{EVAL_TT, SharedEvalTT};
+use crate::engine::search::history::History;
+use crate::engine::search::pawn_hash_table::{PAWN_TT, SharedPawnTT};
+use crate::engine::search::transposition_table::{SharedTT, TT};
+use std::sync::Arc;
+
 use crate::engine::{
     board::fen::FenTrait,
     misc::{bitboard::Bitboard, const_utility::FEN_START},
 };
-const MAX_PLY: usize = 64;
+pub const MAX_PLY: usize = 64;
 
 #[derive(Debug, Clone)]
 pub struct Board {
@@ -20,23 +29,30 @@ pub struct Board {
     pub squares: [Piece; 64],
     pub bitboard: [Bitboard; 14],
     pub p_count: [usize; 14],
+    // Attacks of every piece and of both sides, updated for the squares changed by make_move
+    pub attacks: AttackMaps,
 
     // Position Vectors (Moves until now)
     pub moves: Vec<Move>,
     pub history: Vec<BoardState>,
     pub state: BoardState,
 
-    // TODO: Add This to Move Ordering Structure
-    // pub tt: TTTable,
-    // pub pawn_tt: PawnHashTable,
-    pub s_history: [[isize; 64]; 14],
+    // Hash tables, the engine's tables unless replaced (e.g. by the match runner)
+    pub tt: SharedTT,
+    pub pawn_tt: SharedPawnTT,
+    pub eval_tt: SharedEvalTT,
+    pub s_history: History,
     pub s_killers: [[Option<Move>; 2]; 64],
     pub pv_moves: [[Option<Move>; MAX_PLY]; MAX_PLY],
     pub pv_len: [usize; MAX_PLY],
     pub pv_line: Vec<Move>,
     pub gen_moves: Vec<(Move, isize)>,
 
+    // Evaluation weights, the engine's defaults unless replaced (e.g. by the tuner)
+    pub params: SharedEvalParams,
     pub eval: Evaluation,
+    // Network and accumulators of the NNUE evaluation, off unless a network is set
+    pub nnue: Nnue,
 }
 
 impl Board {
@@ -50,15 +66,17 @@ impl Board {
             squares: [0; 64],
             bitboard: [0 as Bitboard; 14],
             p_count: [0; 14],
+            attacks: AttackMaps::init(),
 
             moves: Vec::with_capacity(1024),
             history: Vec::with_capacity(1024),
             state: BoardState::init(),
 
             // Move Ordering
-            // tt: TTTable::init(),
-            // pawn_tt: PawnHashTable::init(),
-            s_history: [[0isize; 64]; 14],
+            tt: Arc::clone(&TT),
+            pawn_tt: Arc::clone(&PAWN_TT),
+            eval_tt: Arc::clone(&EVAL_TT),
+            s_history: History::init(),
             s_killers: [[None; 2]; 64],
             pv_moves: [[None; 64]; 64],
             pv_len: [0; 64],
@@ -66,7 +84,9 @@ impl Board {
 
             gen_moves: Vec::with_capacity(256),
 
+            params: Arc::clone(&EVAL_PARAMS),
             eval: Evaluation::init(),
+            nnue: Nnue::init(),
         }
     }
 
@@ -74,12 +94,13 @@ impl Board {
     pub fn reset(&mut self) {
         self.squares = [0; 64];
         self.bitboard = [0 as Bitboard; 14];
+        self.attacks = AttackMaps::init();
         self.moves = Vec::with_capacity(1024);
         self.history = Vec::with_capacity(1024);
         self.state = BoardState::init();
         // self.tt.clear();
         // self.pawn_tt.clear();
-        self.s_history = [[0isize; 64]; 14]; // FIXME: Don't  create new, just fill with 0's
+        self.s_history.clear();
         self.s_killers = [[None; 2]; 64]; // FIXME: Don't  create new, just fill with 0's
         self.pv_line.clear();
         self.gen_moves.clear();
@@ -269,30 +290,32 @@ impl Board {
         self.s_killers[self.ply()][idx]
     }
 
-    // FIXME: Is this really needed here ?
-    #[inline(always)]
+    ///
+    /// Flips the colors of the position: the pieces are mirrored vertically and change color,
+    /// and so do the side to move, the castling rights and the en passant square.
+    /// The moves played until now belong to another position and are dropped.
+    ///
     pub fn mirror(&mut self) {
-        for idx in 0..(self.squares.len() / 2) {
-            let first_piece = self.squares[idx];
-            let second_piece = self.squares[CLR_SQ[1][idx]];
-            self.squares[idx] = second_piece;
-            self.squares[CLR_SQ[1][idx]] = first_piece;
+        let squares = self.squares;
+        for (sq, &piece) in squares.iter().enumerate().filter(|(_, piece)| **piece != 0) {
+            self.clear_piece(sq, piece);
         }
 
-        for idx in (0..self.bitboard.len()).step_by(2) {
-            let bb = self.bitboard[idx];
-            self.bitboard[idx] = self.bitboard[idx + 1].swap_bytes();
-            self.bitboard[idx + 1] = bb.swap_bytes();
+        self.state.key = 0;
+        self.state.pk_key = 0;
+        for (sq, &piece) in squares.iter().enumerate().filter(|(_, piece)| **piece != 0) {
+            self.add_piece(CLR_SQ[1][sq], piece ^ 1);
         }
 
+        let castling = self.state.castling;
         self.state.color = self.state.color.opp();
-        self.state.ep = match self.state.ep {
-            Some(sq) => Some(CLR_SQ[1][sq as usize] as u8),
-            None => None,
-        };
+        self.state.castling =
+            (castling & (CASTLING_WKINGSIDE | CASTLING_WQUEENSIDE)) << 2 | castling >> 2;
+        self.state.ep = self.state.ep.map(|sq| CLR_SQ[1][sq as usize] as u8);
+        self.zb_reset_key();
 
-        // self.state.castling = 0; TODO:
-        // self.state.key = self.generate_pos_key(); // TODO: Update Zobrist key Structure
+        self.moves.clear();
+        self.history.clear();
     }
 }
 
@@ -326,12 +349,33 @@ mod tests {
     fn test_mirror_framework(fen: &str) {
         let mut board = Board::read_fen(fen);
         let eval = board.evaluation();
-        // print_chess(&board);
         board.mirror();
-        // print_chess(&board);
-        board.eval.reset();
         let mirror_eval = board.evaluation();
-        // assert_eq!(eval, mirror_eval)
+        assert_eq!(eval, mirror_eval);
+
+        // Mirroring twice gives back the position
+        board.mirror();
+        let original = Board::read_fen(fen);
+        assert_eq!(board.squares, original.squares);
+        assert_eq!(board.bitboard, original.bitboard);
+        assert_eq!(board.p_count, original.p_count);
+        assert_eq!(board.state, original.state);
+        assert_eq!(board.eval.psqt_eval, original.eval.psqt_eval);
+        assert_eq!(board.eval.material_eval, original.eval.material_eval);
+    }
+
+    #[test]
+    fn test_mirror_state() {
+        let mut board =
+            Board::read_fen("rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w Kq c6 0 2");
+        board.mirror();
+        let mirror = Board::read_fen("rnbqkbnr/pppp1ppp/8/4p3/2P5/8/PP1PPPPP/RNBQKBNR b Qk c3 0 2");
+
+        assert_eq!(board.to_fen(), mirror.to_fen());
+        assert_eq!(board.squares, mirror.squares);
+        assert_eq!(board.p_count, mirror.p_count);
+        assert_eq!(board.state, mirror.state);
+        assert_eq!(board.eval.psqt_eval, mirror.eval.psqt_eval);
     }
 
     #[test]
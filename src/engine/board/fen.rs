@@ -5,18 +5,23 @@ use crate::engine::board::castling::*;
 use crate::engine::board::zobrist::ZobristKeysTrait;
 use crate::engine::misc::bit_pos_utility::*;
 use crate::engine::misc::bitboard::BitboardTrait;
+use crate::engine::misc::display::display_moves::sq_notation;
 use crate::engine::move_generator::make_move::BoardMoveTrait;
 
 // TODO: Validate if the fen is correct
 
 pub trait FenTrait {
     fn read_fen(fen: &str) -> Self;
+    fn try_read_fen(fen: &str) -> Option<Self>
+    where
+        Self: Sized;
     fn set_position(&mut self, position: &str);
     fn set_en_passant(&mut self, square: &str);
     fn set_color(&mut self, color: &str);
     fn set_castling(&mut self, castling: &str);
     fn set_half_move_clock(&mut self, half_move: &str);
     fn set_full_move_number(&mut self, full_move: &str);
+    fn to_fen(&self) -> String;
 }
 
 impl FenTrait for Board {
@@ -38,6 +43,50 @@ impl FenTrait for Board {
         board
     }
 
+    ///
+    /// Validates the fen before reading it, the move counters are optional (as in EPD)
+    ///
+    fn try_read_fen(fen: &str) -> Option<Self> {
+        let data: Vec<&str> = fen.split_whitespace().collect();
+        if !(4..=6).contains(&data.len()) {
+            return None;
+        }
+
+        let valid_rank = |rank: &str| {
+            let mut files = 0;
+            for ch in rank.chars() {
+                files += match ch {
+                    '1'..='8' => ch.to_digit(10).unwrap(),
+                    'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => 1,
+                    _ => return false,
+                };
+            }
+            files == 8
+        };
+
+        let ranks: Vec<&str> = data[0].split('/').collect();
+        let position = ranks.len() == 8
+            && ranks.iter().all(|rank| valid_rank(rank))
+            && data[0].matches('K').count() == 1
+            && data[0].matches('k').count() == 1;
+        let castling = data[2] == "-" || data[2].chars().all(|ch| "KQkq".contains(ch));
+        let en_passant = data[3] == "-" || position_to_bit(data[3]).is_ok();
+        let half_move = data.get(4).unwrap_or(&"0");
+        let full_move = data.get(5).unwrap_or(&"1");
+
+        let valid = position
+            && matches!(data[1], "w" | "b")
+            && castling
+            && en_passant
+            && half_move.parse::<u8>().is_ok()
+            && full_move.parse::<u16>().is_ok();
+
+        valid.then(|| {
+            let fen = [data[0], data[1], data[2], data[3], half_move, full_move].join(" ");
+            Board::read_fen(&fen)
+        })
+    }
+
     fn set_position(&mut self, position: &str) {
         let mut idx: usize = 64;
         for row in position.splitn(8, '/') {
@@ -101,13 +150,63 @@ impl FenTrait for Board {
             Err(_) => panic!("Invalid fullmove: {}", full_move),
         }
     }
+
+    fn to_fen(&self) -> String {
+        let mut position = String::with_capacity(64);
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.squares[rank * 8 + file] {
+                    0 => empty += 1,
+                    piece => {
+                        if empty > 0 {
+                            position.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        position.push(piece.to_char());
+                    }
+                }
+            }
+            if empty > 0 {
+                position.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                position.push('/');
+            }
+        }
+
+        let rights = [
+            (CASTLING_WKINGSIDE, 'K'),
+            (CASTLING_WQUEENSIDE, 'Q'),
+            (CASTLING_BKINGSIDE, 'k'),
+            (CASTLING_BQUEENSIDE, 'q'),
+        ];
+        let mut castling: String = rights
+            .iter()
+            .filter(|(right, _)| self.state.castling.is_set(*right))
+            .map(|(_, ch)| ch)
+            .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        format!(
+            "{} {} {} {} {} {}",
+            position,
+            if self.color() == WHITE { "w" } else { "b" },
+            castling,
+            self.ep().map_or("-".to_string(), sq_notation),
+            self.half_move(),
+            self.full_move()
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::engine::board::piece::WHITE_PAWN;
     use crate::engine::board::square::SqPos;
-    use crate::engine::misc::const_utility::{FEN_MIDDLE_GAME, FEN_PAWNS_BLACK};
+    use crate::engine::misc::const_utility::{FEN_MIDDLE_GAME, FEN_PAWNS_BLACK, FEN_START};
 
     use super::*;
 
@@ -147,6 +246,28 @@ mod tests {
         board.bitboard[WHITE_PAWN.idx()].print(None);
     }
 
+    #[test]
+    fn test_try_read_fen() {
+        let board = Board::try_read_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+        assert_eq!(board.state.color, BLACK);
+        assert_eq!(board.state.full_move, 1);
+
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/4K3 b - - 0").is_some());
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1").is_none());
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/4K2 w - - 0 1").is_none());
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/8 w - - 0 1").is_none());
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/4K3 w X - 0 1").is_none());
+        assert!(Board::try_read_fen("4k3/8/8/8/8/8/8/4K3 w - z9 0 1").is_none());
+    }
+
+    #[test]
+    fn test_to_fen() {
+        for fen in [FEN_START, FEN_MIDDLE_GAME, FEN_PAWNS_BLACK, "4k3/8/8/8/8/8/8/4K2R w K - 3 40"]
+        {
+            assert_eq!(Board::read_fen(fen).to_fen(), fen);
+        }
+    }
+
     #[test]
     fn test_occupancy_start_position() {
         let board = Board::initialize();
@@ -1,9 +1,12 @@
+pub mod attack_map;
 pub mod board;
 pub mod castling;
 pub mod color;
 pub mod fen;
 pub mod moves;
+pub mod packed;
 pub mod piece;
+pub mod san;
 pub mod square;
 pub mod state;
 pub mod zobrist;
@@ -0,0 +1,162 @@
+use super::board::Board;
+use super::castling::CASTLING_ALL;
+use super::color::{BLACK, WHITE};
+use super::moves::Move;
+use super::piece::{PAWN, Piece, PieceTrait, QUEEN};
+use super::san::SanTrait;
+use super::zobrist::ZobristKeysTrait;
+use crate::engine::misc::bitboard::{BitboardTrait, Iterator};
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+
+// NOTE: Packed board (32 bytes)
+//
+//  0..8   occupancy bitboard (little endian)
+//  8..24  a nibble per piece in the order of the occupied squares, the low nibble first
+//         (the piece as in the board, e.g. 2 = white pawn, 13 = black queen)
+//  24     side to move (bit 0) and the castling rights (bits 1-4)
+//  25     en passant square, 64 if none
+//  26     half move clock
+//  27..29 full move number (little endian)
+//  29..32 reserved (zero)
+
+pub const PACKED_SIZE: usize = 32;
+const NO_EP: u8 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedBoard(pub [u8; PACKED_SIZE]);
+
+impl PackedBoard {
+    pub fn encode(board: &Board) -> Self {
+        let mut bytes = [0; PACKED_SIZE];
+        let occupancy = board.occ_bb(WHITE) | board.occ_bb(BLACK);
+        debug_assert!(occupancy.count() <= 32, "More than 32 pieces on the board");
+        bytes[0..8].copy_from_slice(&occupancy.to_le_bytes());
+
+        let mut bb = occupancy;
+        let mut idx = 0;
+        while let Some(sq) = bb.next() {
+            bytes[8 + idx / 2] |= board.squares[sq] << (4 * (idx % 2));
+            idx += 1;
+        }
+
+        bytes[24] = board.color() | board.castling() << 1;
+        bytes[25] = board.ep().unwrap_or(NO_EP);
+        bytes[26] = board.half_move();
+        bytes[27..29].copy_from_slice(&board.full_move().to_le_bytes());
+        Self(bytes)
+    }
+
+    ///
+    /// The board of the packed position, the same as read from the FEN of the position
+    ///
+    pub fn decode(&self) -> Result<Board, String> {
+        let bytes = &self.0;
+        let occupancy = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        if occupancy.count() > 32 {
+            return Err("More than 32 pieces".to_string());
+        }
+
+        let mut board = Board::create();
+        let mut bb = occupancy;
+        let mut idx = 0;
+        while let Some(sq) = bb.next() {
+            let piece: Piece = (bytes[8 + idx / 2] >> (4 * (idx % 2))) & 0xF;
+            if !(PAWN..=QUEEN + BLACK).contains(&piece) {
+                return Err(format!("Invalid piece {} on square {}", piece, sq));
+            }
+            board.add_piece(sq, piece);
+            idx += 1;
+        }
+        if board.king_count(WHITE) != 1 || board.king_count(BLACK) != 1 {
+            return Err("Every side needs exactly one king".to_string());
+        }
+
+        let castling = bytes[24] >> 1;
+        let ep = bytes[25];
+        if bytes[24] >> 5 != 0 || castling > CASTLING_ALL || (ep > NO_EP) {
+            return Err("Invalid state".to_string());
+        }
+
+        board.state.color = bytes[24] & 1;
+        board.state.castling = castling;
+        board.state.ep = (ep != NO_EP).then_some(ep);
+        board.state.half_move = bytes[26];
+        board.state.full_move = u16::from_le_bytes([bytes[27], bytes[28]]);
+        board.zb_reset_key();
+        Ok(board)
+    }
+}
+
+///
+/// Packs the move into 16 bits: from (bits 0-5), to (bits 6-11) and the kind of the
+/// promotion piece (bits 12-15), 0 for a null move
+///
+pub fn pack_move(mv: &Move) -> u16 {
+    let promo = mv.flag.get_promo_piece().map_or(0, |piece| piece.kind() as u16);
+    mv.from as u16 | (mv.to as u16) << 6 | promo << 12
+}
+
+///
+/// The legal move of the board that was packed, if there is one
+///
+pub fn unpack_move(packed: u16, board: &mut Board) -> Option<Move> {
+    let (from, to, promo) = (packed & 63, (packed >> 6) & 63, (packed >> 12) as Piece);
+    if packed == 0 {
+        return None;
+    }
+    board.legal_moves().into_iter().find(|mv| {
+        let kind = mv.flag.get_promo_piece().map_or(0, |piece| piece.kind());
+        mv.from as u16 == from && mv.to as u16 == to && kind == promo
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::board::piece::{KNIGHT, ROOK};
+    use crate::engine::misc::const_utility::{FEN_POS_FIVE, FEN_POS_THREE, FEN_START};
+
+    #[test]
+    fn test_packed_board() {
+        let fens = [
+            FEN_START,
+            FEN_POS_THREE,
+            FEN_POS_FIVE,
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w Kq c6 0 2",
+            "8/8/8/8/8/5k2/8/6K1 b - - 99 300",
+        ];
+        for fen in fens {
+            let board = Board::read_fen(fen);
+            let packed = PackedBoard::encode(&board);
+            let decoded = packed.decode().unwrap();
+
+            assert_eq!(decoded.to_fen(), fen);
+            assert_eq!(decoded.squares, board.squares);
+            assert_eq!(decoded.bitboard, board.bitboard);
+            assert_eq!(decoded.state, board.state);
+            assert_eq!(decoded.eval.psqt_eval, board.eval.psqt_eval);
+        }
+
+        let mut packed = PackedBoard::encode(&Board::initialize());
+        packed.0[8] = 0x0F;
+        assert!(packed.decode().is_err());
+        assert!(PackedBoard([0; PACKED_SIZE]).decode().is_err());
+    }
+
+    #[test]
+    fn test_packed_move() {
+        let mut board = Board::read_fen("r3k3/1P6/8/8/8/8/8/4K2R w K - 0 1");
+        for mv in board.legal_moves() {
+            let packed = pack_move(&mv);
+            assert_eq!(unpack_move(packed, &mut board), Some(mv));
+        }
+
+        // The promotion piece is part of the move
+        let mv = board.parse_san("bxa8=N").unwrap();
+        let rook = unpack_move(pack_move(&mv) ^ ((KNIGHT ^ ROOK) as u16) << 12, &mut board);
+        assert_eq!(rook.unwrap().flag.get_promo_piece(), Some(ROOK));
+        assert_eq!(unpack_move(pack_move(&mv) ^ 1, &mut board), None);
+        assert_eq!(unpack_move(0, &mut board), None);
+    }
+}
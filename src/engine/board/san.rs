@@ -0,0 +1,267 @@
+use super::board::Board;
+use super::moves::{Flag, Move};
+use super::piece::*;
+use crate::engine::misc::display::display_moves::sq_notation;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+pub trait SanTrait {
+    fn move_to_san(&mut self, mv: &Move) -> String;
+    fn parse_san(&mut self, san: &str) -> Option<Move>;
+    fn line_to_san(&self, moves: &[Move]) -> Vec<String>;
+    fn legal_moves(&mut self) -> Vec<Move>;
+}
+
+impl SanTrait for Board {
+    ///
+    /// Standard algebraic notation of the legal move (e.g. "Nbd7", "exd5", "e8=Q+", "O-O")
+    ///
+    fn move_to_san(&mut self, mv: &Move) -> String {
+        let mut san = match mv.flag {
+            Flag::KingCastle => "O-O".to_string(),
+            Flag::QueenCastle => "O-O-O".to_string(),
+            _ => {
+                let mut san = String::new();
+                let from = sq_notation(mv.from);
+                if mv.piece.kind() != PAWN {
+                    san.push(mv.piece.kind().to_char());
+                    san.push_str(&self.disambiguation(mv, &from));
+                } else if mv.flag.is_capture() {
+                    san.push_str(&from[..1]);
+                }
+
+                if mv.flag.is_capture() {
+                    san.push('x');
+                }
+                san.push_str(&sq_notation(mv.to));
+                if let Some(promo) = mv.flag.get_promo_piece() {
+                    san.push('=');
+                    san.push(promo.kind().to_char());
+                }
+                san
+            }
+        };
+
+        if self.make_move(mv) {
+            if in_check(self) {
+                san.push(if self.legal_moves().is_empty() { '#' } else { '+' });
+            }
+            self.undo_move();
+        }
+        san
+    }
+
+    ///
+    /// Finds the legal move of the SAN, sloppy notation is accepted as well: missing "x",
+    /// "0-0", lowercase pieces ("nf3"), promotions without "=" ("e8q") and annotations ("!?")
+    ///
+    fn parse_san(&mut self, san: &str) -> Option<Move> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let legal = self.legal_moves();
+
+        let castle = match san.to_ascii_uppercase().replace('0', "O").as_str() {
+            "O-O" => Some(Flag::KingCastle),
+            "O-O-O" => Some(Flag::QueenCastle),
+            _ => None,
+        };
+        if let Some(flag) = castle {
+            return legal.into_iter().find(|mv| mv.flag == flag);
+        }
+
+        let mut chars: Vec<char> =
+            san.chars().filter(|ch| !matches!(ch, 'x' | 'X' | ':' | '-' | '=')).collect();
+
+        // Promotion piece after the destination square
+        let mut promo = None;
+        if chars.len() > 2 && chars[chars.len() - 2].is_ascii_digit() {
+            let last = chars[chars.len() - 1];
+            if last.is_ascii_alphabetic() {
+                promo = Some(piece_kind(last.to_ascii_uppercase()).filter(|&kind| kind != KING)?);
+                chars.pop();
+            }
+        }
+
+        let find = |kind: Piece, chars: &[char]| -> Vec<Move> {
+            legal.iter().filter(|mv| san_matches(mv, kind, chars, promo)).copied().collect()
+        };
+
+        let candidates = match chars.first() {
+            Some('P') => find(PAWN, &chars[1..]),
+            Some(&ch) if ch.is_ascii_uppercase() => find(piece_kind(ch)?, &chars[1..]),
+            // Lowercase pieces are tried after the pawn moves ("bc4" can be a bishop move)
+            Some(&ch) => {
+                let pawn_moves = find(PAWN, &chars);
+                match piece_kind(ch.to_ascii_uppercase()) {
+                    Some(kind) if pawn_moves.is_empty() => find(kind, &chars[1..]),
+                    _ => pawn_moves,
+                }
+            }
+            None => Vec::new(),
+        };
+
+        match candidates.as_slice() {
+            [mv] => Some(*mv),
+            _ => None,
+        }
+    }
+
+    ///
+    /// SAN of the line played from the current position, it stops at the first illegal move
+    ///
+    fn line_to_san(&self, moves: &[Move]) -> Vec<String> {
+        let mut board = self.clone();
+        let mut line = Vec::with_capacity(moves.len());
+        for mv in moves {
+            if !board.legal_moves().contains(mv) {
+                break;
+            }
+
+            line.push(board.move_to_san(mv));
+            board.play_root_move(mv);
+        }
+        line
+    }
+
+    fn legal_moves(&mut self) -> Vec<Move> {
+        let moves = self.gen_moves();
+        let legal = |mv: &Move| {
+            let legal = self.make_move(mv);
+            if legal {
+                self.undo_move();
+            }
+            legal
+        };
+        moves.into_iter().map(|(mv, _)| mv).filter(legal).collect()
+    }
+}
+
+impl Board {
+    // File, rank or the whole square of the origin, if other pieces can go to the same square
+    fn disambiguation(&mut self, mv: &Move, from: &str) -> String {
+        let others: Vec<Move> = self
+            .legal_moves()
+            .into_iter()
+            .filter(|other| other.piece == mv.piece && other.to == mv.to && other.from != mv.from)
+            .collect();
+        if others.is_empty() {
+            return String::new();
+        }
+
+        let same_file = others.iter().any(|other| other.from % 8 == mv.from % 8);
+        let same_rank = others.iter().any(|other| other.from / 8 == mv.from / 8);
+        match (same_file, same_rank) {
+            (false, _) => from[..1].to_string(),
+            (true, false) => from[1..].to_string(),
+            (true, true) => from.to_string(),
+        }
+    }
+}
+
+// The chars are the optional origin file and rank, followed by the destination square
+fn san_matches(mv: &Move, kind: Piece, chars: &[char], promo: Option<Piece>) -> bool {
+    if chars.len() < 2 || mv.piece.kind() != kind {
+        return false;
+    }
+
+    let (to_file, to_rank) = (chars[chars.len() - 2], chars[chars.len() - 1]);
+    let to_matches = to_file as u8 == b'a' + mv.to % 8 && to_rank as u8 == b'1' + mv.to / 8;
+    let from_matches = chars[..chars.len() - 2].iter().all(|&ch| match ch {
+        'a'..='h' => ch as u8 == b'a' + mv.from % 8,
+        '1'..='8' => ch as u8 == b'1' + mv.from / 8,
+        _ => false,
+    });
+
+    // Promotions without a piece are taken as queen promotions
+    let promo_matches = match mv.flag.get_promo_piece() {
+        Some(piece) => piece.kind() == promo.unwrap_or(QUEEN),
+        None => promo.is_none(),
+    };
+    to_matches && from_matches && promo_matches
+}
+
+fn in_check(board: &Board) -> bool {
+    board.sq_attack(board.king_sq(board.color()), board.color()) != 0
+}
+
+fn piece_kind(ch: char) -> Option<Piece> {
+    match ch {
+        'N' => Some(KNIGHT),
+        'B' => Some(BISHOP),
+        'R' => Some(ROOK),
+        'Q' => Some(QUEEN),
+        'K' => Some(KING),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::misc::const_utility::FEN_START;
+
+    fn san(fen: &str, sloppy: &str) -> String {
+        let mut board = Board::read_fen(fen);
+        let mv = board.parse_san(sloppy).unwrap_or_else(|| panic!("Can't parse {}", sloppy));
+        board.move_to_san(&mv)
+    }
+
+    #[test]
+    fn test_move_to_san() {
+        let fen = "r3k2r/1P6/8/8/8/2N3N1/8/R3K2R w KQkq - 0 1";
+        assert_eq!(san(fen, "O-O"), "O-O");
+        assert_eq!(san(fen, "O-O-O"), "O-O-O");
+        assert_eq!(san(fen, "Nge4"), "Nge4");
+        assert_eq!(san(fen, "bxa8=Q"), "bxa8=Q+");
+        assert_eq!(san(fen, "b8=N"), "b8=N");
+
+        let fen = "4k3/8/8/8/8/R6R/8/R3K3 w - - 0 1";
+        assert_eq!(san(fen, "Ra3b3"), "Rab3");
+        assert_eq!(san(fen, "R1a2"), "R1a2");
+        assert_eq!(san("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "Qa1b2"), "Qa1b2");
+
+        // Pinned knight doesn't need to be disambiguated
+        assert_eq!(san("4k3/4r3/8/8/8/8/2N1N3/4K3 w - - 0 1", "Nd4"), "Nd4");
+        assert_eq!(san("7k/Q7/6K1/8/8/8/8/8 w - - 0 1", "Qg7"), "Qg7#");
+        assert_eq!(
+            san("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPP2PPP/RNBQKBNR b KQkq e3 0 3", "dxe3"),
+            "dxe3"
+        );
+    }
+
+    #[test]
+    fn test_parse_sloppy_san() {
+        let fen = "r3k2r/1P6/8/8/8/2N3N1/8/R3K2R w KQkq - 0 1";
+        assert_eq!(san(fen, "0-0"), "O-O");
+        assert_eq!(san(fen, "0-0-0!?"), "O-O-O");
+        assert_eq!(san(fen, "nge4"), "Nge4");
+        assert_eq!(san(fen, "Ngxe4"), "Nge4");
+        assert_eq!(san(fen, "ba8q"), "bxa8=Q+");
+        assert_eq!(san(fen, "b8"), "b8=Q+");
+        assert_eq!(san(FEN_START, "Pe4!!"), "e4");
+        assert_eq!(san(FEN_START, "nf3"), "Nf3");
+        assert_eq!(san("4k3/8/8/8/8/2p5/1P1B4/4K3 w - - 0 1", "bc3"), "bxc3");
+        assert_eq!(san("4k3/8/8/8/8/8/1P1B4/4K3 w - - 0 1", "bc3"), "Bc3");
+
+        let mut board = Board::read_fen(fen);
+        assert_eq!(board.parse_san("Ne4"), None); // Ambiguous
+        assert_eq!(board.parse_san("Nd5xe7"), None);
+        assert_eq!(board.parse_san("Zz9"), None);
+    }
+
+    #[test]
+    fn test_line_to_san() {
+        let mut board = Board::read_fen(FEN_START);
+        let mut moves = Vec::new();
+        let mut line = board.clone();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+            let mv = line.parse_san(san).unwrap();
+            line.make_move(&mv);
+            moves.push(mv);
+        }
+
+        assert_eq!(board.line_to_san(&moves), vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(board.line_to_san(&moves[1..]), Vec::<String>::new());
+        assert_eq!(board.legal_moves().len(), 20);
+    }
+}
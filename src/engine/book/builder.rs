@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use super::native::{BookEntry, MoveStats, NativeBook};
+use super::polyglot::{from_move, polyglot_key};
+use crate::engine::board::color::ColorTrait;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::protocols::pgn::{PgnGame, PgnReader};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BuilderOptions {
+    // Moves played in fewer games are left out of the book
+    pub min_games: u32,
+    // Only the first plies of every game are added
+    pub max_ply: usize,
+    // Games where either player is rated lower (or unrated) are skipped
+    pub min_rating: u32,
+}
+
+impl BuilderOptions {
+    pub fn init() -> Self {
+        Self { min_games: 3, max_ply: 30, min_rating: 0 }
+    }
+}
+
+///
+/// Collects the move statistics of every position from a game collection
+///
+pub struct BookBuilder {
+    pub options: BuilderOptions,
+    pub games: usize,
+    pub skipped: usize,
+    stats: HashMap<(u64, u16), MoveStats>,
+}
+
+impl BookBuilder {
+    pub fn init(options: BuilderOptions) -> Self {
+        Self { options, games: 0, skipped: 0, stats: HashMap::new() }
+    }
+
+    ///
+    /// Streams the games from the PGN, the games that can't be read are skipped
+    ///
+    pub fn add_pgn<R: BufRead>(&mut self, reader: R) {
+        for game in PgnReader::init(reader) {
+            match game {
+                Ok(game) => self.add_game(&game),
+                Err(err) => {
+                    eprintln!("[Book Builder]: Skipped game: {}", err);
+                    self.skipped += 1;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Adds the main line of the game, variations are ignored
+    ///
+    pub fn add_game(&mut self, game: &PgnGame) {
+        let rating = |tag: &str| game.tag(tag).and_then(|r| r.parse::<u32>().ok()).unwrap_or(0);
+
+        // Result from white's point of view: 2 = win, 1 = draw, 0 = loss
+        let white_result = match game.result.as_str() {
+            "1-0" => 2,
+            "1/2-1/2" => 1,
+            "0-1" => 0,
+            _ => {
+                self.skipped += 1;
+                return;
+            }
+        };
+
+        let min_rating = rating("WhiteElo").min(rating("BlackElo"));
+        if self.options.min_rating > 0 && min_rating < self.options.min_rating {
+            self.skipped += 1;
+            return;
+        }
+
+        let Ok(mut board) = game.start_board() else {
+            self.skipped += 1;
+            return;
+        };
+
+        for pgn_move in game.moves.iter().take(self.options.max_ply) {
+            let key = (polyglot_key(&board), from_move(&pgn_move.mv));
+            let stats = self.stats.entry(key).or_default();
+            match (white_result, board.color().is_white()) {
+                (1, _) => stats.draws += 1,
+                (2, true) | (0, false) => stats.wins += 1,
+                _ => stats.losses += 1,
+            }
+
+            board.play_root_move(&pgn_move.mv);
+        }
+        self.games += 1;
+    }
+
+    ///
+    /// Statistics of the moves that were played in at least the minimum number of games
+    ///
+    pub fn build(&self) -> NativeBook {
+        let entries = self.stats.iter().filter(|(_, s)| s.games() >= self.options.min_games);
+        let entries = entries.map(|(&(key, mv), &stats)| BookEntry { key, mv, stats });
+        NativeBook::from_entries(entries.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::native::NATIVE_BOOK_EXTENSION;
+    use super::*;
+    use crate::engine::board::board::Board;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::board::san::SanTrait;
+    use crate::engine::misc::const_utility::FEN_START;
+
+    const PGN: &str = r#"
+[Event "Test"]
+[White "A"]
+[Black "B"]
+[WhiteElo "2500"]
+[BlackElo "2400"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4) Nc6 $1 3. Bb5 a6 1-0
+
+[Event "Test"]
+[WhiteElo "2500"]
+[BlackElo "2000"]
+[Result "1/2-1/2"]
+
+1. e4 e5 2. Nf3 Nf6 1/2-1/2
+
+[Event "Test"]
+[Result "0-1"]
+
+1.d4 d5 2.c4 dxc4 0-1
+"#;
+
+    fn build(options: BuilderOptions) -> NativeBook {
+        let mut builder = BookBuilder::init(options);
+        builder.add_pgn(PGN.as_bytes());
+        assert_eq!(builder.games + builder.skipped, 3);
+        builder.build()
+    }
+
+    #[test]
+    fn test_build_stats() {
+        let book = build(BuilderOptions { min_games: 1, max_ply: 100, min_rating: 0 });
+        let mut board = Board::read_fen(FEN_START);
+        let moves = book.query(&mut board);
+
+        assert_eq!(moves.len(), 2);
+        let (e4, stats) = moves[0];
+        assert_eq!(e4, board.parse_san("e4").unwrap());
+        assert_eq!(stats, MoveStats { wins: 1, draws: 1, losses: 0 });
+        assert_eq!(moves[1].1, MoveStats { wins: 0, draws: 0, losses: 1 });
+
+        // The variation 2. f4 is not part of the book
+        for san in ["e4", "e5"] {
+            let mv = board.parse_san(san).unwrap();
+            board.make_move(&mv);
+        }
+        let moves = book.query(&mut board);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].1.games(), 2);
+    }
+
+    #[test]
+    fn test_build_filters() {
+        let book = build(BuilderOptions { min_games: 2, max_ply: 100, min_rating: 0 });
+        assert_eq!(book.len(), 3); // e4, e5, Nf3
+
+        let book = build(BuilderOptions { min_games: 1, max_ply: 2, min_rating: 2200 });
+        assert_eq!(book.len(), 2); // e4 e5 of the first game
+
+        let polyglot = book.to_polyglot();
+        let mut board = Board::read_fen(FEN_START);
+        assert_eq!(polyglot.moves(&mut board).len(), 1);
+    }
+
+    #[test]
+    fn test_native_book_file() {
+        let book = build(BuilderOptions { min_games: 1, max_ply: 100, min_rating: 0 });
+        let path = std::env::temp_dir().join(format!("book_test.{}", NATIVE_BOOK_EXTENSION));
+        book.write(&path).unwrap();
+
+        let read = NativeBook::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.len(), book.len());
+
+        let mut board = Board::read_fen(FEN_START);
+        assert_eq!(read.query(&mut board), book.query(&mut board));
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::polyglot::{PolyglotBook, PolyglotEntry, polyglot_key, to_move};
+use crate::engine::board::board::Board;
+use crate::engine::board::moves::Move;
+
+// NOTE: Native opening books (.fbk), built from game collections with book/builder.rs
+//
+// File Format, all numbers are little endian:
+//   [0..4]  Magic "FBOK"
+//   [4]     Version
+//   [5..9]  Number of entries
+//   [..]    Entries of 22 bytes: Polyglot key (u64), Polyglot move (u16), wins, draws, losses (u32)
+//
+// Wins, draws and losses are counted from the point of view of the side that played the move
+
+pub const NATIVE_BOOK_EXTENSION: &str = "fbk";
+
+const BOOK_MAGIC: [u8; 4] = *b"FBOK";
+const BOOK_VERSION: u8 = 1;
+const ENTRY_SIZE: usize = 22;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MoveStats {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MoveStats {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    ///
+    /// Expected score of the move in [0, 1] for the side that plays it
+    ///
+    pub fn score(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            games => (self.wins as f64 + self.draws as f64 / 2.0) / games as f64,
+        }
+    }
+
+    // Same weighting as the Polyglot book maker
+    fn weight(&self) -> u64 {
+        2 * self.wins as u64 + self.draws as u64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub mv: u16,
+    pub stats: MoveStats,
+}
+
+#[derive(Debug, Default)]
+pub struct NativeBook {
+    entries: Vec<BookEntry>,
+}
+
+impl NativeBook {
+    pub fn init() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub fn from_entries(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by_key(|e| (e.key, std::cmp::Reverse(e.stats.games()), e.mv));
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    ///
+    /// All the entries stored for the key, the most played moves first
+    ///
+    pub fn entries(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|e| e.key < key);
+        let end = self.entries.partition_point(|e| e.key <= key);
+        &self.entries[start..end]
+    }
+
+    ///
+    /// Statistics of every book move in the position, the most played moves first
+    ///
+    pub fn query(&self, board: &mut Board) -> Vec<(Move, MoveStats)> {
+        let entries = self.entries(polyglot_key(board));
+        entries.iter().filter_map(|e| Some((to_move(board, e.mv)?, e.stats))).collect()
+    }
+
+    ///
+    /// Converts the statistics into Polyglot weights (2 * wins + draws), moves that
+    /// never scored are left out
+    ///
+    pub fn to_polyglot(&self) -> PolyglotBook {
+        let max = self.entries.iter().map(|e| e.stats.weight()).max().unwrap_or(0);
+        let scale = max.div_ceil(u16::MAX as u64).max(1);
+
+        let entries = self.entries.iter().filter_map(|e| {
+            let weight = (e.stats.weight() / scale) as u16;
+            (weight > 0).then_some(PolyglotEntry { key: e.key, mv: e.mv, weight, learn: 0 })
+        });
+        PolyglotBook::from_entries(entries.collect())
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(9 + self.entries.len() * ENTRY_SIZE);
+        bytes.extend_from_slice(&BOOK_MAGIC);
+        bytes.push(BOOK_VERSION);
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.key.to_le_bytes());
+            bytes.extend_from_slice(&entry.mv.to_le_bytes());
+            bytes.extend_from_slice(&entry.stats.wins.to_le_bytes());
+            bytes.extend_from_slice(&entry.stats.draws.to_le_bytes());
+            bytes.extend_from_slice(&entry.stats.losses.to_le_bytes());
+        }
+        fs::write(path, bytes)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+        let bytes = fs::read(path)?;
+        if bytes.len() < 9 || bytes[0..4] != BOOK_MAGIC || bytes[4] != BOOK_VERSION {
+            return Err(invalid("Not a native book file"));
+        }
+
+        let count = u32::from_le_bytes(bytes[5..9].try_into().unwrap()) as usize;
+        let data = &bytes[9..];
+        if data.len() != count * ENTRY_SIZE {
+            return Err(invalid("Truncated book file"));
+        }
+
+        let u32_at =
+            |chunk: &[u8], at: usize| u32::from_le_bytes(chunk[at..at + 4].try_into().unwrap());
+        let entries = data.chunks_exact(ENTRY_SIZE).map(|chunk| BookEntry {
+            key: u64::from_le_bytes(chunk[0..8].try_into().unwrap()),
+            mv: u16::from_le_bytes(chunk[8..10].try_into().unwrap()),
+            stats: MoveStats {
+                wins: u32_at(chunk, 10),
+                draws: u32_at(chunk, 14),
+                losses: u32_at(chunk, 18),
+            },
+        });
+        Ok(Self::from_entries(entries.collect()))
+    }
+}
@@ -0,0 +1,300 @@
+use once_cell::sync::Lazy;
+use rand::Rng;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::color::ColorTrait;
+use crate::engine::board::moves::{Flag, Move};
+use crate::engine::board::piece::*;
+use crate::engine::generated::polyglot_keys::POLYGLOT_RANDOM;
+use crate::engine::misc::bitboard::BitboardTrait;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+// NOTE: Polyglot opening books (.bin)
+//
+// The file is a sorted list of 16 byte entries, all numbers are big endian:
+//   [0..8]   Polyglot key of the position
+//   [8..10]  Move: to file (3 bits), to row (3), from file (3), from row (3), promotion (3)
+//   [10..12] Weight
+//   [12..16] Learn
+//
+// Castling moves are stored as king takes own rook (e1h1, e1a1, e8h8, e8a8)
+
+pub static BOOK: Lazy<RwLock<PolyglotBook>> = Lazy::new(|| RwLock::new(PolyglotBook::init()));
+
+pub const MAX_BOOK_VARIETY: usize = 100;
+
+const ENTRY_SIZE: usize = 16;
+
+// Offsets of the castling, en passant and side to move keys in POLYGLOT_RANDOM
+const RANDOM_CASTLE: usize = 768;
+const RANDOM_EP: usize = 772;
+const RANDOM_TURN: usize = 780;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolyglotEntry {
+    pub key: u64,
+    pub mv: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl PolyglotEntry {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            mv: u16::from_be_bytes(bytes[8..10].try_into().unwrap()),
+            weight: u16::from_be_bytes(bytes[10..12].try_into().unwrap()),
+            learn: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0; ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.mv.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PolyglotBook {
+    entries: Vec<PolyglotEntry>,
+}
+
+impl PolyglotBook {
+    pub fn init() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub fn from_entries(mut entries: Vec<PolyglotEntry>) -> Self {
+        entries.sort_by_key(|e| (e.key, std::cmp::Reverse(e.weight), e.mv));
+        Self { entries }
+    }
+
+    ///
+    /// Loads the book (an empty path unloads it), returns the number of entries
+    ///
+    pub fn load(&mut self, path: &str) -> io::Result<usize> {
+        self.entries.clear();
+        if path.trim().is_empty() {
+            return Ok(0);
+        }
+
+        let bytes = fs::read(path.trim())?;
+        let entries = bytes.chunks_exact(ENTRY_SIZE).map(PolyglotEntry::from_bytes).collect();
+        *self = Self::from_entries(entries);
+        Ok(self.entries.len())
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let bytes: Vec<u8> = self.entries.iter().flat_map(|e| e.to_bytes()).collect();
+        fs::write(path, bytes)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    ///
+    /// All the entries stored for the key
+    ///
+    pub fn entries(&self, key: u64) -> &[PolyglotEntry] {
+        let start = self.entries.partition_point(|e| e.key < key);
+        let end = self.entries.partition_point(|e| e.key <= key);
+        &self.entries[start..end]
+    }
+
+    ///
+    /// Legal book moves of the position with their weights
+    ///
+    pub fn moves(&self, board: &mut Board) -> Vec<(Move, u16)> {
+        let entries = self.entries(polyglot_key(board));
+        entries.iter().filter_map(|e| Some((to_move(board, e.mv)?, e.weight))).collect()
+    }
+
+    ///
+    /// Picks a book move: with variety 0 the highest weight is always played, with the
+    /// maximum variety the moves are picked randomly in proportion to their weights
+    ///
+    pub fn probe(&self, board: &mut Board, variety: usize) -> Option<Move> {
+        let moves = self.moves(board);
+        if variety == 0 {
+            return moves.iter().max_by_key(|(_, weight)| *weight).map(|(mv, _)| *mv);
+        }
+
+        // Lower variety sharpens the distribution towards the heavier moves
+        let exponent = MAX_BOOK_VARIETY as f64 / variety.min(MAX_BOOK_VARIETY) as f64;
+        let weights: Vec<f64> = moves.iter().map(|(_, w)| (*w as f64).powf(exponent)).collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return moves.first().map(|(mv, _)| *mv);
+        }
+
+        let mut pick = rand::rng().random_range(0.0..total);
+        for ((mv, _), weight) in moves.iter().zip(weights) {
+            if pick < weight {
+                return Some(*mv);
+            }
+            pick -= weight;
+        }
+        moves.last().map(|(mv, _)| *mv)
+    }
+}
+
+///
+/// The standard Polyglot key, it uses different random numbers than the engine's own key
+///
+pub fn polyglot_key(board: &Board) -> u64 {
+    let mut key = 0;
+    for piece in CLR_PIECES {
+        let mut bb = board.bb(piece);
+        while bb != 0 {
+            let sq = bb.pop_lsb();
+            key ^= POLYGLOT_RANDOM[64 * piece_kind(piece) + sq];
+        }
+    }
+
+    for bit in 0..4 {
+        if board.castling() & (1 << bit) != 0 {
+            key ^= POLYGLOT_RANDOM[RANDOM_CASTLE + bit];
+        }
+    }
+
+    // En passant is hashed only when a pawn of the side to move stands next to the pushed pawn
+    if let Some(ep) = board.ep() {
+        let ep = ep as usize;
+        let behind = if board.color().is_white() { ep - 8 } else { ep + 8 };
+        let file = ep % 8;
+        let pawns = board.pawn_bb(board.color());
+        let left = file > 0 && pawns.is_set(behind - 1);
+        let right = file < 7 && pawns.is_set(behind + 1);
+        if left || right {
+            key ^= POLYGLOT_RANDOM[RANDOM_EP + file];
+        }
+    }
+
+    if board.color().is_white() {
+        key ^= POLYGLOT_RANDOM[RANDOM_TURN];
+    }
+    key
+}
+
+///
+/// Converts the internal move into the Polyglot move encoding
+///
+pub fn from_move(mv: &Move) -> u16 {
+    let to = match mv.flag {
+        Flag::KingCastle => mv.from + 3,
+        Flag::QueenCastle => mv.from - 4,
+        _ => mv.to,
+    };
+
+    let promo = match mv.flag.get_promo_piece().map(|p| p.kind()) {
+        Some(KNIGHT) => 1,
+        Some(BISHOP) => 2,
+        Some(ROOK) => 3,
+        Some(QUEEN) => 4,
+        _ => 0,
+    };
+
+    (promo << 12) | ((mv.from as u16) << 6) | to as u16
+}
+
+///
+/// Finds the legal move that matches the Polyglot move encoding
+///
+pub fn to_move(board: &mut Board, raw: u16) -> Option<Move> {
+    let moves = board.gen_moves();
+    let (mv, _) = moves.into_iter().find(|(mv, _)| from_move(mv) == raw)?;
+
+    let legal = board.make_move(&mv);
+    if legal {
+        board.undo_move();
+    }
+    legal.then_some(mv)
+}
+
+// Polyglot piece index: black pawn = 0, white pawn = 1, black knight = 2, ..., white king = 11
+fn piece_kind(piece: Piece) -> usize {
+    let role = match piece.kind() {
+        PAWN => 0,
+        KNIGHT => 1,
+        BISHOP => 2,
+        ROOK => 3,
+        QUEEN => 4,
+        _ => 5,
+    };
+    2 * role + piece.color().is_white() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::misc::const_utility::FEN_START;
+    use crate::engine::misc::display::display_moves::from_move_notation;
+
+    use super::*;
+
+    // Reference keys from the Polyglot specification
+    #[test]
+    fn test_polyglot_key() {
+        let mut board = Board::read_fen(FEN_START);
+        assert_eq!(polyglot_key(&board), 0x463b96181691fc9c);
+
+        let keys = [
+            ("e2e4", 0x823c9b50fd114196),
+            ("d7d5", 0x0756b94461c50fb0),
+            ("e4e5", 0x662fafb965db29d4),
+            ("f7f5", 0x22a48b5a8e47ff78),
+            ("e1e2", 0x652a607ca3f242c1),
+            ("e8f7", 0x00fdd303c946bdd9),
+        ];
+        for (notation, key) in keys {
+            let mv = from_move_notation(notation, &mut board);
+            board.make_move(&mv);
+            assert_eq!(polyglot_key(&board), key, "After {}", notation);
+        }
+
+        let board =
+            Board::read_fen("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3");
+        assert_eq!(polyglot_key(&board), 0x3c8123ea7b067637);
+    }
+
+    #[test]
+    fn test_book_probe() {
+        let mut board = Board::read_fen(FEN_START);
+        let e2e4 = from_move_notation("e2e4", &mut board);
+        let d2d4 = from_move_notation("d2d4", &mut board);
+
+        let key = polyglot_key(&board);
+        let book = PolyglotBook::from_entries(vec![
+            PolyglotEntry { key, mv: from_move(&d2d4), weight: 10, learn: 0 },
+            PolyglotEntry { key, mv: from_move(&e2e4), weight: 20, learn: 0 },
+            PolyglotEntry { key: key ^ 1, mv: from_move(&d2d4), weight: 30, learn: 0 },
+        ]);
+
+        assert_eq!(book.entries(key).len(), 2);
+        assert_eq!(book.probe(&mut board, 0), Some(e2e4));
+        assert!([Some(e2e4), Some(d2d4)].contains(&book.probe(&mut board, MAX_BOOK_VARIETY)));
+    }
+
+    #[test]
+    fn test_castle_encoding() {
+        let mut board = Board::read_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let castle = from_move_notation("e1g1", &mut board);
+        assert_eq!(castle.flag, Flag::KingCastle);
+        assert_eq!(to_move(&mut board, from_move(&castle)), Some(castle));
+        assert_eq!(from_move(&castle), (4 << 6) | 7);
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, WHITE};
+use crate::engine::board::fen::FenTrait;
+use crate::engine::board::piece::PieceTrait;
+use crate::engine::evaluation::imbalance_eval::ImbalanceEvalTrait;
+use crate::engine::evaluation::init_eval::InitEvalTrait;
+use crate::engine::evaluation::king_eval::KingEvalTrait;
+use crate::engine::evaluation::material_eval::MaterialEvalTrait;
+use crate::engine::evaluation::mobility_eval::MobilityEvalTrait;
+use crate::engine::evaluation::passed_pawn_eval::PassedPawnEvalTrait;
+use crate::engine::evaluation::pawn_eval::PawnEvalTrait;
+use crate::engine::evaluation::piece_eval::PieceEvalTrait;
+use crate::engine::evaluation::psqt_eval::PSQTEvalTrait;
+use crate::engine::evaluation::space_eval::SpaceEvalTrait;
+use crate::engine::evaluation::tempo_eval::TempoEvalTrait;
+use crate::engine::evaluation::threats_eval::ThreatsEvalTrait;
+use crate::engine::evaluation::winnable_eval::WinnableEvalTrait;
+
+pub type Score = (isize, isize);
+type EvalTerm = (&'static str, fn(&mut Board));
+
+// The terms of the evaluation, in the order in which they are added up
+#[rustfmt::skip]
+pub const EVAL_TERMS: [EvalTerm; 13] = [
+    ("Material", |b| { b.material_eval(WHITE); b.material_eval(BLACK) }),
+    ("PSQT", |b| { b.psqt_eval(WHITE); b.psqt_eval(BLACK) }),
+    ("Imbalance", |b| { b.imbalance(WHITE); b.imbalance(BLACK) }),
+    ("Pawns", |b| { b.pawns_eval(WHITE); b.pawns_eval(BLACK) }),
+    ("Pieces", |b| { b.piece_eval(WHITE); b.piece_eval(BLACK) }),
+    ("Mobility", |b| { b.mobility_eval(WHITE); b.mobility_eval(BLACK) }),
+    ("Threats", |b| { b.threats_eval(WHITE); b.threats_eval(BLACK) }),
+    ("Passed Pawns", |b| { b.passed_pawn(WHITE); b.passed_pawn(BLACK) }),
+    ("Space", |b| { b.space(WHITE); b.space(BLACK) }),
+    ("King", |b| { b.king_eval(WHITE); b.king_eval(BLACK) }),
+    ("Winnable", |b| b.winnable_eval()),
+    ("Scale", |b| b.scale_eval()),
+    ("Tempo", |b| { let clr = b.color(); b.tempo(clr) }),
+];
+
+// Names of the terms in the JSON output, the same as the fields of `EvalBreakdown`
+static JSON_NAMES: [&str; 13] = [
+    "material",
+    "psqt",
+    "imbalance",
+    "pawns",
+    "piece",
+    "mobility",
+    "threats",
+    "passed_pawn",
+    "space",
+    "king",
+    "winnable",
+    "scale",
+    "tempo",
+];
+
+///
+/// Score of every term of the evaluation for both sides, as added up by the evaluation
+///
+pub fn term_scores(board: &mut Board) -> [(&'static str, [Score; 2]); 13] {
+    board.eval.reset();
+    board.init();
+
+    EVAL_TERMS.map(|(name, term)| {
+        let before = board.eval.score;
+        term(board);
+        let after = board.eval.score;
+        let delta = |clr: usize| (after[clr].0 - before[clr].0, after[clr].1 - before[clr].1);
+        (name, [delta(WHITE.idx()), delta(BLACK.idx())])
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermBreakdown {
+    pub white: Score,
+    pub black: Score,
+    // Tapered (white - black)
+    pub total: isize,
+}
+
+impl TermBreakdown {
+    fn new([white, black]: [Score; 2], phase: Score) -> Self {
+        let (mg, eg) = (white.0 - black.0, white.1 - black.1);
+        Self { white, black, total: (phase.0 * mg + phase.1 * eg) / 128 }
+    }
+}
+
+///
+/// The classical evaluation split up by its terms, all scores from white's point of view.
+/// Same shape as `SFEval`, plus the scale factor
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalBreakdown {
+    pub fen: String,
+    pub phase: isize,
+    pub eval: isize,
+    pub scale_factor: isize,
+
+    pub material: TermBreakdown,
+    pub psqt: TermBreakdown,
+    pub imbalance: TermBreakdown,
+    pub pawns: TermBreakdown,
+    pub piece: TermBreakdown,
+    pub mobility: TermBreakdown,
+    pub threats: TermBreakdown,
+    pub passed_pawn: TermBreakdown,
+    pub space: TermBreakdown,
+    pub king: TermBreakdown,
+    pub winnable: TermBreakdown,
+    // Endgame score removed by the scale factor
+    pub scale: TermBreakdown,
+    pub tempo: TermBreakdown,
+}
+
+impl EvalBreakdown {
+    pub fn terms(&self) -> [&TermBreakdown; 13] {
+        [
+            &self.material,
+            &self.psqt,
+            &self.imbalance,
+            &self.pawns,
+            &self.piece,
+            &self.mobility,
+            &self.threats,
+            &self.passed_pawn,
+            &self.space,
+            &self.king,
+            &self.winnable,
+            &self.scale,
+            &self.tempo,
+        ]
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"fen\":{:?},\"phase\":{},\"eval\":{},\"scale_factor\":{}",
+            self.fen, self.phase, self.eval, self.scale_factor
+        );
+        for (name, term) in JSON_NAMES.iter().zip(self.terms()) {
+            json.push_str(&format!(
+                ",\"{}\":{{\"white\":[{},{}],\"black\":[{},{}],\"total\":{}}}",
+                name, term.white.0, term.white.1, term.black.0, term.black.1, term.total
+            ));
+        }
+        json.push('}');
+        json
+    }
+}
+
+impl fmt::Display for EvalBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "      Term     |    White    |    Black    | Total")?;
+        writeln!(f, "               |   MG    EG  |   MG    EG  |")?;
+        writeln!(f, "---------------+-------------+-------------+-------")?;
+        for ((name, _), term) in EVAL_TERMS.iter().zip(self.terms()) {
+            let (white, black) = (term.white, term.black);
+            writeln!(
+                f,
+                " {:<13} | {:>5} {:>5} | {:>5} {:>5} | {:>5}",
+                name, white.0, white.1, black.0, black.1, term.total
+            )?;
+        }
+        writeln!(f, "---------------+-------------+-------------+-------")?;
+        write!(
+            f,
+            "Phase: {}, Scale factor: {}, Eval: {} (white side)",
+            self.phase, self.scale_factor, self.eval
+        )
+    }
+}
+
+pub trait BreakdownEvalTrait {
+    fn evaluation_breakdown(&mut self) -> EvalBreakdown;
+}
+
+impl BreakdownEvalTrait for Board {
+    ///
+    /// Evaluates the position term by term (the classical evaluation, even with NNUE enabled)
+    ///
+    fn evaluation_breakdown(&mut self) -> EvalBreakdown {
+        let fen = self.to_fen();
+        let scores = term_scores(self);
+        let phase = self.eval.phase;
+
+        let [
+            material,
+            psqt,
+            imbalance,
+            pawns,
+            piece,
+            mobility,
+            threats,
+            passed_pawn,
+            space,
+            king,
+            winnable,
+            scale,
+            tempo,
+        ] = scores.map(|(_, score)| TermBreakdown::new(score, phase));
+        let eval = TermBreakdown::new(self.eval.score, phase).total;
+
+        EvalBreakdown {
+            fen,
+            phase: phase.0,
+            eval,
+            scale_factor: self.eval.scale_factor,
+            material,
+            psqt,
+            imbalance,
+            pawns,
+            piece,
+            mobility,
+            threats,
+            passed_pawn,
+            space,
+            king,
+            winnable,
+            scale,
+            tempo,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::color::ColorTrait;
+    use crate::engine::evaluation::common_eval::CommonEvalTrait;
+    use crate::engine::evaluation::evaluation::EvaluationTrait;
+    use crate::engine::evaluation::test_evaluation::{SF_EVAL, eval_assert};
+
+    #[test]
+    fn test_term_scores() {
+        // The terms add up to the evaluation
+        let mut board = Board::read_fen(SF_EVAL[0].fen);
+        let eval = board.evaluation();
+        let scores = term_scores(&mut board);
+        assert_eq!(board.calculate_score() * board.color().sign(), eval);
+
+        let sum = scores.iter().fold([(0, 0); 2], |acc, (_, score)| {
+            [0, 1].map(|clr| (acc[clr].0 + score[clr].0, acc[clr].1 + score[clr].1))
+        });
+        assert_eq!(sum, board.eval.score);
+    }
+
+    #[test]
+    fn test_breakdown() {
+        for obj in &SF_EVAL {
+            let mut board = Board::read_fen(obj.fen);
+            let eval = board.evaluation() * board.color().sign();
+            let breakdown = board.evaluation_breakdown();
+
+            assert_eq!(breakdown.fen, board.to_fen());
+            assert_eq!(breakdown.eval, eval);
+
+            // The same tolerances as the tests of the single terms
+            eval_assert(breakdown.material.total, obj.material, 0, false);
+            eval_assert(breakdown.psqt.total, obj.psqt, 0, false);
+            eval_assert(breakdown.imbalance.total, obj.imbalance, 1, false);
+            eval_assert(breakdown.pawns.total, obj.pawns, 0, false);
+            eval_assert(breakdown.piece.total, obj.piece, 45, false);
+            eval_assert(breakdown.mobility.total, obj.mobility, 36, false);
+            eval_assert(breakdown.threats.total, obj.threats, 0, false);
+            eval_assert(breakdown.passed_pawn.total, obj.passed_pawn, 10, false);
+            eval_assert(breakdown.space.total, obj.space, 0, false);
+            eval_assert(breakdown.king.total, obj.king, 54, false);
+            if obj.winnable != -123465 {
+                eval_assert(breakdown.winnable.total, obj.winnable, 2, false);
+            }
+            eval_assert(breakdown.tempo.total, obj.tempo, 0, false);
+        }
+    }
+
+    fn term(white: Score, black: Score, total: isize) -> TermBreakdown {
+        TermBreakdown { white, black, total }
+    }
+
+    #[test]
+    fn test_breakdown_values() {
+        let mut board = Board::read_fen(SF_EVAL[11].fen);
+        let expected = EvalBreakdown {
+            fen: SF_EVAL[11].fen.to_string(),
+            phase: 0,
+            eval: 203,
+            scale_factor: 64,
+            material: term((2677, 3264), (2474, 3211), 53),
+            psqt: term((356, -2), (395, 45), -47),
+            imbalance: term((169, 169), (312, 312), -143),
+            pawns: term((203, 145), (146, -47), 192),
+            piece: term((-13, -47), (30, -43), -4),
+            mobility: term((36, 118), (-26, -42), 160),
+            threats: term((114, 124), (285, 315), -191),
+            passed_pawn: term((433, 127), (-13, 32), 95),
+            space: term((0, 0), (0, 0), 0),
+            king: term((-205, 5), (25, -27), 32),
+            winnable: term((0, 28), (0, 0), 28),
+            scale: term((0, 0), (0, 0), 0),
+            tempo: term((28, 28), (0, 0), 28),
+        };
+        assert_eq!(board.evaluation_breakdown(), expected);
+    }
+
+    #[test]
+    fn test_breakdown_json() {
+        let mut board = Board::read_fen(SF_EVAL[0].fen);
+        let breakdown = board.evaluation_breakdown();
+
+        let expected = [
+            format!(
+                "{{\"fen\":\"{}\",\"phase\":106,\"eval\":-62,\"scale_factor\":64",
+                SF_EVAL[0].fen
+            ),
+            "\"material\":{\"white\":[7396,8386],\"black\":[7440,8447],\"total\":-46}".into(),
+            "\"psqt\":{\"white\":[295,-25],\"black\":[351,32],\"total\":-56}".into(),
+            "\"imbalance\":{\"white\":[379,379],\"black\":[343,343],\"total\":36}".into(),
+            "\"pawns\":{\"white\":[47,-36],\"black\":[50,-91],\"total\":6}".into(),
+            "\"piece\":{\"white\":[29,-26],\"black\":[-29,-52],\"total\":52}".into(),
+            "\"mobility\":{\"white\":[134,340],\"black\":[166,361],\"total\":-30}".into(),
+            "\"threats\":{\"white\":[146,123],\"black\":[135,152],\"total\":4}".into(),
+            "\"passed_pawn\":{\"white\":[0,0],\"black\":[0,0],\"total\":0}".into(),
+            "\"space\":{\"white\":[75,0],\"black\":[60,0],\"total\":12}".into(),
+            "\"king\":{\"white\":[-101,-5],\"black\":[-22,26],\"total\":-70}".into(),
+            "\"winnable\":{\"white\":[0,8],\"black\":[0,0],\"total\":1}".into(),
+            "\"scale\":{\"white\":[0,0],\"black\":[0,0],\"total\":0}".into(),
+            "\"tempo\":{\"white\":[28,28],\"black\":[0,0],\"total\":28}}".into(),
+        ];
+        assert_eq!(breakdown.to_json(), expected.join(","));
+        assert_eq!(breakdown.to_string().lines().count(), EVAL_TERMS.len() + 5);
+    }
+}
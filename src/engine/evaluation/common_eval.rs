@@ -4,6 +4,7 @@ use crate::engine::attacks::knight::get_knight_mask;
 use crate::engine::attacks::pawn::{get_pawn_2_att, get_pawn_att_mask};
 use crate::engine::attacks::queen::get_queen_mask;
 use crate::engine::attacks::rook::get_rook_mask;
+use crate::engine::board::attack_map::x_ray_mask;
 use crate::engine::board::board::Board;
 use crate::engine::board::color::{BLACK, Color, ColorTrait, WHITE};
 use crate::engine::board::piece::*;
@@ -168,25 +169,7 @@ impl CommonEvalTrait for Board {
 
     #[inline(always)]
     fn x_ray_mask(&mut self, piece: Piece, sq: usize) -> u64 {
-        let clr = piece.color();
-        let (mut own, mut enemy) = self.both_occ_bb(clr);
-        match piece.kind() {
-            PAWN => get_pawn_att_mask(sq, own, enemy, clr),
-            KNIGHT => get_knight_mask(sq, own, enemy, clr),
-            BISHOP => {
-                own &= !(self.queen_bb(clr));
-                enemy &= !self.queen_bb(clr.opp());
-                get_bishop_mask(sq, own, enemy, clr)
-            }
-            ROOK => {
-                own &= !(self.queen_bb(clr) | self.rook_bb(clr));
-                enemy &= !self.queen_bb(clr.opp());
-                get_rook_mask(sq, own, enemy, clr)
-            }
-            QUEEN => get_queen_mask(sq, own, enemy, clr),
-            KING => get_king_mask(sq, own, enemy, clr),
-            _ => panic!("Invalid Peace Type"),
-        }
+        x_ray_mask(self, piece, sq)
     }
 
     #[inline(always)]
@@ -0,0 +1,609 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+
+use crate::engine::evaluation::common_eval::KING_ATT_WEIGHT;
+use crate::engine::evaluation::imbalance_eval::{QUADRATIC_OURS, QUADRATIC_THEIRS};
+use crate::engine::evaluation::king_eval::{BLOCKED_STORM, UNBLOCKED_STORM, WEAKNESS};
+use crate::engine::evaluation::material_eval::PIECE_MATERIAL;
+use crate::engine::evaluation::mobility_eval::{
+    BISHOP_MOBILITY, KNIGHT_MOBILITY, QUEEN_MOBILITY, ROOK_MOBILITY,
+};
+use crate::engine::evaluation::passed_pawn_eval::PASSED_PAWN_REW;
+use crate::engine::evaluation::psqt_eval::PSQT;
+use crate::engine::evaluation::tempo_eval::TEMPO_WT;
+use crate::engine::evaluation::threats_eval::{MINOR_THREAT, ROOK_THREAT};
+use crate::engine::evaluation::winnable_eval::WINNABLE_WT;
+use crate::engine::misc::display::display_moves::sq_notation;
+
+// The evaluation weights of the engine, every board holds a handle to them
+pub type SharedEvalParams = Arc<EvalParams>;
+
+pub static EVAL_PARAMS: Lazy<SharedEvalParams> = Lazy::new(|| Arc::new(EvalParams::default()));
+
+// Names of the pieces in the order of `arr_idx`
+pub static PIECE_NAMES: [&str; 6] = ["pawn", "knight", "king", "bishop", "rook", "queen"];
+
+///
+/// Every weight of the evaluation as (middle game, end game) pairs. The defaults are the
+/// tables of the `*_eval.rs` files and the Stockfish 9 constants of the evaluation terms
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalParams {
+    // Material and PSQT (indexed by `arr_idx`, the PSQT from white's point of view)
+    pub material: [(isize, isize); 6],
+    pub psqt: [[(isize, isize); 64]; 6],
+
+    // Imbalance (in 1/16)
+    pub bishop_pair: isize,
+    pub quadratic_ours: [[isize; 6]; 6],
+    pub quadratic_theirs: [[isize; 6]; 6],
+
+    // Mobility (indexed by the safe squares)
+    pub knight_mobility: [(isize, isize); 9],
+    pub bishop_mobility: [(isize, isize); 14],
+    pub rook_mobility: [(isize, isize); 15],
+    pub queen_mobility: [(isize, isize); 28],
+
+    // Pawns
+    pub isolated: (isize, isize),
+    pub backward: (isize, isize),
+    pub doubled: (isize, isize),
+    pub doubled_isolated: (isize, isize),
+    pub weak_unopposed: (isize, isize),
+    pub weak_lever: (isize, isize),
+    // Blocked on the 5th and on the 6th rank
+    pub blocked_pawn: [(isize, isize); 2],
+    // Connected bonus by the relative rank, and per supporting pawn
+    pub connected_seed: [isize; 8],
+    pub connected_supported: isize,
+
+    // Pieces
+    pub minor_behind_pawn: (isize, isize),
+    pub bishop_pawns: (isize, isize),
+    pub bishop_xray_pawns: (isize, isize),
+    pub rook_on_queen_file: (isize, isize),
+    pub rook_on_king_ring: (isize, isize),
+    pub bishop_on_king_ring: (isize, isize),
+    pub rook_on_open_file: (isize, isize),
+    pub rook_on_semi_open_file: (isize, isize),
+    pub trapped_rook: (isize, isize),
+    pub weak_queen: (isize, isize),
+    pub queen_infiltration: (isize, isize),
+    pub knight_protector: (isize, isize),
+    pub bishop_protector: (isize, isize),
+    pub reachable_outpost: (isize, isize),
+    pub knight_outpost: (isize, isize),
+    pub bishop_outpost: (isize, isize),
+
+    // Threats (by the attacked piece)
+    pub minor_threat: [(isize, isize); 6],
+    pub rook_threat: [(isize, isize); 6],
+    pub hanging: (isize, isize),
+    pub king_threat: (isize, isize),
+    pub pawn_push_threat: (isize, isize),
+    pub threat_safe_pawn: (isize, isize),
+    pub slider_on_queen: (isize, isize),
+    pub knight_on_queen: (isize, isize),
+    pub restricted: (isize, isize),
+    pub weak_queen_protection: (isize, isize),
+
+    // Passed Pawns (by the relative rank)
+    pub passed_rank: [(isize, isize); 8],
+    pub passed_file: (isize, isize),
+    // Free path: no unsafe square, no unsafe square in front, safe push, defended push
+    pub passed_block: [isize; 4],
+    // Distance of the enemy king (in 1/4), of the own king, and again of the own king
+    // unless the pawn is about to promote
+    pub passed_king_proximity: [isize; 3],
+
+    // King
+    pub king_att_weight: [isize; 6],
+    pub danger_king_attacks: isize,
+    pub danger_weak_squares: isize,
+    pub danger_knight_defender: isize,
+    pub danger_unsafe_checks: isize,
+    pub danger_flank_defense: isize,
+    // In 1/8
+    pub danger_flank_attack: isize,
+    pub danger_no_queen: isize,
+    // In 1/8
+    pub danger_shelter: isize,
+    pub danger_offset: isize,
+    pub danger_safe_checks: [isize; 6],
+    pub pawnless_flank: (isize, isize),
+    pub flank_attack: (isize, isize),
+    pub king_pawn_distance: (isize, isize),
+    pub unblocked_storm: [[isize; 7]; 8],
+    pub blocked_storm: [[isize; 7]; 2],
+    pub weakness: [[isize; 7]; 8],
+
+    // Winnable: passed pawns, pawns, outflanking, pawns on both flanks, infiltration,
+    // pure pawn ending, almost unwinnable, offset
+    pub winnable: [isize; 8],
+
+    // Tempo
+    pub tempo: isize,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self {
+            material: PIECE_MATERIAL,
+            psqt: PSQT,
+
+            bishop_pair: 1438,
+            quadratic_ours: QUADRATIC_OURS,
+            quadratic_theirs: QUADRATIC_THEIRS,
+
+            knight_mobility: KNIGHT_MOBILITY,
+            bishop_mobility: BISHOP_MOBILITY,
+            rook_mobility: ROOK_MOBILITY,
+            queen_mobility: QUEEN_MOBILITY,
+
+            isolated: (-5, -15),
+            backward: (-9, -24),
+            doubled: (-11, -56),
+            doubled_isolated: (-11, -56),
+            weak_unopposed: (-13, -27),
+            weak_lever: (0, -56),
+            blocked_pawn: [(-11, -4), (-3, 4)],
+            connected_seed: [0, 7, 8, 12, 29, 48, 86, 0],
+            connected_supported: 21,
+
+            minor_behind_pawn: (18, 3),
+            bishop_pawns: (-3, -5),
+            bishop_xray_pawns: (-4, -5),
+            rook_on_queen_file: (6, 11),
+            rook_on_king_ring: (16, 0),
+            bishop_on_king_ring: (24, 0),
+            rook_on_open_file: (48, 29),
+            rook_on_semi_open_file: (19, 7),
+            trapped_rook: (-55, -13),
+            weak_queen: (-56, -15),
+            queen_infiltration: (-2, 14),
+            knight_protector: (-8, -9),
+            bishop_protector: (-6, -9),
+            reachable_outpost: (31, 22),
+            knight_outpost: (56, 36),
+            bishop_outpost: (30, 23),
+
+            minor_threat: MINOR_THREAT,
+            rook_threat: ROOK_THREAT,
+            hanging: (69, 36),
+            king_threat: (24, 89),
+            pawn_push_threat: (48, 39),
+            threat_safe_pawn: (173, 94),
+            slider_on_queen: (60, 18),
+            knight_on_queen: (16, 11),
+            restricted: (7, 7),
+            weak_queen_protection: (14, 0),
+
+            passed_rank: PASSED_PAWN_REW[0],
+            passed_file: (-11, -8),
+            passed_block: [35, 20, 9, 5],
+            passed_king_proximity: [19, 2, 1],
+
+            king_att_weight: KING_ATT_WEIGHT,
+            danger_king_attacks: 69,
+            danger_weak_squares: 185,
+            danger_knight_defender: -100,
+            danger_unsafe_checks: 148,
+            danger_flank_defense: -4,
+            danger_flank_attack: 3,
+            danger_no_queen: -873,
+            danger_shelter: 6,
+            danger_offset: 37,
+            danger_safe_checks: [0, 792, 0, 645, 1084, 772],
+            pawnless_flank: (17, 95),
+            flank_attack: (8, 0),
+            king_pawn_distance: (0, -16),
+            unblocked_storm: UNBLOCKED_STORM,
+            blocked_storm: BLOCKED_STORM,
+            weakness: WEAKNESS,
+
+            winnable: WINNABLE_WT,
+
+            tempo: TEMPO_WT,
+        }
+    }
+}
+
+impl EvalParams {
+    ///
+    /// Calls `f` with the name and a reference of every single weight, always in the same
+    /// order, e.g. "psqt.knight.e4.mg" or "connected_seed[3]"
+    ///
+    pub fn visit(&mut self, f: &mut impl FnMut(&str, &mut isize)) {
+        pieces(f, "material", &mut self.material);
+        for (idx, table) in self.psqt.iter_mut().enumerate() {
+            for (sq, value) in table.iter_mut().enumerate() {
+                score(f, &format!("psqt.{}.{}", PIECE_NAMES[idx], sq_notation(sq as u8)), value);
+            }
+        }
+
+        f("bishop_pair", &mut self.bishop_pair);
+        for (idx, row) in self.quadratic_ours.iter_mut().enumerate() {
+            values(f, &format!("quadratic_ours[{}]", idx), row);
+        }
+        for (idx, row) in self.quadratic_theirs.iter_mut().enumerate() {
+            values(f, &format!("quadratic_theirs[{}]", idx), row);
+        }
+
+        scores(f, "knight_mobility", &mut self.knight_mobility);
+        scores(f, "bishop_mobility", &mut self.bishop_mobility);
+        scores(f, "rook_mobility", &mut self.rook_mobility);
+        scores(f, "queen_mobility", &mut self.queen_mobility);
+
+        score(f, "isolated", &mut self.isolated);
+        score(f, "backward", &mut self.backward);
+        score(f, "doubled", &mut self.doubled);
+        score(f, "doubled_isolated", &mut self.doubled_isolated);
+        score(f, "weak_unopposed", &mut self.weak_unopposed);
+        score(f, "weak_lever", &mut self.weak_lever);
+        scores(f, "blocked_pawn", &mut self.blocked_pawn);
+        values(f, "connected_seed", &mut self.connected_seed);
+        f("connected_supported", &mut self.connected_supported);
+
+        score(f, "minor_behind_pawn", &mut self.minor_behind_pawn);
+        score(f, "bishop_pawns", &mut self.bishop_pawns);
+        score(f, "bishop_xray_pawns", &mut self.bishop_xray_pawns);
+        score(f, "rook_on_queen_file", &mut self.rook_on_queen_file);
+        score(f, "rook_on_king_ring", &mut self.rook_on_king_ring);
+        score(f, "bishop_on_king_ring", &mut self.bishop_on_king_ring);
+        score(f, "rook_on_open_file", &mut self.rook_on_open_file);
+        score(f, "rook_on_semi_open_file", &mut self.rook_on_semi_open_file);
+        score(f, "trapped_rook", &mut self.trapped_rook);
+        score(f, "weak_queen", &mut self.weak_queen);
+        score(f, "queen_infiltration", &mut self.queen_infiltration);
+        score(f, "knight_protector", &mut self.knight_protector);
+        score(f, "bishop_protector", &mut self.bishop_protector);
+        score(f, "reachable_outpost", &mut self.reachable_outpost);
+        score(f, "knight_outpost", &mut self.knight_outpost);
+        score(f, "bishop_outpost", &mut self.bishop_outpost);
+
+        pieces(f, "minor_threat", &mut self.minor_threat);
+        pieces(f, "rook_threat", &mut self.rook_threat);
+        score(f, "hanging", &mut self.hanging);
+        score(f, "king_threat", &mut self.king_threat);
+        score(f, "pawn_push_threat", &mut self.pawn_push_threat);
+        score(f, "threat_safe_pawn", &mut self.threat_safe_pawn);
+        score(f, "slider_on_queen", &mut self.slider_on_queen);
+        score(f, "knight_on_queen", &mut self.knight_on_queen);
+        score(f, "restricted", &mut self.restricted);
+        score(f, "weak_queen_protection", &mut self.weak_queen_protection);
+
+        scores(f, "passed_rank", &mut self.passed_rank);
+        score(f, "passed_file", &mut self.passed_file);
+        values(f, "passed_block", &mut self.passed_block);
+        values(f, "passed_king_proximity", &mut self.passed_king_proximity);
+
+        for (idx, value) in self.king_att_weight.iter_mut().enumerate() {
+            f(&format!("king_att_weight.{}", PIECE_NAMES[idx]), value);
+        }
+        f("danger_king_attacks", &mut self.danger_king_attacks);
+        f("danger_weak_squares", &mut self.danger_weak_squares);
+        f("danger_knight_defender", &mut self.danger_knight_defender);
+        f("danger_unsafe_checks", &mut self.danger_unsafe_checks);
+        f("danger_flank_defense", &mut self.danger_flank_defense);
+        f("danger_flank_attack", &mut self.danger_flank_attack);
+        f("danger_no_queen", &mut self.danger_no_queen);
+        f("danger_shelter", &mut self.danger_shelter);
+        f("danger_offset", &mut self.danger_offset);
+        for (idx, value) in self.danger_safe_checks.iter_mut().enumerate() {
+            f(&format!("danger_safe_checks.{}", PIECE_NAMES[idx]), value);
+        }
+        score(f, "pawnless_flank", &mut self.pawnless_flank);
+        score(f, "flank_attack", &mut self.flank_attack);
+        score(f, "king_pawn_distance", &mut self.king_pawn_distance);
+        for (idx, row) in self.unblocked_storm.iter_mut().enumerate() {
+            values(f, &format!("unblocked_storm[{}]", idx), row);
+        }
+        for (idx, row) in self.blocked_storm.iter_mut().enumerate() {
+            values(f, &format!("blocked_storm[{}]", idx), row);
+        }
+        for (idx, row) in self.weakness.iter_mut().enumerate() {
+            values(f, &format!("weakness[{}]", idx), row);
+        }
+
+        values(f, "winnable", &mut self.winnable);
+
+        f("tempo", &mut self.tempo);
+    }
+
+    ///
+    /// Names of the weights in the order of `to_vec`
+    ///
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::with_capacity(2048);
+        self.clone().visit(&mut |name, _| names.push(name.to_string()));
+        names
+    }
+
+    ///
+    /// The weights as one flat parameter vector
+    ///
+    pub fn to_vec(&self) -> Vec<isize> {
+        let mut vec = Vec::with_capacity(2048);
+        self.clone().visit(&mut |_, value| vec.push(*value));
+        vec
+    }
+
+    ///
+    /// Sets the weights from a parameter vector created with `to_vec`
+    ///
+    pub fn from_vec(&mut self, vec: &[isize]) {
+        let mut iter = vec.iter();
+        self.visit(&mut |_, value| *value = *iter.next().expect("Parameter vector is too short"));
+        assert!(iter.next().is_none(), "Parameter vector is too long");
+    }
+
+    ///
+    /// The weights as text, one "name = mg eg" or "name = value" line per weight
+    ///
+    pub fn to_text(&self) -> String {
+        let mut entries: Vec<(String, Vec<isize>)> = Vec::with_capacity(1024);
+        self.clone().visit(&mut |name, value| match name.strip_suffix(".eg") {
+            Some(base) if entries.last().is_some_and(|(last, _)| last == base) => {
+                entries.last_mut().unwrap().1.push(*value)
+            }
+            _ => entries.push((name.strip_suffix(".mg").unwrap_or(name).to_string(), vec![*value])),
+        });
+
+        let mut text = String::from("# Evaluation weights: \"name = mg eg\" or \"name = value\"\n");
+        let mut group = "";
+        for (name, values) in &entries {
+            let prefix = name.split(['.', '[']).next().unwrap_or_default();
+            if prefix != group {
+                text.push('\n');
+                group = prefix;
+            }
+            let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            text.push_str(&format!("{} = {}\n", name, values.join(" ")));
+        }
+        text
+    }
+
+    ///
+    /// Parses weights written by `to_text`. Missing weights keep their default, so a file can
+    /// hold only the weights that differ. Lines starting with "#" are comments
+    ///
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines: HashMap<&str, (usize, Vec<isize>)> = HashMap::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((name, values)) = line.split_once('=') else {
+                return Err(format!("Line {}: Expected \"name = value\": {}", idx + 1, line));
+            };
+            let values: Result<Vec<isize>, _> = values.split_whitespace().map(str::parse).collect();
+            match values {
+                Ok(values) if !values.is_empty() && values.len() <= 2 => {
+                    lines.insert(name.trim(), (idx + 1, values));
+                }
+                _ => return Err(format!("Line {}: Invalid value: {}", idx + 1, line)),
+            }
+        }
+
+        let mut params = Self::default();
+        let mut used = Vec::with_capacity(lines.len());
+        let mut errors = Vec::new();
+        params.visit(&mut |name, value| {
+            // Either the full name of the weight, or the name of its (mg, eg) pair
+            let pair = match name.rsplit_once('.') {
+                Some((base, "mg")) => Some((base, 0)),
+                Some((base, "eg")) => Some((base, 1)),
+                _ => None,
+            };
+            let entry = match lines.get_key_value(name) {
+                Some((key, (line, values))) => Some((*key, *line, values, 0)),
+                None => pair.and_then(|(base, idx)| {
+                    lines
+                        .get_key_value(base)
+                        .map(|(key, (line, values))| (*key, *line, values, idx))
+                }),
+            };
+
+            if let Some((key, line, values, idx)) = entry {
+                match (values.len(), pair.is_some() && key != name) {
+                    (2, true) | (1, false) => *value = values[idx],
+                    _ => errors.push(format!("Line {}: Wrong number of values for {}", line, key)),
+                }
+                used.push(key);
+            }
+        });
+
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        let mut unknown: Vec<_> = lines.iter().filter(|(name, _)| !used.contains(name)).collect();
+        unknown.sort_by_key(|(_, (line, _))| *line);
+        match unknown.first() {
+            Some((name, (line, _))) => Err(format!("Line {}: Unknown weight: {}", line, name)),
+            None => Ok(params),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_text(&text)
+    }
+
+    ///
+    /// The weights as Rust source, ready to replace the default of the engine
+    ///
+    pub fn to_rust(&self) -> String {
+        let mut rust = String::from("EvalParams {\n");
+        rust.push_str(&format!("    material: {:?},\n", self.material));
+        rust.push_str("    psqt: [\n");
+        for (idx, table) in self.psqt.iter().enumerate() {
+            rust.push_str(&format!("        [ // {}\n", PIECE_NAMES[idx]));
+            for row in table.chunks(8) {
+                rust.push_str(&format!("            {},\n", rust_row(row)));
+            }
+            rust.push_str("        ],\n");
+        }
+        rust.push_str("    ],\n");
+
+        let mut fields = Vec::new();
+        fields.push(("bishop_pair", format!("{:?}", self.bishop_pair)));
+        fields.push(("quadratic_ours", rust_table(&self.quadratic_ours)));
+        fields.push(("quadratic_theirs", rust_table(&self.quadratic_theirs)));
+        fields.push(("knight_mobility", format!("{:?}", self.knight_mobility)));
+        fields.push(("bishop_mobility", format!("{:?}", self.bishop_mobility)));
+        fields.push(("rook_mobility", format!("{:?}", self.rook_mobility)));
+        fields.push(("queen_mobility", format!("{:?}", self.queen_mobility)));
+        fields.push(("isolated", format!("{:?}", self.isolated)));
+        fields.push(("backward", format!("{:?}", self.backward)));
+        fields.push(("doubled", format!("{:?}", self.doubled)));
+        fields.push(("doubled_isolated", format!("{:?}", self.doubled_isolated)));
+        fields.push(("weak_unopposed", format!("{:?}", self.weak_unopposed)));
+        fields.push(("weak_lever", format!("{:?}", self.weak_lever)));
+        fields.push(("blocked_pawn", format!("{:?}", self.blocked_pawn)));
+        fields.push(("connected_seed", format!("{:?}", self.connected_seed)));
+        fields.push(("connected_supported", format!("{:?}", self.connected_supported)));
+        fields.push(("minor_behind_pawn", format!("{:?}", self.minor_behind_pawn)));
+        fields.push(("bishop_pawns", format!("{:?}", self.bishop_pawns)));
+        fields.push(("bishop_xray_pawns", format!("{:?}", self.bishop_xray_pawns)));
+        fields.push(("rook_on_queen_file", format!("{:?}", self.rook_on_queen_file)));
+        fields.push(("rook_on_king_ring", format!("{:?}", self.rook_on_king_ring)));
+        fields.push(("bishop_on_king_ring", format!("{:?}", self.bishop_on_king_ring)));
+        fields.push(("rook_on_open_file", format!("{:?}", self.rook_on_open_file)));
+        fields.push(("rook_on_semi_open_file", format!("{:?}", self.rook_on_semi_open_file)));
+        fields.push(("trapped_rook", format!("{:?}", self.trapped_rook)));
+        fields.push(("weak_queen", format!("{:?}", self.weak_queen)));
+        fields.push(("queen_infiltration", format!("{:?}", self.queen_infiltration)));
+        fields.push(("knight_protector", format!("{:?}", self.knight_protector)));
+        fields.push(("bishop_protector", format!("{:?}", self.bishop_protector)));
+        fields.push(("reachable_outpost", format!("{:?}", self.reachable_outpost)));
+        fields.push(("knight_outpost", format!("{:?}", self.knight_outpost)));
+        fields.push(("bishop_outpost", format!("{:?}", self.bishop_outpost)));
+        fields.push(("minor_threat", format!("{:?}", self.minor_threat)));
+        fields.push(("rook_threat", format!("{:?}", self.rook_threat)));
+        fields.push(("hanging", format!("{:?}", self.hanging)));
+        fields.push(("king_threat", format!("{:?}", self.king_threat)));
+        fields.push(("pawn_push_threat", format!("{:?}", self.pawn_push_threat)));
+        fields.push(("threat_safe_pawn", format!("{:?}", self.threat_safe_pawn)));
+        fields.push(("slider_on_queen", format!("{:?}", self.slider_on_queen)));
+        fields.push(("knight_on_queen", format!("{:?}", self.knight_on_queen)));
+        fields.push(("restricted", format!("{:?}", self.restricted)));
+        fields.push(("weak_queen_protection", format!("{:?}", self.weak_queen_protection)));
+        fields.push(("passed_rank", format!("{:?}", self.passed_rank)));
+        fields.push(("passed_file", format!("{:?}", self.passed_file)));
+        fields.push(("passed_block", format!("{:?}", self.passed_block)));
+        fields.push(("passed_king_proximity", format!("{:?}", self.passed_king_proximity)));
+        fields.push(("king_att_weight", format!("{:?}", self.king_att_weight)));
+        fields.push(("danger_king_attacks", format!("{:?}", self.danger_king_attacks)));
+        fields.push(("danger_weak_squares", format!("{:?}", self.danger_weak_squares)));
+        fields.push(("danger_knight_defender", format!("{:?}", self.danger_knight_defender)));
+        fields.push(("danger_unsafe_checks", format!("{:?}", self.danger_unsafe_checks)));
+        fields.push(("danger_flank_defense", format!("{:?}", self.danger_flank_defense)));
+        fields.push(("danger_flank_attack", format!("{:?}", self.danger_flank_attack)));
+        fields.push(("danger_no_queen", format!("{:?}", self.danger_no_queen)));
+        fields.push(("danger_shelter", format!("{:?}", self.danger_shelter)));
+        fields.push(("danger_offset", format!("{:?}", self.danger_offset)));
+        fields.push(("danger_safe_checks", format!("{:?}", self.danger_safe_checks)));
+        fields.push(("pawnless_flank", format!("{:?}", self.pawnless_flank)));
+        fields.push(("flank_attack", format!("{:?}", self.flank_attack)));
+        fields.push(("king_pawn_distance", format!("{:?}", self.king_pawn_distance)));
+        fields.push(("unblocked_storm", rust_table(&self.unblocked_storm)));
+        fields.push(("blocked_storm", rust_table(&self.blocked_storm)));
+        fields.push(("weakness", rust_table(&self.weakness)));
+        fields.push(("winnable", format!("{:?}", self.winnable)));
+        fields.push(("tempo", format!("{:?}", self.tempo)));
+
+        for (name, value) in fields {
+            rust.push_str(&format!("    {}: {},\n", name, value));
+        }
+        rust.push_str("}\n");
+        rust
+    }
+}
+
+fn score(f: &mut impl FnMut(&str, &mut isize), name: &str, value: &mut (isize, isize)) {
+    f(&format!("{}.mg", name), &mut value.0);
+    f(&format!("{}.eg", name), &mut value.1);
+}
+
+fn scores(f: &mut impl FnMut(&str, &mut isize), name: &str, values: &mut [(isize, isize)]) {
+    for (idx, value) in values.iter_mut().enumerate() {
+        score(f, &format!("{}[{}]", name, idx), value);
+    }
+}
+
+fn pieces(f: &mut impl FnMut(&str, &mut isize), name: &str, values: &mut [(isize, isize); 6]) {
+    for (idx, value) in values.iter_mut().enumerate() {
+        score(f, &format!("{}.{}", name, PIECE_NAMES[idx]), value);
+    }
+}
+
+fn values(f: &mut impl FnMut(&str, &mut isize), name: &str, values: &mut [isize]) {
+    for (idx, value) in values.iter_mut().enumerate() {
+        f(&format!("{}[{}]", name, idx), value);
+    }
+}
+
+fn rust_row<T: Debug>(row: &[T]) -> String {
+    row.iter().map(|value| format!("{:?}", value)).collect::<Vec<_>>().join(", ")
+}
+
+fn rust_table<T: Debug>(table: &[T]) -> String {
+    let rows: Vec<String> = table.iter().map(|row| format!("        {:?},\n", row)).collect();
+    format!("[\n{}    ]", rows.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_vector() {
+        let params = EvalParams::default();
+        let names = params.names();
+        let mut vec = params.to_vec();
+        assert_eq!(names.len(), vec.len());
+        assert!(names.contains(&"psqt.knight.e4.mg".to_string()));
+        assert!(names.contains(&"king_att_weight.rook".to_string()));
+
+        let idx = names.iter().position(|name| name == "tempo").unwrap();
+        assert_eq!(vec[idx], TEMPO_WT);
+        vec[idx] += 5;
+
+        let mut tuned = EvalParams::default();
+        tuned.from_vec(&vec);
+        assert_eq!(tuned.tempo, TEMPO_WT + 5);
+        assert_eq!(tuned.to_vec(), vec);
+        assert!(tuned.to_rust().contains(&format!("tempo: {},", TEMPO_WT + 5)));
+    }
+
+    #[test]
+    fn test_params_text() {
+        let mut params = EvalParams::default();
+        params.psqt[1][28] = (1, -2);
+        params.tempo = 40;
+
+        let text = params.to_text();
+        assert!(text.contains("psqt.knight.e4 = 1 -2\n"));
+        assert!(text.contains("tempo = 40\n"));
+        assert_eq!(EvalParams::from_text(&text), Ok(params));
+
+        // Only the listed weights change, single values of a pair can be set by their name
+        let text = "# Test\nmaterial.knight = 800 900\nhanging.eg = 40 # Comment\n";
+        let params = EvalParams::from_text(text).unwrap();
+        assert_eq!(params.material[1], (800, 900));
+        assert_eq!(params.hanging, (EvalParams::default().hanging.0, 40));
+        assert_eq!(params.tempo, TEMPO_WT);
+
+        assert!(EvalParams::from_text("tempo = 1 2").is_err());
+        assert!(EvalParams::from_text("material.knight = 800").is_err());
+        assert!(EvalParams::from_text("unknown = 1").is_err());
+        assert!(EvalParams::from_text("tempo 1").is_err());
+    }
+}
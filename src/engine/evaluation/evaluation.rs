@@ -2,11 +2,13 @@ use crate::engine::board::board::Board;
 use crate::engine::board::color::*;
 use crate::engine::board::piece::{Piece, PieceTrait};
 use crate::engine::evaluation::common_eval::CommonEvalTrait;
+use crate::engine::evaluation::eval_params::SharedEvalParams;
 use crate::engine::evaluation::imbalance_eval::ImbalanceEvalTrait;
 use crate::engine::evaluation::init_eval::InitEvalTrait;
 use crate::engine::evaluation::king_eval::KingEvalTrait;
 use crate::engine::evaluation::material_eval::MaterialEvalTrait;
 use crate::engine::evaluation::mobility_eval::MobilityEvalTrait;
+use crate::engine::evaluation::nnue::NnueTrait;
 use crate::engine::evaluation::passed_pawn_eval::PassedPawnEvalTrait;
 use crate::engine::evaluation::pawn_eval::PawnEvalTrait;
 use crate::engine::evaluation::piece_eval::PieceEvalTrait;
@@ -15,8 +17,8 @@ use crate::engine::evaluation::space_eval::SpaceEvalTrait;
 use crate::engine::evaluation::tempo_eval::TempoEvalTrait;
 use crate::engine::evaluation::threats_eval::ThreatsEvalTrait;
 use crate::engine::evaluation::trace_eval::TraceEvalTrait;
+use crate::engine::evaluation::winnable_eval::{SCALE_FACTOR_NORMAL, WinnableEvalTrait};
 use crate::engine::misc::bitboard::Bitboard;
-use crate::engine::search::pawn_hash_table::PAWN_TT;
 
 // The Numbers (Tapered Eval) for the evaluation are taken from -> STOCKFISH SF_9
 
@@ -54,6 +56,10 @@ pub struct Evaluation {
     pub checks: [Bitboard; 14],
     pub king_ring: [Bitboard; 2],
 
+    // Winnable Evaluation
+    pub winnable: (isize, isize),
+    pub scale_factor: isize,
+
     // Trace
     pub mg_test: [[isize; 64]; 2],
     pub eg_test: [[isize; 64]; 2],
@@ -106,6 +112,10 @@ impl Evaluation {
             king_pawn_dx: [6; 2],
             king_shelter: [(0, 0, 0); 2],
 
+            // Winnable Evaluation
+            winnable: (0, 0),
+            scale_factor: SCALE_FACTOR_NORMAL,
+
             // Trace
             mg_test: [[0; 64]; 2],
             eg_test: [[0; 64]; 2],
@@ -157,6 +167,10 @@ impl Evaluation {
         self.king_pawn_dx.fill(6);
         self.king_shelter.fill((0, 0, 0));
 
+        // Winnable Evaluation
+        self.winnable = (0, 0);
+        self.scale_factor = SCALE_FACTOR_NORMAL;
+
         // Trace
         // self.mg_test = [[0; 64]; 2];
         // self.eg_test = [[0; 64]; 2];
@@ -202,6 +216,7 @@ pub trait EvaluationTrait:
     + ThreatsEvalTrait
     + KingEvalTrait
     + SpaceEvalTrait
+    + WinnableEvalTrait
     + TempoEvalTrait
 {
     fn evaluation(&mut self) -> isize;
@@ -211,6 +226,8 @@ pub trait EvaluationTrait:
     fn clear_eval(&mut self, piece: Piece, sq: usize);
     fn add_eval(&mut self, piece: Piece, sq: usize);
     fn quiet_eval(&mut self, piece: Piece, from: usize, to: usize);
+
+    fn set_params(&mut self, params: SharedEvalParams);
 }
 
 impl EvaluationTrait for Board {
@@ -229,6 +246,10 @@ impl EvaluationTrait for Board {
     }
 
     fn evaluation(&mut self) -> isize {
+        if self.nnue.network.is_some() {
+            return self.nnue_eval();
+        }
+
         self.eval.reset();
         self.init();
 
@@ -272,17 +293,30 @@ impl EvaluationTrait for Board {
         self.king_eval(WHITE);
         self.king_eval(BLACK);
 
-        // 11. Tempo
+        // 11. Winnable
+        self.winnable_eval();
+        self.scale_eval();
+
+        // 12. Tempo
         self.tempo(self.color());
 
         return self.calculate_score() * self.color().sign();
     }
 
     fn inc_eval(&mut self) -> isize {
+        if self.nnue.network.is_some() {
+            return self.nnue_eval();
+        }
+
+        // The same positions are evaluated again and again (transpositions, futility pruning)
+        if let Some(eval) = self.eval_tt.get(self.key()) {
+            return eval;
+        }
+
         self.eval.reset();
 
         // if let Some(pawn_entry) = self.pawn_tt.get(self.pk_key()) {
-        if let Some(pawn_entry) = PAWN_TT.read().unwrap().get(self.pk_key()) {
+        if let Some(pawn_entry) = self.pawn_tt.read().unwrap().get(self.pk_key()) {
             self.eval.king_shelter =
                 pawn_entry.shelter.map(|(x, y, z)| (x as isize, y as isize, z as isize));
             self.eval.pawn_eval = pawn_entry.pawn_eval.map(|(x, y)| (x as isize, y as isize));
@@ -349,14 +383,18 @@ impl EvaluationTrait for Board {
         self.king_eval(WHITE);
         self.king_eval(BLACK);
 
-        // 11. Tempo
+        // 11. Winnable
+        self.winnable_eval();
+        self.scale_eval();
+
+        // 12. Tempo
         self.tempo(self.color());
 
         if !self.eval.pawn_hash_hit {
             let king_shelter =
                 self.eval.king_shelter.map(|(x, y, z)| (x as i16, y as i16, z as i16));
             // self.pawn_tt.set(
-            PAWN_TT.write().unwrap().set(
+            self.pawn_tt.write().unwrap().set(
                 self.pk_key(),
                 king_shelter,
                 self.eval.pawn_eval.map(|(x, y)| (x as i16, y as i16)),
@@ -364,7 +402,9 @@ impl EvaluationTrait for Board {
             );
         }
 
-        return self.calculate_score() * self.color().sign();
+        let eval = self.calculate_score() * self.color().sign();
+        self.eval_tt.set(self.key(), eval);
+        eval
     }
 
     #[inline(always)]
@@ -397,6 +437,19 @@ impl EvaluationTrait for Board {
         self.eval.psqt_eval[piece.color().idx()].1 += PSQTEvalTrait::piece_psqt(self, piece, to).1
             - PSQTEvalTrait::piece_psqt(self, piece, from).1;
     }
+
+    fn set_params(&mut self, params: SharedEvalParams) {
+        self.params = params;
+
+        // The material and PSQT sums are incremental, so they are rebuilt with the new weights
+        self.eval.inc_reset();
+        for sq in 0..64 {
+            let piece = self.squares[sq];
+            if piece != 0 {
+                self.add_eval(piece, sq);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -419,6 +472,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn params_test() {
+        use crate::engine::board::piece::KNIGHT;
+        use crate::engine::evaluation::eval_params::EvalParams;
+        use std::sync::Arc;
+
+        let mut board = Board::read_fen(SF_EVAL[0].fen);
+        let score = board.evaluation();
+
+        let mut params = EvalParams::default();
+        params.tempo += 10;
+        board.set_params(Arc::new(params.clone()));
+        assert_eq!(board.evaluation(), score + 10);
+
+        // The incremental material is rebuilt with the new weights
+        params.material[KNIGHT.arr_idx()].1 += 100;
+        board.set_params(Arc::new(params));
+        let material = board.eval.material_eval;
+        board.eval.score = [(0, 0); 2];
+        board.material_eval(WHITE);
+        board.material_eval(BLACK);
+        assert_eq!(board.eval.score, material);
+    }
+
     // #[test]
     // fn storm_sq_test() {
     //     for obj in &SF_EVAL {
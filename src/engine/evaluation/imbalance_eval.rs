@@ -46,6 +46,7 @@ impl ImbalanceEvalTrait for Board {
             self.rook_count(clr.opp()) as isize,
             self.queen_count(clr.opp()) as isize,
         ];
+        let params = &self.params;
         let mut bonus = 0;
 
         for pt1 in 1..6 {
@@ -54,8 +55,12 @@ impl ImbalanceEvalTrait for Board {
             }
 
             bonus += ours[pt1]
-                * (QUADRATIC_OURS[pt1].iter().zip(ours).map(|(x, y)| x * y).sum::<isize>()
-                    + QUADRATIC_THEIRS[pt1].iter().zip(theirs).map(|(x, y)| x * y).sum::<isize>());
+                * (params.quadratic_ours[pt1].iter().zip(ours).map(|(x, y)| x * y).sum::<isize>()
+                    + params.quadratic_theirs[pt1]
+                        .iter()
+                        .zip(theirs)
+                        .map(|(x, y)| x * y)
+                        .sum::<isize>());
             // bonus += (QUADRATIC_OURS[pt1][0] * ours[0]
             //     + QUADRATIC_OURS[pt1][1] * ours[1]
             //     + QUADRATIC_OURS[pt1][2] * ours[2]
@@ -71,7 +76,7 @@ impl ImbalanceEvalTrait for Board {
             //     * ours[pt1];
         }
 
-        bonus += 1438 * ours[0];
+        bonus += params.bishop_pair * ours[0];
         bonus /= 16;
         self.sum(clr, None, None, (bonus, bonus));
     }
@@ -2,11 +2,12 @@ use crate::engine::attacks::king::get_king_mask;
 use crate::engine::attacks::pawn::{
     get_all_pawn_left_att_mask, get_all_pawn_right_att_mask, get_pawn_att_mask,
 };
+use crate::engine::board::attack_map::AttackMapTrait;
 use crate::engine::board::board::Board;
 use crate::engine::board::color::*;
 use crate::engine::board::piece::*;
 use crate::engine::board::square::get_file;
-use crate::engine::evaluation::common_eval::{CLR_CENTER, CommonEvalTrait, KING_ATT_WEIGHT};
+use crate::engine::evaluation::common_eval::{CLR_CENTER, CommonEvalTrait};
 use crate::engine::evaluation::king_eval::KingEvalTrait;
 use crate::engine::evaluation::material_eval::MaterialEvalTrait;
 use crate::engine::evaluation::mobility_eval::MobilityEvalTrait;
@@ -32,6 +33,7 @@ pub trait InitEvalTrait {
 impl InitEvalTrait for Board {
     #[inline(always)]
     fn init(&mut self) {
+        self.att_update();
         self.determine_phase();
 
         self.pawn_init();
@@ -75,6 +77,8 @@ impl InitEvalTrait for Board {
         }
 
         for &clr in &COLORS {
+            self.eval.attack_map[clr.idx()] = self.attacks.side[clr.idx()];
+            self.eval.attacked_by_2[clr.idx()] = self.attacks.side_2[clr.idx()];
             self.eval.mobility_area[clr.idx()] = self.mobility_area(clr);
             let king_sq = self.king_sq(clr.opp());
             let king_ring = self.king_ring(clr.opp());
@@ -87,12 +91,7 @@ impl InitEvalTrait for Board {
                 let mut attckers_count = 0;
 
                 while let Some(sq) = bb.next() {
-                    let piece_mask = self.x_ray_mask(piece, sq);
-
-                    self.eval.attacked_by_2[clr.idx()] |=
-                        self.eval.attack_map[clr.idx()] & piece_mask;
-
-                    self.eval.attack_map[clr.idx()] |= piece_mask;
+                    let piece_mask = self.attacks.mask[sq];
 
                     self.eval.attacked_by[piece.idx()] |= piece_mask;
 
@@ -119,7 +118,7 @@ impl InitEvalTrait for Board {
                 }
 
                 self.eval.king_att_weight[clr.idx()] +=
-                    KING_ATT_WEIGHT[piece.arr_idx()] * attckers_count as isize;
+                    self.params.king_att_weight[piece.arr_idx()] * attckers_count as isize;
             }
         }
 
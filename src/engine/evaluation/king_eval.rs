@@ -77,13 +77,16 @@ impl KingEvalTrait for Board {
         self.sum(clr, None, None, (bonus.1, 0)); // Shelter Storm
 
         let bonus = if self.pawnless_flank(king_sq, clr) { 1 } else { 0 };
-        self.sum(clr, None, None, (17 * bonus, 95 * bonus));
+        let (mg, eg) = self.params.pawnless_flank;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.flank_attack(clr);
-        self.sum(clr, None, None, (8 * bonus, 0));
+        let (mg, eg) = self.params.flank_attack;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.eval.king_pawn_dx[clr.idx()] as isize;
-        self.sum(clr, None, None, (0, -16 * bonus));
+        let (mg, eg) = self.params.king_pawn_distance;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         // FIXME: This is not correct, the function is wrong
         let bonus = self.endgame_shelter(clr);
@@ -139,19 +142,29 @@ impl KingEvalTrait for Board {
         // println!("Queen Safe Check");
         // print_bitboard(queen_safe, None);
 
-        let v = count * weight + 69 * king_att + 185 * weak - 100 * knight_defender
-            + 148 * unsafe_checks
-            - 4 * flank_def
-            + (3 * flank_att * flank_att / 8)
-            - 873 * no_queen
-            - (6 * (self.eval.king_shelter[clr.idx()].0 - self.eval.king_shelter[clr.idx()].1) / 8) //self.shelter(clr).0 - self.shelter(clr).1
+        let queen_checks = (self.safe_check(clr, QUEEN + clr).count() as f64).min(1.45) as isize;
+        let rook_checks = (self.safe_check(clr, ROOK + clr).count() as f64).min(1.75) as isize;
+        let bishop_checks = (self.safe_check(clr, BISHOP + clr).count() as f64).min(1.50) as isize;
+        let knight_checks = (self.safe_check(clr, KNIGHT + clr).count() as f64).min(1.62) as isize;
+
+        let p = &self.params;
+        let shelter = self.eval.king_shelter[clr.idx()];
+        let v = count * weight
+            + p.danger_king_attacks * king_att
+            + p.danger_weak_squares * weak
+            + p.danger_knight_defender * knight_defender
+            + p.danger_unsafe_checks * unsafe_checks
+            + p.danger_flank_defense * flank_def
+            + (p.danger_flank_attack * flank_att * flank_att / 8)
+            + p.danger_no_queen * no_queen
+            - (p.danger_shelter * (shelter.0 - shelter.1) / 8) //self.shelter(clr).0 - self.shelter(clr).1
             + self.eval.mobility_eval[clr.idx()].0
             - self.eval.mobility_eval[clr.opp().idx()].0
-            + 37
-            + 772 * (self.safe_check(clr, QUEEN + clr).count() as f64).min(1.45) as isize
-            + 1084 * (self.safe_check(clr, ROOK + clr).count() as f64).min(1.75) as isize
-            + 645 * (self.safe_check(clr, BISHOP + clr).count() as f64).min(1.50) as isize
-            + 792 * (self.safe_check(clr, KNIGHT + clr).count() as f64).min(1.62) as isize;
+            + p.danger_offset
+            + p.danger_safe_checks[QUEEN.arr_idx()] * queen_checks
+            + p.danger_safe_checks[ROOK.arr_idx()] * rook_checks
+            + p.danger_safe_checks[BISHOP.arr_idx()] * bishop_checks
+            + p.danger_safe_checks[KNIGHT.arr_idx()] * knight_checks;
         // println!("V Score: {:?}", v);
         // println!("-------------------------------");
 
@@ -331,7 +344,7 @@ impl KingEvalTrait for Board {
         let file = get_file(sq);
         let sq = sq + ((file == 0) as usize) - ((file == 7) as usize);
 
-        for square in (sq - 1)..(sq + 2) {
+        for square in [sq - 1, sq, sq + 1] {
             // FIXME: ALL Squares forward ????????????
             let us_bb: u64 = (PAWN_FORWARD_SPANS[clr.opp().idx()][square] | Bitboard::init(square))
                 & (self.pawn_bb(clr.opp()) & !self.eval.attacked_by[(PAWN + clr).idx()]);
@@ -357,11 +370,11 @@ impl KingEvalTrait for Board {
                 // ev += BLOCKED_STORM[1][CLR_RANK[clr.idx()][them]];
 
                 if them == 0 {
-                    v += BLOCKED_STORM[0][0];
-                    ev += BLOCKED_STORM[1][0];
+                    v += self.params.blocked_storm[0][0];
+                    ev += self.params.blocked_storm[1][0];
                 } else {
-                    v += BLOCKED_STORM[0][CLR_RANK[clr.idx()][them]];
-                    ev += BLOCKED_STORM[1][CLR_RANK[clr.idx()][them]];
+                    v += self.params.blocked_storm[0][CLR_RANK[clr.idx()][them]];
+                    ev += self.params.blocked_storm[1][CLR_RANK[clr.idx()][them]];
                 }
             } else {
                 // println!("First: {:?}", get_rank(square));
@@ -369,9 +382,9 @@ impl KingEvalTrait for Board {
                 // println!("CLR_RANK: {:?}", CLR_RANK[clr.idx()]);
                 // println!("GET Rank{:?}", get_rank(them));
                 if them == 0 {
-                    v += UNBLOCKED_STORM[get_file(square)][0];
+                    v += self.params.unblocked_storm[get_file(square)][0];
                 } else {
-                    v += UNBLOCKED_STORM[get_file(square)][CLR_RANK[clr.idx()][them]];
+                    v += self.params.unblocked_storm[get_file(square)][CLR_RANK[clr.idx()][them]];
                 }
             }
         }
@@ -385,7 +398,7 @@ impl KingEvalTrait for Board {
         let file = get_file(sq);
         let sq = sq + ((file == 0) as usize) - ((file == 7) as usize);
 
-        for square in (sq - 1)..(sq + 2) {
+        for square in [sq - 1, sq, sq + 1] {
             let mut us = 0;
             let us_bb: u64 = (PAWN_FORWARD_SPANS[clr.opp().idx()][square] | Bitboard::init(square))
                 & (self.pawn_bb(clr.opp()) & !self.eval.attacked_by[(PAWN + clr).idx()]);
@@ -395,9 +408,9 @@ impl KingEvalTrait for Board {
             }
 
             if us == 0 {
-                score += WEAKNESS[get_file(square)][0];
+                score += self.params.weakness[get_file(square)][0];
             } else {
-                score += WEAKNESS[get_file(square)][CLR_RANK[clr.idx()][us]];
+                score += self.params.weakness[get_file(square)][CLR_RANK[clr.idx()][us]];
             }
         }
 
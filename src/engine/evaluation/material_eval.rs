@@ -45,12 +45,12 @@ impl MaterialEvalTrait for Board {
     #[inline(always)]
     fn inc_non_pawn_material_eval(&mut self, clr: Color) -> isize {
         self.eval.material_eval[clr.idx()].0
-            - (self.piece_count(PAWN + clr) as isize * PIECE_MATERIAL[PAWN.arr_idx()].0)
+            - (self.piece_count(PAWN + clr) as isize * self.params.material[PAWN.arr_idx()].0)
     }
 
     #[inline(always)]
     fn piece_material(&mut self, piece: Piece) -> (isize, isize) {
-        PIECE_MATERIAL[piece.arr_idx()]
+        self.params.material[piece.arr_idx()]
     }
 }
 
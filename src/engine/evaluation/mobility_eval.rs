@@ -45,7 +45,7 @@ impl MobilityEvalTrait for Board {
             let piece = pce + clr;
             let mut bb = self.bb(piece);
             while let Some(sq) = bb.next() {
-                let safe_squares = (self.x_ray_mask(piece, sq) & area).count();
+                let safe_squares = (self.attacks.mask[sq] & area).count();
                 let bonus = self.mobility_bonus(piece, safe_squares);
                 self.sum(clr, Some(sq), Some(piece), bonus);
             }
@@ -62,10 +62,10 @@ impl MobilityEvalTrait for Board {
     #[inline(always)]
     fn mobility_bonus(&mut self, piece: Piece, safe_squares: usize) -> (isize, isize) {
         match piece.kind() {
-            KNIGHT => KNIGHT_MOBILITY[safe_squares],
-            BISHOP => BISHOP_MOBILITY[safe_squares],
-            ROOK => ROOK_MOBILITY[safe_squares],
-            QUEEN => QUEEN_MOBILITY[safe_squares],
+            KNIGHT => self.params.knight_mobility[safe_squares],
+            BISHOP => self.params.bishop_mobility[safe_squares],
+            ROOK => self.params.rook_mobility[safe_squares],
+            QUEEN => self.params.queen_mobility[safe_squares],
             _ => panic!("There is other peace that was not expected here"),
         }
     }
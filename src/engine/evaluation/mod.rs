@@ -1,10 +1,13 @@
+pub mod breakdown_eval;
 pub mod common_eval;
+pub mod eval_params;
 pub mod evaluation;
 pub mod imbalance_eval;
 pub mod init_eval;
 pub mod king_eval;
 pub mod material_eval;
 pub mod mobility_eval;
+pub mod nnue;
 pub mod passed_pawn_eval;
 pub mod pawn_eval;
 pub mod piece_eval;
@@ -14,3 +17,4 @@ pub mod tempo_eval;
 pub mod test_evaluation;
 pub mod threats_eval;
 pub mod trace_eval;
+pub mod winnable_eval;
@@ -0,0 +1,404 @@
+// NOTE: NNUE evaluation (HalfKA, mirrored to the king on the files a-d)
+//
+// Every side sees the board from its own point of view (flipped vertically for black) and
+// mirrored horizontally if its king is on the files e-h. A feature is a (king bucket, our or
+// their piece, square) triple, so there are 32 * 768 features. The feature transformer sums
+// the weights of the active features into an accumulator of NNUE_HIDDEN values per side,
+// which is kept up to date incrementally as pieces are added, cleared and moved.
+//
+// The output is a single neuron over the clipped (0..QA) accumulators, the side to move first:
+//     eval = (out_bias + sum(crelu(us) * w_us) + sum(crelu(them) * w_them)) * SCALE / (QA * QB)
+//
+// File format (little endian):
+//     "FRNN", version: u32, hidden: u32,
+//     feature weights: [i16; features * hidden] (feature major), feature biases: [i16; hidden],
+//     output weights: [i16; 2 * hidden], output bias: i32 (in QA * QB units)
+
+use std::fmt::{self, Debug};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, Color, ColorTrait, WHITE};
+use crate::engine::board::piece::{Piece, PieceTrait};
+use crate::engine::misc::bitboard::Iterator;
+
+pub const NNUE_HIDDEN: usize = 256;
+pub const NNUE_FEATURES: usize = 32 * 768;
+
+const NNUE_MAGIC: &[u8; 4] = b"FRNN";
+const NNUE_VERSION: u32 = 1;
+const QA: i32 = 255;
+const QB: i32 = 64;
+const SCALE: i32 = 400;
+
+// Network of the engine, boards cloned from each other share the same network
+pub type SharedNetwork = Arc<Network>;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Network {
+    pub ft_weights: Vec<i16>,
+    pub ft_bias: Vec<i16>,
+    pub out_weights: Vec<i16>,
+    pub out_bias: i32,
+}
+
+impl Debug for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Network {{ features: {}, hidden: {} }}", NNUE_FEATURES, NNUE_HIDDEN)
+    }
+}
+
+impl Network {
+    pub fn zeroed() -> Self {
+        Self {
+            ft_weights: vec![0; NNUE_FEATURES * NNUE_HIDDEN],
+            ft_bias: vec![0; NNUE_HIDDEN],
+            out_weights: vec![0; 2 * NNUE_HIDDEN],
+            out_bias: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 12 || &bytes[0..4] != NNUE_MAGIC {
+            return Err("Not a network file".to_string());
+        }
+        let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        let hidden = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        if version != NNUE_VERSION {
+            return Err(format!("Unsupported network version: {}", version));
+        }
+        if hidden != NNUE_HIDDEN {
+            return Err(format!("Expected {} hidden neurons, found {}", NNUE_HIDDEN, hidden));
+        }
+
+        let values = (NNUE_FEATURES + 3) * NNUE_HIDDEN;
+        if bytes.len() != 12 + 2 * values + 4 {
+            return Err(format!("Invalid network size: {} bytes", bytes.len()));
+        }
+
+        let mut values =
+            bytes[12..12 + 2 * values].chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]));
+        let mut network = Self::zeroed();
+        let tensors = [&mut network.ft_weights, &mut network.ft_bias, &mut network.out_weights];
+        for tensor in tensors {
+            tensor.iter_mut().zip(&mut values).for_each(|(w, v)| *w = v);
+        }
+        network.out_bias = i32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+        Ok(network)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + 2 * (NNUE_FEATURES + 3) * NNUE_HIDDEN);
+        bytes.extend(NNUE_MAGIC);
+        bytes.extend(NNUE_VERSION.to_le_bytes());
+        bytes.extend((NNUE_HIDDEN as u32).to_le_bytes());
+        for tensor in [&self.ft_weights, &self.ft_bias, &self.out_weights] {
+            tensor.iter().for_each(|w| bytes.extend(w.to_le_bytes()));
+        }
+        bytes.extend(self.out_bias.to_le_bytes());
+        bytes
+    }
+
+    ///
+    /// Output of the network from the side to move's point of view (written as plain loops
+    /// over fixed size slices, so that they are vectorized for any target CPU)
+    ///
+    pub fn output(&self, us: &[i16; NNUE_HIDDEN], them: &[i16; NNUE_HIDDEN]) -> isize {
+        let (w_us, w_them) = self.out_weights.split_at(NNUE_HIDDEN);
+        let mut sum = 0;
+        for (acc, weights) in [(us, w_us), (them, w_them)] {
+            for (&v, &w) in acc.iter().zip(weights) {
+                sum += (v as i32).clamp(0, QA) * w as i32;
+            }
+        }
+        ((sum + self.out_bias) as i64 * SCALE as i64 / (QA * QB) as i64) as isize
+    }
+
+    #[inline(always)]
+    fn column(&self, feature: usize) -> &[i16] {
+        &self.ft_weights[feature * NNUE_HIDDEN..(feature + 1) * NNUE_HIDDEN]
+    }
+}
+
+///
+/// Index of the feature of a piece for the side, with its king on the square
+///
+#[inline(always)]
+pub fn feature(side: Color, king_sq: usize, piece: Piece, sq: usize) -> usize {
+    let flip = if side == WHITE { 0 } else { 56 };
+    let mirror = if king_sq & 7 >= 4 { 7 } else { 0 };
+    let their = (piece.color() != side) as usize;
+    king_bucket(side, king_sq) * 768 + (their * 6 + piece.arr_idx()) * 64 + (sq ^ flip ^ mirror)
+}
+
+#[inline(always)]
+pub fn king_bucket(side: Color, king_sq: usize) -> usize {
+    let sq = king_sq ^ if side == WHITE { 0 } else { 56 };
+    (sq >> 3) * 4 + if sq & 7 >= 4 { 7 - (sq & 7) } else { sq & 7 }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(align(64))]
+pub struct Accumulator {
+    pub values: [[i16; NNUE_HIDDEN]; 2],
+    // A side has to be refreshed from scratch (e.g. its king changed the bucket or the mirror)
+    pub computed: [bool; 2],
+}
+
+impl Accumulator {
+    pub fn init() -> Self {
+        Self { values: [[0; NNUE_HIDDEN]; 2], computed: [false; 2] }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Nnue {
+    pub network: Option<SharedNetwork>,
+    // Updates are off without a network, and while a move is undone
+    pub active: bool,
+    pub acc: Box<Accumulator>,
+    // Accumulators before every move that was made, restored when undoing it
+    pub stack: Vec<Accumulator>,
+}
+
+impl Nnue {
+    pub fn init() -> Self {
+        Self { network: None, active: false, acc: Box::new(Accumulator::init()), stack: Vec::new() }
+    }
+}
+
+pub trait NnueTrait {
+    fn set_network(&mut self, network: Option<SharedNetwork>);
+    fn nnue_eval(&mut self) -> isize;
+    fn nnue_refresh(&mut self, side: Color);
+
+    fn nnue_add(&mut self, piece: Piece, sq: usize);
+    fn nnue_clear(&mut self, piece: Piece, sq: usize);
+    fn nnue_quiet(&mut self, piece: Piece, from: usize, to: usize);
+    fn nnue_push(&mut self);
+    fn nnue_pop(&mut self);
+}
+
+impl NnueTrait for Board {
+    ///
+    /// Switches the board to the network (or to the classical evaluation if none)
+    ///
+    fn set_network(&mut self, network: Option<SharedNetwork>) {
+        self.nnue.active = network.is_some();
+        self.nnue.network = network;
+        self.nnue.acc.computed = [false; 2];
+        self.nnue.stack.clear();
+    }
+
+    fn nnue_eval(&mut self) -> isize {
+        for side in [WHITE, BLACK] {
+            if !self.nnue.acc.computed[side.idx()] {
+                self.nnue_refresh(side);
+            }
+        }
+
+        let Some(network) = &self.nnue.network else {
+            return 0;
+        };
+        let values = &self.nnue.acc.values;
+        let color = self.color();
+        network.output(&values[color.idx()], &values[color.opp().idx()])
+    }
+
+    fn nnue_refresh(&mut self, side: Color) {
+        let Some(network) = &self.nnue.network else {
+            return;
+        };
+        let king_sq = self.king_sq(side);
+        debug_assert!(king_sq < 64, "The side has no king");
+
+        let mut bb = self.occ_bb(WHITE) | self.occ_bb(BLACK);
+        let values = &mut self.nnue.acc.values[side.idx()];
+        values.copy_from_slice(&network.ft_bias);
+        while let Some(sq) = bb.next() {
+            let column = network.column(feature(side, king_sq, self.squares[sq], sq));
+            values.iter_mut().zip(column).for_each(|(v, &w)| *v = v.wrapping_add(w));
+        }
+        self.nnue.acc.computed[side.idx()] = true;
+    }
+
+    #[inline(always)]
+    fn nnue_add(&mut self, piece: Piece, sq: usize) {
+        if !self.nnue.active {
+            return;
+        }
+        if piece.is_king() {
+            self.nnue.acc.computed[piece.color().idx()] = false;
+        }
+
+        let Some(network) = &self.nnue.network else {
+            return;
+        };
+        for side in [WHITE, BLACK] {
+            if self.nnue.acc.computed[side.idx()] {
+                let column = network.column(feature(side, self.king_sq(side), piece, sq));
+                let values = &mut self.nnue.acc.values[side.idx()];
+                values.iter_mut().zip(column).for_each(|(v, &w)| *v = v.wrapping_add(w));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn nnue_clear(&mut self, piece: Piece, sq: usize) {
+        if !self.nnue.active {
+            return;
+        }
+        if piece.is_king() {
+            self.nnue.acc.computed[piece.color().idx()] = false;
+        }
+
+        let Some(network) = &self.nnue.network else {
+            return;
+        };
+        for side in [WHITE, BLACK] {
+            if self.nnue.acc.computed[side.idx()] {
+                let column = network.column(feature(side, self.king_sq(side), piece, sq));
+                let values = &mut self.nnue.acc.values[side.idx()];
+                values.iter_mut().zip(column).for_each(|(v, &w)| *v = v.wrapping_sub(w));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn nnue_quiet(&mut self, piece: Piece, from: usize, to: usize) {
+        if !self.nnue.active {
+            return;
+        }
+        let color = piece.color();
+        let mirrored = (from & 7 >= 4) != (to & 7 >= 4);
+        if piece.is_king() && (mirrored || king_bucket(color, from) != king_bucket(color, to)) {
+            self.nnue.acc.computed[color.idx()] = false;
+        }
+
+        let Some(network) = &self.nnue.network else {
+            return;
+        };
+        for side in [WHITE, BLACK] {
+            if self.nnue.acc.computed[side.idx()] {
+                let king_sq = self.king_sq(side);
+                let add = network.column(feature(side, king_sq, piece, to));
+                let sub = network.column(feature(side, king_sq, piece, from));
+                let values = &mut self.nnue.acc.values[side.idx()];
+                for ((v, &a), &s) in values.iter_mut().zip(add).zip(sub) {
+                    *v = v.wrapping_add(a).wrapping_sub(s);
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn nnue_push(&mut self) {
+        if self.nnue.active {
+            self.nnue.stack.push((*self.nnue.acc).clone());
+        }
+    }
+
+    #[inline(always)]
+    fn nnue_pop(&mut self) {
+        if !self.nnue.active {
+            return;
+        }
+        match self.nnue.stack.pop() {
+            Some(acc) => *self.nnue.acc = acc,
+            // The move was made before the network was set
+            None => self.nnue.acc.computed = [false; 2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::evaluation::evaluation::EvaluationTrait;
+    use crate::engine::misc::const_utility::{FEN_CASTLE_ONE, FEN_POS_FOUR, FEN_POS_THREE};
+    use crate::engine::move_generator::make_move::BoardMoveTrait;
+    use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_network() -> SharedNetwork {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut network = Network::zeroed();
+        network.ft_weights.iter_mut().for_each(|w| *w = rng.random_range(-64..64));
+        network.ft_bias.iter_mut().for_each(|w| *w = rng.random_range(0..128));
+        network.out_weights.iter_mut().for_each(|w| *w = rng.random_range(-64..64));
+        network.out_bias = 1000;
+        Arc::new(network)
+    }
+
+    fn refreshed(board: &Board) -> Accumulator {
+        let mut board = board.clone();
+        board.nnue.acc.computed = [false; 2];
+        board.nnue_eval();
+        (*board.nnue.acc).clone()
+    }
+
+    #[test]
+    fn test_feature() {
+        // The same position seen from both sides, and mirrored with the king on the e-file
+        assert_eq!(king_bucket(WHITE, 0), 0);
+        assert_eq!(king_bucket(BLACK, 56), 0);
+        assert_eq!(king_bucket(WHITE, 7), 0);
+        assert_eq!(king_bucket(WHITE, 4), 3);
+        assert_eq!(feature(WHITE, 4, 2, 12), feature(BLACK, 60, 3, 52));
+        assert_eq!(feature(WHITE, 4, 2, 12), 3 * 768 + 11);
+        assert!((0..64).all(|k| feature(BLACK, k, 13, 63) < NNUE_FEATURES));
+    }
+
+    #[test]
+    fn test_network_bytes() {
+        let network = random_network();
+        let bytes = network.to_bytes();
+        assert_eq!(Network::from_bytes(&bytes).as_ref(), Ok(network.as_ref()));
+        assert!(Network::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Network::from_bytes(b"invalid").is_err());
+    }
+
+    #[test]
+    fn test_incremental_accumulator() {
+        let network = random_network();
+        for fen in [FEN_CASTLE_ONE, FEN_POS_THREE, FEN_POS_FOUR] {
+            let mut board = Board::read_fen(fen);
+            board.set_network(Some(Arc::clone(&network)));
+            let root = board.evaluation();
+            let root_acc = (*board.nnue.acc).clone();
+
+            // Every move (and one reply), including captures, castling and promotions
+            for (mv, _) in board.gen_moves() {
+                if !board.make_move(&mv) {
+                    continue;
+                }
+                board.nnue_eval();
+                assert_eq!(*board.nnue.acc, refreshed(&board), "{:?}", mv);
+
+                if let Some(&(reply, _)) = board.gen_moves().last()
+                    && board.make_move(&reply)
+                {
+                    board.nnue_eval();
+                    assert_eq!(*board.nnue.acc, refreshed(&board), "{:?} {:?}", mv, reply);
+                    board.undo_move();
+                }
+                board.undo_move();
+                assert_eq!(*board.nnue.acc, root_acc);
+            }
+            assert_eq!(board.inc_eval(), root);
+        }
+    }
+}
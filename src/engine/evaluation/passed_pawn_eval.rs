@@ -37,9 +37,11 @@ impl PassedPawnEvalTrait for Board {
 
             let passed_file = self.passed_file(sq);
             self.sum(clr, Some(sq), Some(piece), (0, king_proximity));
-            self.sum(clr, Some(sq), Some(piece), PASSED_PAWN_REW[clr.idx()][get_rank(sq)]);
+            let rank = CLR_RANK[clr.idx()][get_rank(sq)];
+            self.sum(clr, Some(sq), Some(piece), self.params.passed_rank[rank]);
             self.sum(clr, Some(sq), Some(piece), (passed_block, passed_block));
-            self.sum(clr, Some(sq), Some(piece), (-11 * passed_file, -8 * passed_file));
+            let (mg, eg) = self.params.passed_file;
+            self.sum(clr, Some(sq), Some(piece), (mg * passed_file, eg * passed_file));
         }
     }
 
@@ -121,6 +123,7 @@ impl PassedPawnEvalTrait for Board {
             unsafe_bb = 1;
         }
 
+        let block = self.params.passed_block;
         let mut k = 0;
 
         // println!("Unsafe: {:?}", unsafe_bb.count());
@@ -129,15 +132,15 @@ impl PassedPawnEvalTrait for Board {
         // println!("is_defended1: {:?}", is_defended1);
 
         if unsafe_bb == 0 && wunsafe_bb == 0 {
-            k = 35;
+            k = block[0];
         } else if unsafe_bb == 0 {
-            k = 20;
+            k = block[1];
         } else if !is_unsafe1 {
-            k = 9;
+            k = block[2];
         }
 
         if is_defended1 {
-            k += 5;
+            k += block[3];
         }
 
         return k * (weight as isize);
@@ -156,13 +159,14 @@ impl PassedPawnEvalTrait for Board {
         let weight = (5 * clr_rank - 13) as isize;
 
         let front_sq = self.front_sq(sq, clr);
+        let proximity = self.params.passed_king_proximity;
 
-        score += (self.king_dist(clr.opp(), front_sq).min(5) * 19 / 4) as isize * weight;
-        score -= (self.king_dist(clr, front_sq).min(5) * 2) as isize * weight;
+        score += (self.king_dist(clr.opp(), front_sq).min(5) as isize * proximity[0] / 4) * weight;
+        score -= (self.king_dist(clr, front_sq).min(5) as isize * proximity[1]) * weight;
 
         // Consider another push if the next square is the queening square
         if clr_rank != 6 {
-            score -= self.king_dist(clr, front_sq).min(5) as isize * weight;
+            score -= self.king_dist(clr, front_sq).min(5) as isize * proximity[2] * weight;
         }
         score
     }
@@ -52,20 +52,20 @@ impl PawnEvalTrait for Board {
         // println!("Connected: {:?}", self.blocked_pawn_5th_6th_rank(sq, clr));
         let mut pawn_eval = self.eval.pawn_eval;
         if self.doubled_isolated_pawn(sq, clr) {
-            self.sum(clr, Some(sq), None, (-11, -56));
-            self.sum_into_arr(clr, Some(sq), None, (-11, -56), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.doubled_isolated);
+            self.sum_into_arr(clr, Some(sq), None, self.params.doubled_isolated, &mut pawn_eval);
         } else if self.isolated_pawn(sq, clr) {
-            self.sum(clr, Some(sq), None, (-5, -15));
-            self.sum_into_arr(clr, Some(sq), None, (-5, -15), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.isolated);
+            self.sum_into_arr(clr, Some(sq), None, self.params.isolated, &mut pawn_eval);
         } else if self.backward_pawn(sq, clr) {
-            self.sum(clr, Some(sq), None, (-9, -24));
-            self.sum_into_arr(clr, Some(sq), None, (-9, -24), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.backward);
+            self.sum_into_arr(clr, Some(sq), None, self.params.backward, &mut pawn_eval);
         }
 
         // FIXME: Not correct (Needs to check how many doubled are on the same file)
         if self.doubled_pawn(sq, clr) {
-            self.sum(clr, Some(sq), None, (-11, -56));
-            self.sum_into_arr(clr, Some(sq), None, (-11, -56), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.doubled);
+            self.sum_into_arr(clr, Some(sq), None, self.params.doubled, &mut pawn_eval);
         }
 
         if self.connected_pawn(sq, clr) {
@@ -79,21 +79,21 @@ impl PawnEvalTrait for Board {
         }
 
         if self.weak_unopposed_pawn(sq, clr) {
-            self.sum(clr, Some(sq), None, (-13, -27));
-            self.sum_into_arr(clr, Some(sq), None, (-13, -27), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.weak_unopposed);
+            self.sum_into_arr(clr, Some(sq), None, self.params.weak_unopposed, &mut pawn_eval);
         }
 
         if self.weak_lever(sq, clr) {
-            self.sum(clr, Some(sq), None, (0, -56));
-            self.sum_into_arr(clr, Some(sq), None, (0, -56), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.weak_lever);
+            self.sum_into_arr(clr, Some(sq), None, self.params.weak_lever, &mut pawn_eval);
         }
 
         if self.blocked_pawn_5th_6th_rank(sq, clr) == 1 {
-            self.sum(clr, Some(sq), None, (-11, -4));
-            self.sum_into_arr(clr, Some(sq), None, (-11, -4), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.blocked_pawn[0]);
+            self.sum_into_arr(clr, Some(sq), None, self.params.blocked_pawn[0], &mut pawn_eval);
         } else if self.blocked_pawn_5th_6th_rank(sq, clr) == 2 {
-            self.sum(clr, Some(sq), None, (-3, 4));
-            self.sum_into_arr(clr, Some(sq), None, (-3, 4), &mut pawn_eval);
+            self.sum(clr, Some(sq), None, self.params.blocked_pawn[1]);
+            self.sum_into_arr(clr, Some(sq), None, self.params.blocked_pawn[1], &mut pawn_eval);
         }
         // println!("self.eval.pawn_eval: {:?}", self.eval.pawn_eval);
         // println!("pawn_eval: {:?}", pawn_eval);
@@ -155,13 +155,14 @@ impl PawnEvalTrait for Board {
             return 0;
         }
 
-        let seed = [0, 7, 8, 12, 29, 48, 86, 0];
+        let seed = self.params.connected_seed;
         let op = self.opposed_pawn(sq, clr);
         let ph = self.phalanx_pawn(sq, clr);
         let su = self.supported_pawn(sq, clr);
         // let bl = self.blocked_pawn(sq, clr, self.pawn_bb(clr.opp()));
 
-        return seed[r] * (2 + ph as isize - op as isize) + 21 * su as isize;
+        return seed[r] * (2 + ph as isize - op as isize)
+            + self.params.connected_supported * su as isize;
     }
 
     #[inline(always)]
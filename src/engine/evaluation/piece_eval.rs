@@ -42,31 +42,38 @@ impl PieceEvalTrait for Board {
     #[inline(always)]
     fn piece_eval(&mut self, clr: Color) {
         let bonus = self.minor_behind_pawn(clr);
-        self.sum(clr, None, None, (18 * bonus, 3 * bonus));
+        let (mg, eg) = self.params.minor_behind_pawn;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.bishop_pawns(clr);
-        self.sum(clr, None, None, (-3 * bonus, -5 * bonus));
+        let (mg, eg) = self.params.bishop_pawns;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.bishop_xray_pawns(clr);
-        self.sum(clr, None, None, (-4 * bonus, -5 * bonus));
+        let (mg, eg) = self.params.bishop_xray_pawns;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         self.rook_on_queen_file(clr);
 
         let bonus = self.rook_on_king_ring(clr);
-        self.sum(clr, None, None, (16 * bonus, 0));
+        let (mg, eg) = self.params.rook_on_king_ring;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.bishop_on_king_ring(clr);
-        self.sum(clr, None, None, (24 * bonus, 0));
+        let (mg, eg) = self.params.bishop_on_king_ring;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         self.trapped_rook(clr);
         // FIXME, If Castle is not awailable add 2 * 55 / 2 * 13
         // self.sum(clr, None, None, (-55 * bonus, -13 * bonus));
 
         let bonus = self.weak_queen(clr);
-        self.sum(clr, None, None, (-56 * bonus, -15 * bonus));
+        let (mg, eg) = self.params.weak_queen;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.queen_infaltration(clr);
-        self.sum(clr, None, None, (-2 * bonus, 14 * bonus));
+        let (mg, eg) = self.params.queen_infiltration;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         self.king_protector(clr);
 
@@ -75,10 +82,12 @@ impl PieceEvalTrait for Board {
         let bonus = (self.rook_bb(clr)
             & (self.eval.open_file[clr.idx()] & self.eval.open_file[clr.opp().idx()]))
         .count() as isize;
-        self.sum(clr, None, None, (48 * bonus, 29 * bonus));
+        let (mg, eg) = self.params.rook_on_open_file;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = (self.rook_bb(clr) & self.eval.open_file[clr.idx()]).count() as isize;
-        self.sum(clr, None, None, (19 * bonus, 7 * bonus));
+        let (mg, eg) = self.params.rook_on_semi_open_file;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
     }
 
     #[inline(always)]
@@ -148,7 +157,8 @@ impl PieceEvalTrait for Board {
                 if self.state.castling.long(clr) != 0 || self.state.castling.short(clr) != 0 {
                     castling = 1;
                 }
-                self.sum(clr, Some(sq), Some(ROOK + clr), (-55 * castling, -13 * castling));
+                let (mg, eg) = self.params.trapped_rook;
+                self.sum(clr, Some(sq), Some(ROOK + clr), (mg * castling, eg * castling));
             }
         }
     }
@@ -177,13 +187,15 @@ impl PieceEvalTrait for Board {
         let mut bb = self.knight_bb(clr);
         while let Some(sq) = bb.next() {
             let dx = self.king_dist(clr, sq) as isize;
-            self.sum(clr, Some(sq), Some(KNIGHT), (-8 * dx, -9 * dx));
+            let (mg, eg) = self.params.knight_protector;
+            self.sum(clr, Some(sq), Some(KNIGHT), (mg * dx, eg * dx));
         }
 
         let mut bb = self.bishop_bb(clr);
         while let Some(sq) = bb.next() {
             let dx = self.king_dist(clr, sq) as isize;
-            self.sum(clr, Some(sq), Some(BISHOP), (-6 * dx, -9 * dx));
+            let (mg, eg) = self.params.bishop_protector;
+            self.sum(clr, Some(sq), Some(BISHOP), (mg * dx, eg * dx));
         }
     }
 
@@ -195,16 +207,18 @@ impl PieceEvalTrait for Board {
                 & self.x_ray_mask(KNIGHT + clr, sq)
                 & !self.occ_bb(clr);
             if !self.eval.outpost[clr.idx()].is_set(sq) && reachable_bb > 0 {
-                self.sum(clr, Some(sq), Some(KNIGHT + clr), (31, 22));
+                self.sum(clr, Some(sq), Some(KNIGHT + clr), self.params.reachable_outpost);
                 break;
             }
         }
 
         let bonus = (self.knight_bb(clr) & self.eval.outpost[clr.idx()]).count() as isize;
-        self.sum(clr, None, Some(KNIGHT + clr), (bonus * 56, bonus * 36));
+        let (mg, eg) = self.params.knight_outpost;
+        self.sum(clr, None, Some(KNIGHT + clr), (bonus * mg, bonus * eg));
 
         let bonus = (self.bishop_bb(clr) & self.eval.outpost[clr.idx()]).count() as isize;
-        self.sum(clr, None, Some(BISHOP + clr), (bonus * 30, bonus * 23));
+        let (mg, eg) = self.params.bishop_outpost;
+        self.sum(clr, None, Some(BISHOP + clr), (bonus * mg, bonus * eg));
         // NOTE: FIXME: NOT FULL EVAL BUT AN OK ONE
         // Only the +2 is missing
     }
@@ -215,7 +229,7 @@ impl PieceEvalTrait for Board {
         let all_queens = self.queen_bb(clr) | self.queen_bb(clr.opp());
         while let Some(sq) = bb.next() {
             if all_queens & FILE_BITBOARD[get_file(sq)] != 0 {
-                self.sum(clr, Some(sq), Some(ROOK), (6, 11));
+                self.sum(clr, Some(sq), Some(ROOK), self.params.rook_on_queen_file);
             }
         }
     }
@@ -236,7 +250,7 @@ impl PieceEvalTrait for Board {
         let mut count = 0;
         let mut bb = self.rook_bb(clr) & !self.eval.king_att_count_pieces[clr.idx()];
         while let Some(sq) = bb.next() {
-            if self.eval.king_ring[clr.opp().idx()] & RANK_BITBOARD[get_file(sq)] != 0 {
+            if self.eval.king_ring[clr.opp().idx()] & FILE_BITBOARD[get_file(sq)] != 0 {
                 count += 1;
             }
         }
@@ -89,7 +89,7 @@ impl PSQTEvalTrait for Board {
     #[inline(always)]
     fn piece_psqt(&mut self, piece: Piece, sq: usize) -> (isize, isize) {
         let fixed_sq = CLR_SQ[piece.color().idx()][sq];
-        PSQT[piece.arr_idx()][fixed_sq]
+        self.params.psqt[piece.arr_idx()][fixed_sq]
     }
 }
 
@@ -11,7 +11,7 @@ pub trait TempoEvalTrait {
 impl TempoEvalTrait for Board {
     #[inline(always)]
     fn tempo(&mut self, clr: Color) {
-        self.sum(clr, None, None, (TEMPO_WT, TEMPO_WT));
+        self.sum(clr, None, None, (self.params.tempo, self.params.tempo));
     }
 }
 
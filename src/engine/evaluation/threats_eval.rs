@@ -36,29 +36,36 @@ impl ThreatsEvalTrait for Board {
     #[inline(always)]
     fn threats_eval(&mut self, clr: Color) {
         let bonus = self.hanging(clr).count() as isize;
-        self.sum(clr, None, None, (69 * bonus, 36 * bonus));
+        let (mg, eg) = self.params.hanging;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         if self.king_threat(clr) > 0 {
-            self.sum(clr, None, Some(KING + clr), (24, 89));
+            self.sum(clr, None, Some(KING + clr), self.params.king_threat);
         }
 
         let bonus = self.pawn_push_threat(clr).count() as isize;
-        self.sum(clr, None, None, (48 * bonus, 39 * bonus));
+        let (mg, eg) = self.params.pawn_push_threat;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.threat_safe_pawn(clr).count() as isize;
-        self.sum(clr, None, None, (173 * bonus, 94 * bonus));
+        let (mg, eg) = self.params.threat_safe_pawn;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.slider_on_queen(clr);
-        self.sum(clr, None, None, (60 * bonus, 18 * bonus));
+        let (mg, eg) = self.params.slider_on_queen;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.knight_on_queen(clr);
-        self.sum(clr, None, None, (16 * bonus, 11 * bonus));
+        let (mg, eg) = self.params.knight_on_queen;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.restricted(clr).count() as isize;
-        self.sum(clr, None, None, (7 * bonus, 7 * bonus));
+        let (mg, eg) = self.params.restricted;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         let bonus = self.weak_queen_protection(clr).count() as isize;
-        self.sum(clr, None, None, (14 * bonus, 0));
+        let (mg, eg) = self.params.weak_queen_protection;
+        self.sum(clr, None, None, (mg * bonus, eg * bonus));
 
         self.minor_threat(clr);
         self.rook_threat(clr);
@@ -102,7 +109,7 @@ impl ThreatsEvalTrait for Board {
 
         while let Some(sq) = bb.next() {
             let piece = self.piece_sq(sq);
-            self.sum(clr, Some(sq), Some(piece), MINOR_THREAT[piece.arr_idx()]);
+            self.sum(clr, Some(sq), Some(piece), self.params.minor_threat[piece.arr_idx()]);
         }
     }
 
@@ -113,7 +120,7 @@ impl ThreatsEvalTrait for Board {
 
         while let Some(sq) = bb.next() {
             let piece = self.piece_sq(sq);
-            self.sum(clr, Some(sq), Some(piece), ROOK_THREAT[piece.arr_idx()]);
+            self.sum(clr, Some(sq), Some(piece), self.params.rook_threat[piece.arr_idx()]);
         }
     }
 
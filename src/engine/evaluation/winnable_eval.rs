@@ -0,0 +1,202 @@
+use crate::engine::attacks::king::get_king_mask;
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, Color, ColorTrait, WHITE};
+use crate::engine::board::piece::{BISHOP, PieceTrait, ROOK};
+use crate::engine::board::square::{get_file, get_rank};
+use crate::engine::evaluation::common_eval::CommonEvalTrait;
+use crate::engine::evaluation::material_eval::MaterialEvalTrait;
+use crate::engine::misc::bitboard::BitboardTrait;
+use crate::engine::misc::const_utility::FILE_BITBOARD;
+
+pub const SCALE_FACTOR_NORMAL: isize = 64;
+
+pub static WINNABLE_WT: [isize; 8] = [9, 12, 9, 21, 24, 51, -43, -110];
+
+static DARK_SQUARES: u64 = 0xAA55_AA55_AA55_AA55;
+static QUEEN_SIDE: u64 = FILE_BITBOARD[0] | FILE_BITBOARD[1] | FILE_BITBOARD[2] | FILE_BITBOARD[3];
+
+pub trait WinnableEvalTrait {
+    fn winnable_eval(&mut self);
+    fn scale_eval(&mut self);
+    fn winnable(&mut self) -> isize;
+    fn scale_factor(&mut self, eg: isize) -> isize;
+    fn opposite_bishops(&self) -> bool;
+    fn sum_signed(&mut self, value: (isize, isize));
+}
+
+impl WinnableEvalTrait for Board {
+    ///
+    /// Lowers the advantage of the side ahead in positions that are hard to win (the
+    /// initiative)
+    ///
+    #[inline(always)]
+    fn winnable_eval(&mut self) {
+        let mg = self.eval.score[WHITE.idx()].0 - self.eval.score[BLACK.idx()].0;
+        let eg = self.eval.score[WHITE.idx()].1 - self.eval.score[BLACK.idx()].1;
+        let complexity = self.winnable();
+
+        // The middlegame and endgame advantage can shrink, but never change sides
+        let u = mg.signum() * (complexity + 50).min(0).max(-mg.abs());
+        let v = eg.signum() * complexity.max(-eg.abs());
+        self.eval.winnable = (u, v);
+        self.sum_signed((u, v));
+    }
+
+    ///
+    /// Scales the endgame score down for drawish material
+    ///
+    #[inline(always)]
+    fn scale_eval(&mut self) {
+        let eg = self.eval.score[WHITE.idx()].1 - self.eval.score[BLACK.idx()].1;
+        self.eval.scale_factor = self.scale_factor(eg);
+        let scaled = eg * self.eval.scale_factor / SCALE_FACTOR_NORMAL;
+        self.sum_signed((0, scaled - eg));
+    }
+
+    ///
+    /// Complexity of the position: passed pawns, pawns on both flanks, a king ahead of the
+    /// enemy king or a pure pawn ending make it easier for the side ahead to win
+    ///
+    #[inline(always)]
+    fn winnable(&mut self) -> isize {
+        let [passed, pawns, outflanking, both_flanks, infiltration, pure_pawn, unwinnable, offset] =
+            self.params.winnable;
+        let pawn_bb = self.pawn_bb(WHITE) | self.pawn_bb(BLACK);
+        let (white_king, black_king) = (self.king_sq(WHITE), self.king_sq(BLACK));
+
+        let passed_cnt = (self.eval.candidate_passed[WHITE.idx()]
+            | self.eval.candidate_passed[BLACK.idx()])
+        .count() as isize;
+        let pawn_cnt = pawn_bb.count() as isize;
+        let king_outflanking = get_file(white_king).abs_diff(get_file(black_king)) as isize
+            - get_rank(white_king).abs_diff(get_rank(black_king)) as isize;
+        let is_both_flanks = pawn_bb & QUEEN_SIDE != 0 && pawn_bb & !QUEEN_SIDE != 0;
+        let is_infiltration = get_rank(white_king) > 3 || get_rank(black_king) < 4;
+        let is_pure_pawn =
+            self.non_pawn_material_eval(WHITE) + self.non_pawn_material_eval(BLACK) == 0;
+        let is_unwinnable = king_outflanking < 0 && !is_both_flanks;
+
+        passed * passed_cnt
+            + pawns * pawn_cnt
+            + outflanking * king_outflanking
+            + both_flanks * is_both_flanks as isize
+            + infiltration * is_infiltration as isize
+            + pure_pawn * is_pure_pawn as isize
+            + unwinnable * is_unwinnable as isize
+            + offset
+    }
+
+    ///
+    /// Scale factor of the endgame score (in 1/64) for the side ahead in the endgame
+    ///
+    #[inline(always)]
+    fn scale_factor(&mut self, eg: isize) -> isize {
+        let strong: Color = if eg > 0 { WHITE } else { BLACK };
+        let weak = strong.opp();
+        let bishop_mg = self.params.material[BISHOP.arr_idx()].0;
+        let rook_mg = self.params.material[ROOK.arr_idx()].0;
+        let (strong_npm, weak_npm) =
+            (self.non_pawn_material_eval(strong), self.non_pawn_material_eval(weak));
+
+        // Pawnless with at most a minor piece more
+        if self.pawn_count(strong) == 0 && strong_npm - weak_npm <= bishop_mg {
+            return match () {
+                _ if strong_npm < rook_mg => 0,
+                _ if weak_npm <= bishop_mg => 4,
+                _ => 14,
+            };
+        }
+
+        let mut sf = SCALE_FACTOR_NORMAL;
+        if self.opposite_bishops() {
+            sf = if strong_npm == bishop_mg && weak_npm == bishop_mg {
+                22 + 4 * self.eval.candidate_passed[strong.idx()].count() as isize
+            } else {
+                22 + 3 * self.occ_bb(strong).count() as isize
+            };
+        } else if strong_npm == rook_mg
+            && weak_npm == rook_mg
+            && self.pawn_count(strong) <= self.pawn_count(weak) + 1
+        {
+            // Rook ending with the pawns on one flank and the defending king next to its pawns
+            let pawns = self.pawn_bb(strong);
+            let one_flank = (pawns & QUEEN_SIDE != 0) != (pawns & !QUEEN_SIDE != 0);
+            let king_mask = get_king_mask(self.king_sq(weak), 0, 0, weak);
+            if one_flank && king_mask & self.pawn_bb(weak) != 0 {
+                return 36;
+            }
+        }
+
+        if self.queen_count(WHITE) + self.queen_count(BLACK) == 1 {
+            let minors = |clr| (self.bishop_count(clr) + self.knight_count(clr)) as isize;
+            let side = if self.queen_count(strong) == 1 { weak } else { strong };
+            37 + 5 * minors(side)
+        } else {
+            sf.min(36 + 7 * self.pawn_count(strong) as isize)
+        }
+    }
+
+    #[inline(always)]
+    fn opposite_bishops(&self) -> bool {
+        self.bishop_count(WHITE) == 1
+            && self.bishop_count(BLACK) == 1
+            && (self.bishop_bb(WHITE) & DARK_SQUARES != 0)
+                != (self.bishop_bb(BLACK) & DARK_SQUARES != 0)
+    }
+
+    ///
+    /// Adds a score seen from white to the side it favors
+    ///
+    #[inline(always)]
+    fn sum_signed(&mut self, (mg, eg): (isize, isize)) {
+        self.sum(WHITE, None, None, (mg.max(0), eg.max(0)));
+        self.sum(BLACK, None, None, ((-mg).max(0), (-eg).max(0)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::evaluation::evaluation::EvaluationTrait;
+    use crate::engine::evaluation::test_evaluation::{SF_EVAL, eval_assert};
+
+    #[test]
+    fn winnable_test() {
+        let mut checked = 0;
+        for obj in SF_EVAL.iter().filter(|obj| obj.winnable != -123465) {
+            let mut board = Board::read_fen(obj.fen);
+            board.evaluation();
+
+            let winnable = board.eval.winnable;
+            eval_assert(board.tapered(winnable), obj.winnable, 2, false);
+            checked += 1;
+        }
+        // The first 5 of the 18 positions have no reference value
+        assert_eq!(checked, 13);
+    }
+
+    #[test]
+    fn scale_factor_test() {
+        let scale_factor = |fen: &str| {
+            let mut board = Board::read_fen(fen);
+            board.evaluation();
+            board.eval.scale_factor
+        };
+
+        // Opposite colored bishops, only the bishops and with more pieces
+        assert_eq!(scale_factor("8/3b1k2/8/2P1P3/8/4BK2/8/8 w - - 0 1"), 22 + 4 * 2);
+        assert_eq!(scale_factor("8/3b1k2/8/2P1P3/8/4BK2/8/R5r1 w - - 0 1"), 22 + 3 * 5);
+        // Rook ending with the pawns on one flank
+        assert_eq!(scale_factor("8/5pk1/8/6PP/5K2/8/8/R5r1 w - - 0 1"), 36);
+        // Pawnless with a minor piece more
+        assert_eq!(scale_factor("8/5k2/8/8/8/4NK2/8/8 w - - 0 1"), 0);
+        assert_eq!(scale_factor("8/5k2/8/8/8/4BK2/8/r4R2 w - - 0 1"), 14);
+        assert_eq!(scale_factor("8/5k2/8/8/2b5/5K2/8/R7 w - - 0 1"), 4);
+        // Only one queen on the board
+        assert_eq!(scale_factor("8/5k2/4n3/8/8/4QK2/P7/8 w - - 0 1"), 37 + 5);
+        // Few pawns
+        assert_eq!(scale_factor("8/5k2/4n3/8/8/4NK2/P7/8 w - - 0 1"), 36 + 7);
+        assert_eq!(scale_factor(SF_EVAL[0].fen), SCALE_FACTOR_NORMAL);
+    }
+}
@@ -6,3 +6,4 @@ pub mod pawn;
 pub mod rays;
 pub mod rook;
 pub mod zobrist_keys;
+pub mod polyglot_keys;
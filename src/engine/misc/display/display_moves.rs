@@ -53,15 +53,16 @@ pub fn sq_notation(square: u8) -> String {
 }
 
 pub fn from_move_notation(notation: &str, board: &mut Board) -> Move {
-    let notation = notation.to_lowercase();
-    let moves = board.gen_moves();
+    try_from_move_notation(notation, board)
+        .unwrap_or_else(|| panic!("Something is wrong with the move: {:?}", notation))
+}
 
-    for (rev, _) in &moves {
-        let mv_notation =
-            move_notation(rev.from, rev.to, rev.flag.get_promo_piece()).to_lowercase();
-        if notation == mv_notation {
-            return *rev;
-        }
-    }
-    panic!("Something is wrong with the move: {:?}", notation);
+///
+/// Move of the long algebraic notation, None if there is no such pseudo-legal move
+///
+pub fn try_from_move_notation(notation: &str, board: &mut Board) -> Option<Move> {
+    let notation = notation.to_lowercase();
+    board.gen_moves().into_iter().map(|(mv, _)| mv).find(|mv| {
+        move_notation(mv.from, mv.to, mv.flag.get_promo_piece()).to_lowercase() == notation
+    })
 }
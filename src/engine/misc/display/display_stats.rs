@@ -1,17 +1,40 @@
+use std::sync::atomic::Ordering;
+
+use crate::engine::board::san::SanTrait;
 use crate::engine::search::iter_deepening::Search;
+use crate::engine::search::transposition_table::Bound;
 
 pub trait DisplayStatsTrait {
     fn print_info(&self, score: isize, line: String);
+    fn print_bound_info(&self, score: isize, bound: Bound);
     fn print_pruning_info(&self, score: isize);
     fn print_ordering_info(&self, depth: i8);
+    fn print_hash_info(&self);
 }
 
 impl DisplayStatsTrait for Search {
     fn print_info(&self, score: isize, line: String) {
         let time = self.uci.start_time.elapsed().as_millis();
         println!(
-            "info depth {} nodes {} time {} score cp {} pv{}",
-            self.info.curr_depth, self.info.nodes, time, score, line
+            "info depth {} nodes {} tbhits {} time {} score cp {} pv{}",
+            self.info.curr_depth, self.info.nodes, self.info.tb_hits, time, score, line
+        );
+
+        // Debug output of the same line in SAN
+        if self.options.san_pv {
+            let len = self.board.pv_line.len().min(self.info.curr_depth as usize);
+            let san = self.board.line_to_san(&self.board.pv_line[..len]);
+            println!("info string pv san {}", san.join(" "));
+        }
+    }
+
+    // Score of a search that fell out of the aspiration window
+    fn print_bound_info(&self, score: isize, bound: Bound) {
+        let time = self.uci.start_time.elapsed().as_millis();
+        let bound = if bound == Bound::Lower { "lowerbound" } else { "upperbound" };
+        println!(
+            "info depth {} nodes {} tbhits {} time {} score cp {} {}",
+            self.info.curr_depth, self.info.nodes, self.info.tb_hits, time, score, bound
         );
     }
 
@@ -35,4 +58,16 @@ impl DisplayStatsTrait for Search {
             depth, avg_beta_idx, avg_alpha_idx, fhf
         );
     }
+
+    // Hit rates of the hash tables during the last iteration
+    fn print_hash_info(&self) {
+        let tt = self.board.tt.read().unwrap();
+        println!(
+            "info string hash tt {:.1}% collisions {} pawn {:.1}% eval {:.1}%",
+            tt.hit_rate(),
+            tt.collisions.load(Ordering::Relaxed),
+            self.board.pawn_tt.read().unwrap().hit_rate(),
+            self.board.eval_tt.hit_rate()
+        );
+    }
 }
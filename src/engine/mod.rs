@@ -1,8 +1,11 @@
 pub mod attacks;
 pub mod board;
+pub mod book;
 pub mod evaluation;
 pub mod generated;
 pub mod misc;
 pub mod move_generator;
 pub mod protocols;
 pub mod search;
+pub mod tablebase;
+pub mod tools;
@@ -6,6 +6,7 @@ use crate::engine::board::moves::*;
 use crate::engine::board::piece::*;
 use crate::engine::board::zobrist::ZobristKeysTrait;
 use crate::engine::evaluation::evaluation::EvaluationTrait;
+use crate::engine::evaluation::nnue::NnueTrait;
 use crate::engine::generated::zobrist_keys::CASTLE_KEYS;
 use crate::engine::generated::zobrist_keys::PIECE_COUNT_KEYS;
 use crate::engine::generated::zobrist_keys::PIECE_KEYS;
@@ -15,6 +16,8 @@ use core::panic;
 pub trait BoardMoveTrait {
     fn make_move(&mut self, mv: &Move) -> bool;
     fn undo_move(&mut self);
+    fn play_root_move(&mut self, mv: &Move) -> bool;
+    fn undo_root_move(&mut self, mv: &Move);
     fn make_state(&mut self, mv: &Move);
 
     fn clear_piece(&mut self, sq: usize, piece: Piece);
@@ -27,6 +30,7 @@ impl BoardMoveTrait for Board {
     fn make_move(&mut self, mv: &Move) -> bool {
         self.history.push(self.state);
         self.moves.push(*mv);
+        self.nnue_push();
         self.zb_reset_key();
         self.state.pk_key ^= CASTLE_KEYS[self.state.castling.idx()];
 
@@ -72,6 +76,28 @@ impl BoardMoveTrait for Board {
         true
     }
 
+    ///
+    /// Plays a move of the game (not of the search). The move is not kept in the moves, so the
+    /// ply stays 0 and never exceeds the max ply, but the state is kept for the repetitions.
+    ///
+    #[inline(always)]
+    fn play_root_move(&mut self, mv: &Move) -> bool {
+        let legal = self.make_move(mv);
+        if legal {
+            self.moves.pop();
+        }
+        legal
+    }
+
+    ///
+    /// Takes back the last move played with play_root_move
+    ///
+    #[inline(always)]
+    fn undo_root_move(&mut self, mv: &Move) {
+        self.moves.push(*mv);
+        self.undo_move();
+    }
+
     #[inline(always)]
     fn undo_move(&mut self) {
         let (mv, st) = match (self.moves.pop(), self.history.pop()) {
@@ -79,6 +105,8 @@ impl BoardMoveTrait for Board {
             (None, None) => return,
             (_, _) => panic!("There is something wrong"),
         };
+        // The accumulator is restored from the stack, instead of being updated
+        let nnue = std::mem::take(&mut self.nnue.active);
 
         match mv.flag {
             Flag::Quiet => self.quiet_mv(mv.to as usize, mv.from as usize, mv.piece),
@@ -114,6 +142,8 @@ impl BoardMoveTrait for Board {
         }
 
         self.state = st;
+        self.nnue.active = nnue;
+        self.nnue_pop();
     }
 
     #[inline(always)]
@@ -128,6 +158,8 @@ impl BoardMoveTrait for Board {
             self.state.pk_key ^= PIECE_KEYS[from_sq][piece.idx()] ^ PIECE_KEYS[to_sq][piece.idx()];
         }
         self.quiet_eval(piece, from_sq, to_sq);
+        self.nnue_quiet(piece, from_sq, to_sq);
+        self.attacks.dirty |= (1u64 << from_sq) | (1u64 << to_sq);
     }
 
     #[inline(always)]
@@ -145,6 +177,8 @@ impl BoardMoveTrait for Board {
         }
         self.p_count[piece.idx()] += 1;
         self.add_eval(piece, sq);
+        self.nnue_add(piece, sq);
+        self.attacks.dirty |= 1u64 << sq;
     }
 
     #[inline(always)]
@@ -159,6 +193,8 @@ impl BoardMoveTrait for Board {
         }
         self.p_count[piece.idx()] -= 1;
         self.clear_eval(piece, sq);
+        self.nnue_clear(piece, sq);
+        self.attacks.dirty |= 1u64 << sq;
     }
 
     #[inline(always)]
@@ -1,4 +1,5 @@
 pub mod make_move;
 pub mod mv_gen;
 pub mod mv_oredering;
+pub mod mv_picker;
 pub mod perft;
@@ -1,4 +1,3 @@
-use super::make_move::BoardMoveTrait;
 use crate::engine::attacks::bishop::*;
 use crate::engine::attacks::king::*;
 use crate::engine::attacks::knight::*;
@@ -19,6 +18,7 @@ pub trait BoardGenMoveTrait {
     fn gen_moves(&mut self) -> Vec<(Move, isize)>;
     fn gen_captures(&mut self) -> Vec<(Move, isize)>;
     fn gen_cap_promo(&mut self) -> Vec<(Move, isize)>;
+    fn gen_quiets(&mut self) -> Vec<(Move, isize)>;
 
     // Converting Bitboard squares to Move struct
     fn add_quiet_moves(&mut self, bb: u64, piece: Piece, sq: usize);
@@ -46,9 +46,9 @@ pub trait BoardGenMoveTrait {
     fn sq_attack(&self, sq: usize, color: Color) -> u64;
     fn sq_attack_with_occ(&self, sq: usize, color: Color, occ: u64) -> u64;
 
-    // Is repetition & does the move exist for current position
+    // Is repetition & can the move be played in the current position
     fn is_repetition(&self) -> bool;
-    fn move_exists(&mut self, mv: &Move) -> bool;
+    fn is_pseudo_legal(&self, mv: &Move) -> bool;
 }
 
 impl BoardGenMoveTrait for Board {
@@ -96,6 +96,20 @@ impl BoardGenMoveTrait for Board {
         self.gen_moves.drain(..).collect()
     }
 
+    #[inline(always)]
+    /// Generates the quiet moves without the promotions (the other moves of gen_cap_promo)
+    fn gen_quiets(&mut self) -> Vec<(Move, isize)> {
+        self.pawn_quiet_moves();
+
+        for piece in &PIECES_WITHOUT_PAWN {
+            self.piece_quiet_moves(piece + self.color());
+        }
+
+        self.add_castling_moves();
+
+        self.gen_moves.drain(..).filter(|(mv, _)| !mv.flag.is_promo()).collect()
+    }
+
     #[inline(always)]
     /// Gets Move Bitboard for a given piece on a given square considering other pieces on the board
     fn get_mv_bb(piece: Piece, sq: usize, own_occ: u64, enemy_occ: u64) -> u64 {
@@ -377,6 +391,10 @@ impl BoardGenMoveTrait for Board {
     #[inline(always)]
     /// Checks if a square is attacked by the given color considering a given occupancy bitboard
     fn sq_attack(&self, sq: usize, color: Color) -> u64 {
+        // The attack maps see through some pieces, a square outside of them is never attacked
+        if self.attacks.dirty == 0 && self.attacks.side[color.opp().idx()] & (1u64 << sq) == 0 {
+            return 0;
+        }
         let (own_occ, enemy_occ) = self.both_occ_bb(color);
 
         let op_pawns = self.bb(BLACK_PAWN - color);
@@ -426,19 +444,67 @@ impl BoardGenMoveTrait for Board {
     }
 
     #[inline(always)]
-    /// Checks if a move exists in the current position
-    fn move_exists(&mut self, mv: &Move) -> bool {
-        let mut moves = self.gen_moves();
-
-        for temp_mv in &mut moves {
-            if *mv == temp_mv.0 {
-                if self.make_move(mv) {
-                    self.undo_move();
-                    return true;
-                }
-            }
+    /// Checks if the move could be generated in the current position, without generating the
+    /// moves. The move can still leave the king in check, make_move rejects it then.
+    fn is_pseudo_legal(&self, mv: &Move) -> bool {
+        let (from, to) = (mv.from as usize, mv.to as usize);
+        let color = self.color();
+        if mv.flag == Flag::NullMove || mv.piece.color() != color || self.squares[from] != mv.piece
+        {
+            return false;
         }
-        return false;
+
+        // A capture takes the enemy piece of the flag, the other moves go to an empty square
+        let (own_occ, enemy_occ) = self.both_occ_bb(color);
+        let captured = match mv.flag {
+            Flag::Capture(piece) | Flag::Promotion(_, Some(piece)) => Some(piece),
+            _ => None,
+        };
+        match captured {
+            Some(piece) if piece.color() == color || self.squares[to] != piece => return false,
+            None if self.squares[to] != EMPTY => return false,
+            _ => (),
+        }
+
+        // Only a pawn promotes, and always when it reaches the last rank
+        let last_rank = if color.is_white() { 7 } else { 0 };
+        let to_last_rank = RANK_BITBOARD[last_rank] & (1u64 << to) != 0;
+        let is_promo = match mv.flag {
+            Flag::Promotion(promo, _) => {
+                PIECES_WITHOUT_PAWN_KING.iter().any(|&piece| piece + color == promo)
+            }
+            _ => false,
+        };
+        let is_pawn = mv.piece.is_pawn();
+        if mv.flag.is_promo() != is_promo || (is_pawn && to_last_rank != is_promo) {
+            return false;
+        }
+        if is_promo && !is_pawn {
+            return false;
+        }
+
+        let moves = match mv.flag {
+            Flag::KingCastle | Flag::QueenCastle => {
+                let (castle, king_from, king_to) = match (color, mv.flag) {
+                    (WHITE, Flag::KingCastle) => (CASTLING_WKINGSIDE, E1, G1),
+                    (WHITE, _) => (CASTLING_WQUEENSIDE, E1, C1),
+                    (_, Flag::KingCastle) => (CASTLING_BKINGSIDE, E8, G8),
+                    (_, _) => (CASTLING_BQUEENSIDE, E8, C8),
+                };
+                return mv.piece.is_king()
+                    && from == king_from as usize
+                    && to == king_to as usize
+                    && self.state.castling.valid(castle, self, own_occ, enemy_occ);
+            }
+            Flag::EP if is_pawn && self.state.ep == Some(mv.to) => {
+                get_pawn_att_mask(from, own_occ, enemy_occ, color)
+            }
+            Flag::EP => 0,
+            _ if is_pawn && captured.is_some() => get_pawn_att(from, own_occ, enemy_occ, color),
+            _ if is_pawn => get_pawn_mv(from, own_occ, enemy_occ, color),
+            _ => Board::get_mv_bb(mv.piece, from, own_occ, enemy_occ),
+        };
+        moves & (1u64 << to) != 0
     }
 }
 
@@ -450,6 +516,7 @@ mod tests {
     use crate::engine::misc::bitboard::BitboardTrait;
     use crate::engine::misc::display::display_board::*;
     use crate::engine::misc::display::display_moves::*;
+    use crate::engine::move_generator::make_move::BoardMoveTrait;
 
     use super::*;
 
@@ -744,4 +811,31 @@ mod tests {
         let moves = board.gen_moves();
         println!("{:?}", moves);
     }
+
+    #[test]
+    fn test_is_pseudo_legal() {
+        let fens = [
+            FEN_START,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "4k3/8/8/2pP4/8/8/8/4K3 w - c6 0 2",
+            "4k3/8/8/2pP4/8/8/8/4K3 w - - 0 2",
+        ];
+        let moves: Vec<Vec<Move>> = fens
+            .iter()
+            .map(|fen| Board::read_fen(fen).gen_moves().into_iter().map(|(mv, _)| mv).collect())
+            .collect();
+
+        // The moves of any position are pseudo-legal exactly when they are generated
+        for (fen, generated) in fens.iter().zip(&moves) {
+            let board = Board::read_fen(fen);
+            for mv in moves.iter().flatten() {
+                assert_eq!(board.is_pseudo_legal(mv), generated.contains(mv), "{} {:?}", fen, mv);
+            }
+            assert!(!board.is_pseudo_legal(&Move::null_move()));
+        }
+    }
 }
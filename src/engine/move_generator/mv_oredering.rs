@@ -6,19 +6,22 @@ use crate::engine::board::moves::*;
 use crate::engine::board::piece::*;
 use crate::engine::misc::bitboard::BitboardTrait;
 use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
-use crate::engine::search::transposition_table::TT;
 
 static PV_MV_SCORE: isize = 95000;
 static TT_MV_SCORE: isize = 80000;
 static SEE_MV_SCORE: isize = 2000;
 static KILLER_MV_SCORE: [isize; 2] = [2000, 1950];
+static COUNTER_MV_SCORE: isize = 1900;
 static HIS_MV_SCORE: isize = 1000;
+// Every step of the victim outweighs the attacker, the king counts as a queen
+static MVV_LVA_FACTOR: isize = 16;
 
 pub trait MoveOrderingTrait {
     fn next_move(&mut self, moves: &mut Vec<(Move, isize)>) -> Option<Move>;
     fn score_moves(&mut self, moves: &mut Vec<(Move, isize)>);
     fn quiet_eval(&mut self, mv: &Move) -> isize;
     fn capture_eval(&mut self, mv: &Move) -> isize;
+    fn noisy_eval(&mut self, mv: &Move) -> isize;
     fn see(&mut self, from: usize, to: usize) -> isize;
 }
 
@@ -28,7 +31,7 @@ impl MoveOrderingTrait for Board {
     /// PV move, TT move, captures, promotions, killer moves, and history heuristic
     fn score_moves(&mut self, moves: &mut Vec<(Move, isize)>) {
         let pv_mv = if let Some(mv) = self.pv_line.get(self.ply()) { Some(*mv) } else { None };
-        let tt_mv = TT.read().unwrap().get(self.key());
+        let tt_mv = self.tt.read().unwrap().get(self.key());
         for (mv, score) in moves.iter_mut() {
             if pv_mv == Some(*mv) {
                 *score = PV_MV_SCORE;
@@ -69,22 +72,42 @@ impl MoveOrderingTrait for Board {
     }
 
     #[inline(always)]
-    /// Evaluates a quiet move
+    /// Evaluates a quiet move: the killers, the counter move and then the histories
     fn quiet_eval(&mut self, mv: &Move) -> isize {
         if Some(*mv) == self.s_killers[self.ply()][0] {
             return KILLER_MV_SCORE[0];
         } else if Some(*mv) == self.s_killers[self.ply()][1] {
             return KILLER_MV_SCORE[1];
+        } else if Some(*mv) == self.s_history.counter_move(&self.moves) {
+            return COUNTER_MV_SCORE;
         }
 
-        return self.s_history[mv.piece.idx()][mv.to as usize] + HIS_MV_SCORE;
+        // Stays below the counter move, the history is at most 3 * MAX_HISTORY
+        self.s_history.quiet_score(&self.moves, mv) / 32 + HIS_MV_SCORE
     }
 
     #[inline(always)]
-    /// Evaluates a capture move
+    /// Evaluates a capture move, the capture history breaks the ties of the SEE
     fn capture_eval(&mut self, mv: &Move) -> isize {
         debug_assert!(self.squares[mv.to as usize] != 0, "There is no piece in the to square");
-        self.see(mv.from as usize, mv.to as usize) + SEE_MV_SCORE
+        self.see(mv.from as usize, mv.to as usize)
+            + self.s_history.capture_score(mv) / 64
+            + SEE_MV_SCORE
+    }
+
+    #[inline(always)]
+    /// Evaluates a capture or promotion by MVV-LVA without the SEE, the promoted piece counts as
+    /// taken. The capture history breaks the ties.
+    fn noisy_eval(&mut self, mv: &Move) -> isize {
+        let gain = match mv.flag {
+            Flag::Capture(piece) => piece.weight(),
+            Flag::Promotion(promo, Some(piece)) => piece.weight() + promo.weight(),
+            Flag::Promotion(promo, None) => promo.weight(),
+            // En passant
+            _ => PAWN.weight(),
+        };
+        let attacker = mv.piece.weight().min(QUEEN.weight());
+        MVV_LVA_FACTOR * gain - attacker + self.s_history.capture_score(mv) / 64
     }
 
     #[inline(always)]
@@ -0,0 +1,235 @@
+use crate::engine::board::board::Board;
+use crate::engine::board::moves::{Flag, Move};
+use crate::engine::board::piece::PieceTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+use crate::engine::move_generator::mv_oredering::MoveOrderingTrait;
+
+// NOTE: Staged move picker
+//
+// The moves of a node come a stage at a time, so a cutoff on the first ones saves generating and
+// scoring the rest:
+//   1. PV move of the last iteration and TT move, checked without generating the moves
+//   2. Captures and promotions by MVV-LVA, the ones that lose material are put aside
+//   3. Killers and counter move
+//   4. Quiet moves by history
+//   5. Captures that lose material and under-promotions
+//
+// The SEE is only computed for the capture that comes next. The moves are pseudo-legal, make_move
+// rejects the ones that leave the king in check.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    HashMoves,
+    GenNoisy,
+    GoodNoisy,
+    Refutations,
+    GenQuiets,
+    Quiets,
+    BadNoisy,
+    Done,
+}
+
+#[derive(Debug)]
+pub struct MovePicker {
+    pub stage: Stage,
+    // PV and TT moves
+    hash_moves: [Option<Move>; 2],
+    // Killers and counter move
+    refutations: [Option<Move>; 3],
+    idx: usize,
+    moves: Vec<(Move, isize)>,
+    bad_noisy: Vec<(Move, isize)>,
+}
+
+impl MovePicker {
+    pub fn init(board: &Board) -> Self {
+        let ply = board.ply();
+        let pv_mv = board.pv_line.get(ply).copied();
+        let tt_mv = board.tt.read().unwrap().get(board.key()).map(|entry| entry.mv);
+        let [killer_1, killer_2] = board.s_killers[ply];
+        let counter = board.s_history.counter_move(&board.moves);
+
+        Self {
+            stage: Stage::HashMoves,
+            hash_moves: [pv_mv, tt_mv.filter(|mv| Some(*mv) != pv_mv)],
+            refutations: [killer_1, killer_2, counter],
+            idx: 0,
+            moves: Vec::new(),
+            bad_noisy: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_hash_move(&self, mv: &Move) -> bool {
+        self.hash_moves.contains(&Some(*mv))
+    }
+
+    ///
+    /// Killers and counter move are only tried once, and only if they are quiet
+    ///
+    #[inline(always)]
+    fn is_refutation(&self, mv: &Move, idx: usize) -> bool {
+        !mv.flag.is_capture()
+            && !mv.flag.is_promo()
+            && !self.is_hash_move(mv)
+            && !self.refutations[..idx].contains(&Some(*mv))
+    }
+
+    ///
+    /// Captures that lose material by the SEE and under-promotions are tried after the quiets
+    ///
+    #[inline(always)]
+    fn is_bad_noisy(board: &mut Board, mv: &Move) -> bool {
+        match mv.flag {
+            Flag::Capture(_) => board.see(mv.from as usize, mv.to as usize) < 0,
+            Flag::Promotion(promo, _) => !promo.is_queen(),
+            _ => false,
+        }
+    }
+
+    ///
+    /// Next move of the node, None once all the moves were picked
+    ///
+    pub fn next(&mut self, board: &mut Board) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMoves => {
+                    while self.idx < self.hash_moves.len() {
+                        self.idx += 1;
+                        if let Some(mv) = self.hash_moves[self.idx - 1]
+                            && board.is_pseudo_legal(&mv)
+                        {
+                            return Some(mv);
+                        }
+                    }
+                    self.stage = Stage::GenNoisy;
+                }
+                Stage::GenNoisy => {
+                    self.moves = board.gen_cap_promo();
+                    for (mv, score) in self.moves.iter_mut() {
+                        *score = board.noisy_eval(mv);
+                    }
+                    self.stage = Stage::GoodNoisy;
+                }
+                Stage::GoodNoisy => {
+                    while let Some((mv, score)) = Self::pick(&mut self.moves) {
+                        if self.is_hash_move(&mv) {
+                            continue;
+                        }
+                        if Self::is_bad_noisy(board, &mv) {
+                            self.bad_noisy.push((mv, score));
+                            continue;
+                        }
+                        return Some(mv);
+                    }
+                    self.idx = 0;
+                    self.stage = Stage::Refutations;
+                }
+                Stage::Refutations => {
+                    while self.idx < self.refutations.len() {
+                        self.idx += 1;
+                        if let Some(mv) = self.refutations[self.idx - 1]
+                            && self.is_refutation(&mv, self.idx - 1)
+                            && board.is_pseudo_legal(&mv)
+                        {
+                            return Some(mv);
+                        }
+                    }
+                    self.stage = Stage::GenQuiets;
+                }
+                Stage::GenQuiets => {
+                    self.moves = board.gen_quiets();
+                    for (mv, score) in self.moves.iter_mut() {
+                        *score = board.s_history.quiet_score(&board.moves, mv);
+                    }
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => {
+                    while let Some((mv, _)) = Self::pick(&mut self.moves) {
+                        if !self.is_hash_move(&mv) && !self.refutations.contains(&Some(mv)) {
+                            return Some(mv);
+                        }
+                    }
+                    self.stage = Stage::BadNoisy;
+                }
+                Stage::BadNoisy => {
+                    if let Some((mv, _)) = Self::pick(&mut self.bad_noisy) {
+                        return Some(mv);
+                    }
+                    self.stage = Stage::Done;
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+
+    ///
+    /// Removes the highest scored move of the list
+    ///
+    #[inline(always)]
+    fn pick(moves: &mut Vec<(Move, isize)>) -> Option<(Move, isize)> {
+        let best_idx =
+            moves.iter().enumerate().max_by_key(|(_, (_, score))| score).map(|(idx, _)| idx)?;
+        Some(moves.swap_remove(best_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::misc::const_utility::FEN_START;
+
+    fn picked_moves(board: &mut Board) -> Vec<Move> {
+        let mut picker = MovePicker::init(board);
+        let mut moves = Vec::new();
+        while let Some(mv) = picker.next(board) {
+            moves.push(mv);
+        }
+        moves
+    }
+
+    #[test]
+    fn test_picker_same_moves() {
+        let fens = [
+            FEN_START,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "4k3/8/8/2pP4/8/8/8/4K3 w - c6 0 2",
+        ];
+        for fen in fens {
+            let mut board = Board::read_fen(fen);
+            let mut picked = picked_moves(&mut board);
+            let mut generated: Vec<Move> =
+                board.gen_moves().into_iter().map(|(mv, _)| mv).collect();
+
+            let key = |mv: &Move| (mv.from, mv.to, format!("{:?}", mv.flag));
+            picked.sort_by_key(key);
+            generated.sort_by_key(key);
+            assert_eq!(picked, generated, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_picker_order() {
+        // Nxe5 wins the queen, Qxa7 loses it to the rook
+        let mut board = Board::read_fen("r6k/p7/8/4q3/Q7/5N2/8/7K w - - 0 1");
+        let find = |board: &mut Board, from: u8, to: u8| {
+            board
+                .gen_moves()
+                .into_iter()
+                .map(|(mv, _)| mv)
+                .find(|mv| mv.from == from && mv.to == to)
+        };
+        let [nxe5, qxa7, qb3] =
+            [(21, 36), (24, 48), (24, 17)].map(|(from, to)| find(&mut board, from, to));
+        board.s_killers[0][0] = qb3;
+
+        let picked = picked_moves(&mut board);
+        assert_eq!(picked.first().copied(), nxe5);
+        assert_eq!(picked.get(1).copied(), qb3);
+        assert_eq!(picked.last().copied(), qxa7);
+        assert_eq!(picked.iter().filter(|mv| Some(**mv) == qb3).count(), 1);
+    }
+}
@@ -0,0 +1,387 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::book::builder::{BookBuilder, BuilderOptions};
+use crate::engine::evaluation::eval_params::EvalParams;
+use crate::engine::evaluation::test_evaluation::SF_EVAL;
+use crate::engine::protocols::epd::read_epd;
+use crate::engine::tablebase::generator::Generator;
+use crate::engine::tools::epd_runner::{EpdLimits, EpdRunner};
+use crate::engine::tools::gensfen::{GenOptions, GenSfen};
+use crate::engine::tools::match_runner::{
+    EngineConfig, Match, MatchOptions, TimeControl, load_openings,
+};
+use crate::engine::tools::sprt::Sprt;
+use crate::engine::tools::symmetry::check_symmetry;
+use crate::engine::tools::tuner::{TuneOptions, Tuner, load_dataset};
+
+///
+/// Runs the offline tools given on the command line.
+/// Returns false if no tool was requested, so that the engine starts in UCI mode.
+///
+pub fn run_cli(args: &[String]) -> bool {
+    match args.first().map(String::as_str) {
+        Some("generate") => cli_generate(&args[1..]),
+        Some("book") => cli_book(&args[1..]),
+        Some("epd") => cli_epd(&args[1..]),
+        Some("match") => cli_match(&args[1..]),
+        Some("tune") => cli_tune(&args[1..]),
+        Some("params") => cli_params(&args[1..]),
+        Some("gensfen") => cli_gensfen(&args[1..]),
+        Some("symmetry") => cli_symmetry(&args[1..]),
+        _ => return false,
+    }
+    true
+}
+
+// Usage: generate <material> [output dir], e.g. "generate KQvKR tables"
+fn cli_generate(args: &[String]) {
+    let Some(material) = args.first() else {
+        eprintln!("Usage: generate <material> [output dir]");
+        return;
+    };
+    let dir = args.get(1).map(String::as_str).unwrap_or(".");
+
+    let mut generator = Generator::init();
+    generator.verbose = true;
+    if let Err(err) = generator.generate(material) {
+        eprintln!("[CLI]: {}", err);
+        return;
+    }
+
+    match generator.write(Path::new(dir)) {
+        Ok(()) => println!("Generated {} tables in {}", generator.tables.len(), dir),
+        Err(err) => eprintln!("[CLI]: Failed to write the tables: {}", err),
+    }
+}
+
+// Usage: book <pgn file> <output file> [mingames <n>] [maxply <n>] [minelo <n>]
+// The book is written in the Polyglot format for ".bin" files, in the native format (.fbk) otherwise
+fn cli_book(args: &[String]) {
+    let (Some(pgn), Some(output)) = (args.first(), args.get(1)) else {
+        eprintln!("Usage: book <pgn file> <output file> [mingames <n>] [maxply <n>] [minelo <n>]");
+        return;
+    };
+
+    let mut options = BuilderOptions::init();
+    let mut iter = args[2..].iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().and_then(|v| v.parse::<usize>().ok());
+        match (arg.as_str(), value) {
+            ("mingames", Some(v)) => options.min_games = v as u32,
+            ("maxply", Some(v)) => options.max_ply = v,
+            ("minelo", Some(v)) => options.min_rating = v as u32,
+            _ => eprintln!("[CLI]: Invalid book argument: {}", arg),
+        }
+    }
+
+    let file = match File::open(pgn) {
+        Ok(file) => file,
+        Err(err) => return eprintln!("[CLI]: Failed to open {}: {}", pgn, err),
+    };
+
+    let mut builder = BookBuilder::init(options);
+    builder.add_pgn(BufReader::new(file));
+
+    let book = builder.build();
+    let path = Path::new(output);
+    let written = match path.extension().and_then(|e| e.to_str()) {
+        Some("bin") => {
+            let polyglot = book.to_polyglot();
+            polyglot.write(path).map(|_| polyglot.len())
+        }
+        _ => book.write(path).map(|_| book.len()),
+    };
+
+    match written {
+        Ok(entries) => println!(
+            "Added {} games ({} skipped), {} book entries written to {}",
+            builder.games, builder.skipped, entries, output
+        ),
+        Err(err) => eprintln!("[CLI]: Failed to write the book: {}", err),
+    }
+}
+
+// Usage: epd <epd file> [depth <n>] [nodes <n>] [movetime <ms>] [csv <output file>]
+// Without limits every position is searched to its "acd" depth, or for one second
+fn cli_epd(args: &[String]) {
+    let Some(path) = args.first() else {
+        eprintln!("Usage: epd <epd file> [depth <n>] [nodes <n>] [movetime <ms>] [csv <file>]");
+        return;
+    };
+
+    let mut limits = EpdLimits::init();
+    let mut csv = None;
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next();
+        let number = value.and_then(|v| v.parse::<u64>().ok());
+        match (arg.as_str(), number) {
+            ("depth", Some(v)) => limits.depth = Some(v.clamp(1, 63) as i8),
+            ("nodes", Some(v)) => limits.nodes = Some(v as usize),
+            ("movetime", Some(v)) => limits.movetime = Some(v),
+            ("csv", _) if value.is_some() => csv = value,
+            _ => eprintln!("[CLI]: Invalid epd argument: {}", arg),
+        }
+    }
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => return eprintln!("[CLI]: Failed to open {}: {}", path, err),
+    };
+
+    let mut runner = EpdRunner::init(limits);
+    runner.print_header();
+    for epd in read_epd(BufReader::new(file)) {
+        match epd.and_then(|epd| runner.run(&epd).cloned()) {
+            Ok(result) => runner.print_result(&result),
+            Err(err) => eprintln!("[CLI]: Skipped position: {}", err),
+        }
+    }
+    runner.print_summary();
+
+    if let Some(csv) = csv
+        && let Err(err) = std::fs::write(csv, runner.to_csv())
+    {
+        eprintln!("[CLI]: Failed to write {}: {}", csv, err);
+    }
+}
+
+// Usage: match [games <n>] [tc <base>+<inc>] [nodes <n>] [concurrency <n>] [openings <file>]
+//              [pgn <file>] [sprt <elo0> <elo1>] [alpha <a>] [beta <b>]
+//              [first <option>=<value>] [second <option>=<value>]
+// The engines differ by their options, the option "Name" sets the name used in the PGN
+fn cli_match(args: &[String]) {
+    let mut options = MatchOptions::init();
+    let mut engines = [EngineConfig::init("First"), EngineConfig::init("Second")];
+    let mut sprt: Option<Sprt> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().map(String::as_str).unwrap_or_default();
+        let ok = match arg.as_str() {
+            "games" => value.parse().map(|v| options.games = v).is_ok(),
+            "tc" => TimeControl::parse(value).map(|tc| options.tc = tc).is_some(),
+            "nodes" => value.parse().map(|v| options.nodes = Some(v)).is_ok(),
+            "concurrency" => value.parse().map(|v| options.concurrency = v).is_ok(),
+            "pgn" => {
+                options.pgn = Some(value.into());
+                true
+            }
+            "openings" => match load_openings(Path::new(value)) {
+                Ok(openings) => {
+                    options.openings = openings;
+                    true
+                }
+                Err(err) => return eprintln!("[CLI]: Failed to read the openings: {}", err),
+            },
+            "sprt" => {
+                let elo1 = iter.next().and_then(|v| v.parse().ok());
+                match (value.parse(), elo1) {
+                    (Ok(elo0), Some(elo1)) => sprt.insert(Sprt::init(elo0, elo1)).elo0 == elo0,
+                    _ => false,
+                }
+            }
+            "alpha" => value.parse().ok().zip(sprt.as_mut()).map(|(v, s)| s.alpha = v).is_some(),
+            "beta" => value.parse().ok().zip(sprt.as_mut()).map(|(v, s)| s.beta = v).is_some(),
+            "first" | "second" => {
+                let engine = &mut engines[(arg == "second") as usize];
+                match value.split_once('=') {
+                    Some(("Name", name)) => engine.name = name.to_string(),
+                    Some((name, value)) => engine.set_option(name, value),
+                    None => (),
+                }
+                value.contains('=')
+            }
+            _ => false,
+        };
+        if !ok {
+            eprintln!("[CLI]: Invalid match argument: {} {}", arg, value);
+        }
+    }
+    options.sprt = sprt;
+
+    let [first, second] = engines;
+    let mut runner = Match::init(first, second, options);
+    runner.run();
+    println!("Match finished");
+    runner.print_summary();
+}
+
+// Usage: tune <dataset> [k <k>] [passes <n>] [step <n>] [threads <n>] [params <prefix,...>]
+//             [output <file>] [verbose]
+// The dataset holds one quiet position per line followed by the result, e.g. "<fen> [0.5]",
+// or it is a dataset of packed positions (e.g. written by gensfen).
+// The tuned weights are written to the output file after every pass, as Rust source if it
+// ends with ".rs", otherwise as a parameter file for the EvalParamsFile option
+fn cli_tune(args: &[String]) {
+    let Some(path) = args.first() else {
+        eprintln!(
+            "Usage: tune <dataset> [k <k>] [passes <n>] [step <n>] [threads <n>] [params <prefix,...>] [output <file>] [verbose]"
+        );
+        return;
+    };
+
+    let mut options = TuneOptions::init();
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        if arg == "verbose" {
+            options.verbose = true;
+            continue;
+        }
+
+        let value = iter.next().map(String::as_str).unwrap_or_default();
+        let ok = match arg.as_str() {
+            "k" => value.parse().map(|v| options.k = Some(v)).is_ok(),
+            "passes" => value.parse().map(|v| options.passes = v).is_ok(),
+            "step" => value.parse().map(|v| options.step = v).is_ok(),
+            "threads" => value.parse().map(|v| options.threads = v).is_ok(),
+            "params" => {
+                options.filter = value.split(',').map(String::from).collect();
+                true
+            }
+            "output" => {
+                options.output = Some(value.to_string());
+                true
+            }
+            _ => false,
+        };
+        if !ok {
+            eprintln!("[CLI]: Invalid tune argument: {} {}", arg, value);
+        }
+    }
+
+    let entries = match load_dataset(Path::new(path)) {
+        Ok(entries) => entries,
+        Err(err) => return eprintln!("[CLI]: Failed to read {}: {}", path, err),
+    };
+
+    let mut tuner = Tuner::init(entries, options);
+    let error = tuner.tune();
+    println!("Tuning finished, error {:.6}", error);
+    if tuner.options.output.is_none() {
+        println!("{}", tuner.params.to_rust());
+    }
+}
+
+// Usage: params [output file]
+// Writes the default evaluation weights as a parameter file, to be edited and loaded with the
+// EvalParamsFile option
+fn cli_params(args: &[String]) {
+    let text = EvalParams::default().to_text();
+    match args.first() {
+        Some(output) => match std::fs::write(output, text) {
+            Ok(()) => println!("Evaluation weights written to {}", output),
+            Err(err) => eprintln!("[CLI]: Failed to write {}: {}", output, err),
+        },
+        None => print!("{}", text),
+    }
+}
+
+// Usage: gensfen <output file> [positions <n>] [depth <n>] [nodes <n>] [threads <n>]
+//                [random <n>] [openings <file>] [seed <n>] [maxply <n>] [keep <checks,captures,mates>]
+//                [option <Name=Value>]
+// Plays self-play games and writes the quiet positions with their score, best move and result,
+// as text or as packed positions if the output file ends with ".bin"
+fn cli_gensfen(args: &[String]) {
+    let Some(output) = args.first() else {
+        eprintln!(
+            "Usage: gensfen <output file> [positions <n>] [depth <n>] [nodes <n>] [threads <n>] [random <n>] [openings <file>] [seed <n>] [maxply <n>] [keep <checks,captures,mates>] [option <Name=Value>]"
+        );
+        return;
+    };
+
+    let mut options = GenOptions::init();
+    options.output = output.into();
+    let mut engine = EngineConfig::init("Generator");
+
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().map(String::as_str).unwrap_or_default();
+        let ok = match arg.as_str() {
+            "positions" => value.parse().map(|v| options.positions = v).is_ok(),
+            "depth" => value.parse().map(|v| options.depth = v).is_ok(),
+            "nodes" => value.parse().map(|v| options.nodes = Some(v)).is_ok(),
+            "threads" => value.parse().map(|v| options.threads = v).is_ok(),
+            "random" => value.parse().map(|v| options.random_moves = v).is_ok(),
+            "seed" => value.parse().map(|v| options.seed = Some(v)).is_ok(),
+            "maxply" => value.parse().map(|v| options.adjudication.max_ply = v).is_ok(),
+            "openings" => match load_openings(Path::new(value)) {
+                Ok(openings) => {
+                    options.openings = openings;
+                    true
+                }
+                Err(err) => return eprintln!("[CLI]: Failed to read the openings: {}", err),
+            },
+            "keep" => value.split(',').all(|filter| match filter {
+                "checks" => !std::mem::replace(&mut options.filters.skip_in_check, false),
+                "captures" => !std::mem::replace(&mut options.filters.skip_captures, false),
+                "mates" => !std::mem::replace(&mut options.filters.skip_mates, false),
+                _ => false,
+            }),
+            "option" => match value.split_once('=') {
+                Some((name, value)) => {
+                    engine.set_option(name, value);
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        };
+        if !ok {
+            eprintln!("[CLI]: Invalid gensfen argument: {} {}", arg, value);
+        }
+    }
+    options.engine = engine.options;
+
+    let mut generator = GenSfen::init(options);
+    match generator.run() {
+        Ok(written) => {
+            println!("Wrote {} positions from {} games to {}", written, generator.games, output)
+        }
+        Err(err) => eprintln!("[CLI]: Failed to generate the positions: {}", err),
+    }
+}
+
+// Usage: symmetry [epd file]
+// Checks that every position is evaluated as its color flipped mirror, the SF_EVAL set by default
+fn cli_symmetry(args: &[String]) {
+    let boards: Vec<Result<Board, String>> = match args.first() {
+        Some(path) => match File::open(path) {
+            Ok(file) => {
+                read_epd(BufReader::new(file)).into_iter().map(|e| e.map(|e| e.board())).collect()
+            }
+            Err(err) => return eprintln!("[CLI]: Failed to open {}: {}", path, err),
+        },
+        None => SF_EVAL.iter().map(|obj| Ok(Board::read_fen(obj.fen))).collect(),
+    };
+
+    let (mut checked, mut asymmetric) = (0, 0);
+    for board in boards {
+        let board = match board {
+            Ok(board) => board,
+            Err(err) => {
+                eprintln!("[CLI]: Skipped position: {}", err);
+                continue;
+            }
+        };
+        let result = check_symmetry(&board);
+        checked += 1;
+        if result.is_symmetric() {
+            continue;
+        }
+
+        asymmetric += 1;
+        println!("{}: eval {}, mirrored {}", result.fen, result.eval, result.mirror_eval);
+        for term in &result.terms {
+            println!(
+                "  {:<14} white {:?} black {:?}, mirrored white {:?} black {:?}",
+                term.term, term.score[0], term.score[1], term.mirrored[0], term.mirrored[1]
+            );
+        }
+    }
+    println!("Checked {} positions, {} asymmetric", checked, asymmetric);
+}
@@ -0,0 +1,304 @@
+use std::io::BufRead;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::board::moves::Move;
+use crate::engine::board::san::SanTrait;
+use crate::engine::misc::display::display_moves::move_notation;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+
+// NOTE: EPD (Extended Position Description)
+//
+// A line is the first four FEN fields followed by operations, every operation is an opcode
+// with its operands and ends with ";", e.g.
+//   r1b2rk1/... w - - bm Qg6; id "WAC.011"; c0 "Qg6=10, Rf3=3";
+//
+// Moves are written in SAN. The half move clock and full move number are read from the
+// "hmvc" and "fmvn" opcodes (or from the two FEN counters, if present).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdPosition {
+    // Full FEN of the position, including the move counters
+    pub fen: String,
+    pub operations: Vec<(String, Vec<String>)>,
+}
+
+impl EpdPosition {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let mut fields = Vec::with_capacity(6);
+        let mut rest = line;
+        while fields.len() < 6 {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let field = &trimmed[..end];
+            // Only the two move counters are optional
+            if field.is_empty() || (fields.len() >= 4 && field.parse::<u32>().is_err()) {
+                break;
+            }
+            fields.push(field);
+            rest = &trimmed[end..];
+        }
+        if fields.len() < 4 {
+            return Err(format!("Invalid EPD: {}", line));
+        }
+
+        let operations = parse_operations(rest)?;
+        let operand = |opcode: &str| {
+            let (_, operands) = operations.iter().find(|(op, _)| op == opcode)?;
+            operands.first().filter(|v| v.parse::<u32>().is_ok()).cloned()
+        };
+        let half_move = fields.get(4).map(|v| v.to_string()).or_else(|| operand("hmvc"));
+        let full_move = fields.get(5).map(|v| v.to_string()).or_else(|| operand("fmvn"));
+
+        let fen = format!(
+            "{} {} {}",
+            fields[..4].join(" "),
+            half_move.unwrap_or("0".to_string()),
+            full_move.unwrap_or("1".to_string())
+        );
+        if Board::try_read_fen(&fen).is_none() {
+            return Err(format!("Invalid EPD position: {}", fen));
+        }
+        Ok(Self { fen, operations })
+    }
+
+    pub fn board(&self) -> Board {
+        Board::read_fen(&self.fen)
+    }
+
+    ///
+    /// Operands of the first operation with the opcode
+    ///
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        let (_, operands) = self.operations.iter().find(|(op, _)| op == opcode)?;
+        Some(operands)
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.operation("id")?.first().map(String::as_str)
+    }
+
+    ///
+    /// Comment opcodes "c0" to "c9"
+    ///
+    pub fn comment(&self, idx: usize) -> Option<&str> {
+        self.operation(&format!("c{}", idx))?.first().map(String::as_str)
+    }
+
+    ///
+    /// Analysis count depth: the depth that the position should be searched to
+    ///
+    pub fn acd(&self) -> Option<i8> {
+        self.operation("acd")?.first()?.parse().ok()
+    }
+
+    ///
+    /// Best moves, any of them solves the position
+    ///
+    pub fn best_moves(&self) -> Result<Vec<Move>, String> {
+        self.moves("bm")
+    }
+
+    ///
+    /// Avoid moves, all of them fail the position
+    ///
+    pub fn avoid_moves(&self) -> Result<Vec<Move>, String> {
+        self.moves("am")
+    }
+
+    ///
+    /// Predicted variation, every move is played after the previous one
+    ///
+    pub fn pv(&self) -> Result<Vec<Move>, String> {
+        let mut board = self.board();
+        let mut line = Vec::new();
+        for san in self.operation("pv").unwrap_or_default() {
+            let mv = board.parse_san(san).ok_or(format!("Invalid pv move: {}", san))?;
+            board.play_root_move(&mv);
+            line.push(mv);
+        }
+        Ok(line)
+    }
+
+    ///
+    /// Points of the moves for the STS suites, either from a "c0" comment like
+    /// "f5=10, Be5+=2, Bf2=3" or from the "c8" points with the "c9" moves in coordinate notation
+    ///
+    pub fn points(&self) -> Vec<(Move, u32)> {
+        let mut board = self.board();
+        let mut points = Vec::new();
+
+        if let Some(comment) = self.comment(0) {
+            for item in comment.split(',') {
+                let Some((san, value)) = item.trim().rsplit_once('=') else { continue };
+                if let (Some(mv), Ok(value)) = (board.parse_san(san), value.trim().parse()) {
+                    points.push((mv, value));
+                }
+            }
+        }
+
+        if points.is_empty()
+            && let (Some(values), Some(moves)) = (self.operation("c8"), self.operation("c9"))
+        {
+            let values = values.iter().flat_map(|v| v.split_whitespace());
+            let moves = moves.iter().flat_map(|v| v.split_whitespace());
+            for (value, notation) in values.zip(moves) {
+                let mv = board.legal_moves().into_iter().find(|mv| {
+                    move_notation(mv.from, mv.to, mv.flag.get_promo_piece()) == notation
+                });
+                if let (Some(mv), Ok(value)) = (mv, value.parse()) {
+                    points.push((mv, value));
+                }
+            }
+        }
+        points
+    }
+
+    ///
+    /// The position in EPD, the operands with spaces (or without moves) are quoted
+    ///
+    pub fn to_epd(&self) -> String {
+        let fields: Vec<&str> = self.fen.split_whitespace().take(4).collect();
+        let mut epd = fields.join(" ");
+        for (opcode, operands) in &self.operations {
+            epd.push(' ');
+            epd.push_str(opcode);
+            for operand in operands {
+                let quote = operand.is_empty()
+                    || operand.contains(char::is_whitespace)
+                    || operand.contains(';')
+                    || opcode.strip_prefix('c').is_some_and(|n| n.parse::<u8>().is_ok())
+                    || opcode == "id";
+                match quote {
+                    true => epd.push_str(&format!(" \"{}\"", operand)),
+                    false => epd.push_str(&format!(" {}", operand)),
+                }
+            }
+            epd.push(';');
+        }
+        epd
+    }
+
+    fn moves(&self, opcode: &str) -> Result<Vec<Move>, String> {
+        let mut board = self.board();
+        let operands = self.operation(opcode).unwrap_or_default();
+        let parse =
+            |san: &String| board.parse_san(san).ok_or(format!("Invalid {}: {}", opcode, san));
+        operands.iter().map(parse).collect()
+    }
+}
+
+///
+/// Reads all the positions, empty lines and lines starting with "#" are skipped
+///
+pub fn read_epd<R: BufRead>(reader: R) -> Vec<Result<EpdPosition, String>> {
+    let lines = reader.lines().map_while(Result::ok);
+    let lines = lines.filter(|line| !line.trim().is_empty() && !line.trim().starts_with('#'));
+    lines.map(|line| EpdPosition::parse(&line)).collect()
+}
+
+// Splits the operations on ";", quoted operands can contain spaces and ";"
+fn parse_operations(text: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut operations = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ';' => {
+                if !tokens.is_empty() {
+                    let opcode = tokens.remove(0);
+                    operations.push((opcode, std::mem::take(&mut tokens)));
+                }
+            }
+            '"' => {
+                let mut operand = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(ch) => operand.push(ch),
+                        None => return Err(format!("Unterminated string: {}", text)),
+                    }
+                }
+                tokens.push(operand);
+            }
+            ch if ch.is_whitespace() => (),
+            ch => {
+                let mut token = ch.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == ';' || next == '"' {
+                        break;
+                    }
+                    token.push(next);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+
+    // The last operation is often not terminated
+    if !tokens.is_empty() {
+        let opcode = tokens.remove(0);
+        operations.push((opcode, tokens));
+    }
+
+    if let Some((opcode, _)) = operations.iter().find(|(op, _)| op.starts_with(char::is_numeric)) {
+        return Err(format!("Invalid opcode: {}", opcode));
+    }
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAC: &str =
+        r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#;
+    const STS: &str = r#"1kr5/3n4/q3p2p/p2n2p1/PppB1P2/5BP1/1P2Q2P/3R2K1 w - - bm f5; id "STS(v1.0) Undermine.001"; c0 "f5=10, Be5+=2, Bf2=3, Bg4=2";"#;
+
+    #[test]
+    fn test_parse_epd() {
+        let epd = EpdPosition::parse(WAC).unwrap();
+        assert_eq!(epd.fen, "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1");
+        assert_eq!(epd.id(), Some("WAC.001"));
+        assert_eq!(epd.best_moves().unwrap().len(), 1);
+        assert!(epd.avoid_moves().unwrap().is_empty());
+        assert_eq!(EpdPosition::parse(&epd.to_epd()).unwrap(), epd);
+
+        let epd = EpdPosition::parse(
+            "4k3/8/8/8/8/8/4P3/4K3 w - - hmvc 7; fmvn 40; am Kd1 Kf1; acd 12; pv e4 Kd7 e5; c1 \"a; b\"",
+        )
+        .unwrap();
+        assert_eq!(epd.fen, "4k3/8/8/8/8/8/4P3/4K3 w - - 7 40");
+        assert_eq!(epd.avoid_moves().unwrap().len(), 2);
+        assert_eq!(epd.acd(), Some(12));
+        assert_eq!(epd.pv().unwrap().len(), 3);
+        assert_eq!(epd.comment(1), Some("a; b"));
+
+        let epd = EpdPosition::parse("4k3/8/8/8/8/8/4P3/4K3 w - - 3 20 bm e4").unwrap();
+        assert_eq!(epd.fen, "4k3/8/8/8/8/8/4P3/4K3 w - - 3 20");
+        assert!(epd.best_moves().is_ok());
+
+        assert!(
+            EpdPosition::parse("4k3/8/8/8/8/8/4P3/4K3 w - - bm e5").unwrap().best_moves().is_err()
+        );
+        assert!(EpdPosition::parse("4k3/8/8/8/8/8/4P3 w - - bm e4").is_err());
+        assert!(EpdPosition::parse(r#"4k3/8/8/8/8/8/4P3/4K3 w - - id "open"#).is_err());
+    }
+
+    #[test]
+    fn test_sts_points() {
+        let epd = EpdPosition::parse(STS).unwrap();
+        let points = epd.points();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0], (epd.best_moves().unwrap()[0], 10));
+
+        let epd = EpdPosition::parse(
+            r#"1kr5/3n4/q3p2p/p2n2p1/PppB1P2/5BP1/1P2Q2P/3R2K1 w - - bm f5; c8 "10 3"; c9 "f4f5 d4f2";"#,
+        )
+        .unwrap();
+        assert_eq!(epd.points().iter().map(|(_, p)| *p).collect::<Vec<_>>(), vec![10, 3]);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod cli;
+pub mod epd;
+pub mod options;
+pub mod pgn;
 pub mod time;
 pub mod uci;
+pub mod uci_client;
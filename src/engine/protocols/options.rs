@@ -0,0 +1,317 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::engine::book::polyglot::{BOOK, MAX_BOOK_VARIETY};
+use crate::engine::evaluation::eval_params::{EVAL_PARAMS, EvalParams, SharedEvalParams};
+use crate::engine::evaluation::nnue::{Network, SharedNetwork};
+use crate::engine::tablebase::dtm::DTM;
+use crate::engine::tablebase::syzygy::{TB, TB_MAX_PIECES};
+
+#[derive(Debug, Clone)]
+pub struct UCIOptions {
+    pub syzygy_path: String,
+    pub syzygy_probe_limit: usize,
+    pub dtm_path: String,
+    pub book_file: String,
+    pub own_book: bool,
+    pub book_variety: usize,
+    pub san_pv: bool,
+    pub hash_stats: bool,
+    pub eval_params_file: String,
+    // Evaluation weights loaded from the EvalParamsFile, the defaults if empty
+    pub eval_params: SharedEvalParams,
+    pub eval_file: String,
+    pub use_nnue: bool,
+    // Network loaded from the EvalFile, used instead of the classical evaluation with Use NNUE
+    pub network: Option<SharedNetwork>,
+    // Extensions: margin per ply below the TT score, margin above beta of the multi-cut,
+    // difference of the traded pieces and the first rank of the passed pawn pushes
+    pub singular_margin: isize,
+    pub multi_cut_margin: isize,
+    pub recapture_margin: isize,
+    pub passed_pawn_rank: usize,
+    // Lowest depth of the history malus, MAX_MALUS_DEPTH turns it off
+    pub history_malus_depth: i8,
+}
+
+const SINGULAR_MARGIN: isize = 2;
+const MULTI_CUT_MARGIN: isize = 0;
+const RECAPTURE_MARGIN: isize = 0;
+const PASSED_PAWN_RANK: usize = 7;
+const MAX_MARGIN: isize = 1000;
+const HISTORY_MALUS_DEPTH: i8 = MAX_MALUS_DEPTH;
+pub const MAX_MALUS_DEPTH: i8 = 64;
+
+impl UCIOptions {
+    pub fn init() -> Self {
+        Self {
+            syzygy_path: String::new(),
+            syzygy_probe_limit: TB_MAX_PIECES,
+            dtm_path: String::new(),
+            book_file: String::new(),
+            own_book: false,
+            book_variety: MAX_BOOK_VARIETY,
+            san_pv: false,
+            hash_stats: false,
+            eval_params_file: String::new(),
+            eval_params: Arc::clone(&EVAL_PARAMS),
+            eval_file: String::new(),
+            use_nnue: false,
+            network: None,
+            singular_margin: SINGULAR_MARGIN,
+            multi_cut_margin: MULTI_CUT_MARGIN,
+            recapture_margin: RECAPTURE_MARGIN,
+            passed_pawn_rank: PASSED_PAWN_RANK,
+            history_malus_depth: HISTORY_MALUS_DEPTH,
+        }
+    }
+
+    ///
+    /// Prints the supported options as part of the "uci" command response
+    ///
+    pub fn print(&self) {
+        println!("option name SyzygyPath type string default <empty>");
+        println!(
+            "option name SyzygyProbeLimit type spin default {} min 0 max {}",
+            TB_MAX_PIECES, TB_MAX_PIECES
+        );
+        println!("option name DtmPath type string default <empty>");
+        println!("option name OwnBook type check default false");
+        println!("option name BookFile type string default <empty>");
+        println!(
+            "option name BookVariety type spin default {} min 0 max {}",
+            MAX_BOOK_VARIETY, MAX_BOOK_VARIETY
+        );
+        println!("option name SanPv type check default false");
+        println!("option name HashStats type check default false");
+        println!("option name EvalParamsFile type string default <empty>");
+        println!("option name EvalFile type string default <empty>");
+        println!("option name Use NNUE type check default false");
+        println!(
+            "option name SingularMargin type spin default {} min 0 max {}",
+            SINGULAR_MARGIN, MAX_MARGIN
+        );
+        println!(
+            "option name MultiCutMargin type spin default {} min 0 max {}",
+            MULTI_CUT_MARGIN, MAX_MARGIN
+        );
+        println!(
+            "option name RecaptureMargin type spin default {} min 0 max {}",
+            RECAPTURE_MARGIN, MAX_MARGIN
+        );
+        println!("option name PassedPawnRank type spin default {} min 2 max 8", PASSED_PAWN_RANK);
+        println!(
+            "option name HistoryMalusDepth type spin default {} min 0 max {}",
+            HISTORY_MALUS_DEPTH, MAX_MALUS_DEPTH
+        );
+    }
+
+    ///
+    /// Parses "setoption name <id> [value <x>]" arguments (without the "setoption")
+    ///
+    pub fn set_option(&mut self, args: &[&str]) {
+        let mut name = Vec::new();
+        let mut value = Vec::new();
+        let mut is_value = false;
+
+        for &arg in args {
+            match arg {
+                "name" => is_value = false,
+                "value" => is_value = true,
+                _ => match is_value {
+                    true => value.push(arg),
+                    false => name.push(arg),
+                },
+            }
+        }
+
+        let name = name.join(" ");
+        let value = value.join(" ");
+
+        match name.to_lowercase().as_str() {
+            "syzygypath" => {
+                self.syzygy_path = if value == "<empty>" { String::new() } else { value };
+                let count = TB.write().unwrap().init_path(&self.syzygy_path);
+                println!("info string Found {} tablebases", count);
+            }
+            "syzygyprobelimit" => {
+                if let Ok(limit) = value.parse::<usize>() {
+                    self.syzygy_probe_limit = limit.min(TB_MAX_PIECES);
+                }
+            }
+            "dtmpath" => {
+                self.dtm_path = if value == "<empty>" { String::new() } else { value };
+                let count = DTM.write().unwrap().init_path(&self.dtm_path);
+                println!("info string Found {} DTM tables", count);
+            }
+            "ownbook" => self.own_book = value.eq_ignore_ascii_case("true"),
+            "bookfile" => {
+                self.book_file = if value == "<empty>" { String::new() } else { value };
+                match BOOK.write().unwrap().load(&self.book_file) {
+                    Ok(count) => println!("info string Loaded {} book entries", count),
+                    Err(err) => eprintln!("[UCI Options]: Failed to load book: {}", err),
+                }
+            }
+            "bookvariety" => {
+                if let Ok(variety) = value.parse::<usize>() {
+                    self.book_variety = variety.min(MAX_BOOK_VARIETY);
+                }
+            }
+            "sanpv" => self.san_pv = value.eq_ignore_ascii_case("true"),
+            "hashstats" => self.hash_stats = value.eq_ignore_ascii_case("true"),
+            "evalparamsfile" => {
+                self.eval_params_file = if value == "<empty>" { String::new() } else { value };
+                if self.eval_params_file.is_empty() {
+                    self.eval_params = Arc::clone(&EVAL_PARAMS);
+                    return;
+                }
+                match EvalParams::load(Path::new(&self.eval_params_file)) {
+                    Ok(params) => {
+                        self.eval_params = Arc::new(params);
+                        println!(
+                            "info string Loaded evaluation weights from {}",
+                            self.eval_params_file
+                        );
+                    }
+                    Err(err) => {
+                        eprintln!("[UCI Options]: Failed to load evaluation weights: {}", err)
+                    }
+                }
+            }
+            "evalfile" => {
+                self.eval_file = if value == "<empty>" { String::new() } else { value };
+                self.network = None;
+                if self.eval_file.is_empty() {
+                    return;
+                }
+                match Network::load(Path::new(&self.eval_file)) {
+                    Ok(network) => {
+                        self.network = Some(Arc::new(network));
+                        println!("info string Loaded network from {}", self.eval_file);
+                    }
+                    Err(err) => eprintln!("[UCI Options]: Failed to load network: {}", err),
+                }
+            }
+            "use nnue" => {
+                self.use_nnue = value.eq_ignore_ascii_case("true");
+                if self.use_nnue && self.network.is_none() {
+                    println!("info string No network loaded, using the classical evaluation");
+                }
+            }
+            "singularmargin" => {
+                if let Ok(margin) = value.parse::<isize>() {
+                    self.singular_margin = margin.clamp(0, MAX_MARGIN);
+                }
+            }
+            "multicutmargin" => {
+                if let Ok(margin) = value.parse::<isize>() {
+                    self.multi_cut_margin = margin.clamp(0, MAX_MARGIN);
+                }
+            }
+            "recapturemargin" => {
+                if let Ok(margin) = value.parse::<isize>() {
+                    self.recapture_margin = margin.clamp(0, MAX_MARGIN);
+                }
+            }
+            "passedpawnrank" => {
+                if let Ok(rank) = value.parse::<usize>() {
+                    self.passed_pawn_rank = rank.clamp(2, 8);
+                }
+            }
+            "historymalusdepth" => {
+                if let Ok(depth) = value.parse::<i8>() {
+                    self.history_malus_depth = depth.clamp(0, MAX_MALUS_DEPTH);
+                }
+            }
+            _ => eprintln!("[UCI Options]: Unknown option: {}", name),
+        }
+    }
+
+    ///
+    /// Network of the evaluation, none for the classical evaluation
+    ///
+    pub fn active_network(&self) -> Option<SharedNetwork> {
+        self.network.as_ref().filter(|_| self.use_nnue).cloned()
+    }
+
+    ///
+    /// Both options evaluate positions the same (same weights and the same network)
+    ///
+    pub fn same_evaluation(&self, other: &UCIOptions) -> bool {
+        let same_network = match (self.active_network(), other.active_network()) {
+            (Some(a), Some(b)) => Arc::ptr_eq(&a, &b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        same_network && Arc::ptr_eq(&self.eval_params, &other.eval_params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_option_probe_limit() {
+        let mut options = UCIOptions::init();
+        options.set_option(&["name", "SyzygyProbeLimit", "value", "5"]);
+        assert_eq!(options.syzygy_probe_limit, 5);
+
+        options.set_option(&["name", "SyzygyProbeLimit", "value", "12"]);
+        assert_eq!(options.syzygy_probe_limit, TB_MAX_PIECES);
+    }
+
+    #[test]
+    fn test_set_option_book() {
+        let mut options = UCIOptions::init();
+        options.set_option(&["name", "OwnBook", "value", "true"]);
+        options.set_option(&["name", "BookVariety", "value", "20"]);
+        assert!(options.own_book);
+        assert_eq!(options.book_variety, 20);
+    }
+
+    #[test]
+    fn test_set_option_extensions() {
+        let mut options = UCIOptions::init();
+        options.set_option(&["name", "SingularMargin", "value", "5"]);
+        options.set_option(&["name", "MultiCutMargin", "value", "-20"]);
+        options.set_option(&["name", "RecaptureMargin", "value", "250"]);
+        options.set_option(&["name", "PassedPawnRank", "value", "7"]);
+        assert_eq!(options.singular_margin, 5);
+        assert_eq!(options.multi_cut_margin, 0);
+        assert_eq!(options.recapture_margin, 250);
+        assert_eq!(options.passed_pawn_rank, 7);
+    }
+
+    #[test]
+    fn test_set_option_eval_params() {
+        let path = std::env::temp_dir().join("fri_challenger_eval_params.txt");
+        std::fs::write(&path, "tempo = 42\n").unwrap();
+
+        let mut options = UCIOptions::init();
+        options.set_option(&["name", "EvalParamsFile", "value", path.to_str().unwrap()]);
+        assert_eq!(options.eval_params.tempo, 42);
+        assert_eq!(options.eval_params.material, EVAL_PARAMS.material);
+
+        options.set_option(&["name", "EvalParamsFile", "value", "<empty>"]);
+        assert!(Arc::ptr_eq(&options.eval_params, &EVAL_PARAMS));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_set_option_nnue() {
+        let path = std::env::temp_dir().join("fri_challenger_network.nnue");
+        Network::zeroed().save(&path).unwrap();
+
+        let mut options = UCIOptions::init();
+        let default = options.clone();
+        options.set_option(&["name", "EvalFile", "value", path.to_str().unwrap()]);
+        assert!(options.network.is_some());
+        assert!(options.active_network().is_none());
+        assert!(options.same_evaluation(&default));
+
+        options.set_option(&["name", "Use", "NNUE", "value", "true"]);
+        assert!(options.active_network().is_some());
+        assert!(!options.same_evaluation(&default));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,680 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::board::moves::Move;
+use crate::engine::board::san::SanTrait;
+use crate::engine::misc::const_utility::FEN_START;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+
+// NOTE: PGN (Portable Game Notation) import and export
+//
+// The reader streams the games one at a time and replays every move (variations included)
+// on a board. A game that can't be parsed or replayed is returned as an error, and the
+// reader continues with the next game.
+
+pub const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+pub const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+// Move suffix annotations and their NAG equivalents
+const SUFFIX_NAGS: [(&str, u8); 6] =
+    [("!", 1), ("?", 2), ("!!", 3), ("??", 4), ("!?", 5), ("?!", 6)];
+
+const LINE_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub mv: Move,
+    pub san: String,
+    pub nags: Vec<u8>,
+    // Comment before the first move of a line
+    pub comment_before: Option<String>,
+    pub comment: Option<String>,
+    // Alternatives to this move
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    pub fn init(mv: Move, san: String) -> Self {
+        Self {
+            mv,
+            san,
+            nags: Vec::new(),
+            comment_before: None,
+            comment: None,
+            variations: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<PgnMove>,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn init() -> Self {
+        let tags =
+            SEVEN_TAG_ROSTER.iter().map(|(name, value)| (name.to_string(), value.to_string()));
+        Self { tags: tags.collect(), moves: Vec::new(), result: "*".to_string() }
+    }
+
+    ///
+    /// Creates the game from the moves played from the position, the comments (e.g. engine
+    /// evaluations) are matched with the moves by their index
+    ///
+    pub fn from_moves(
+        fen: &str,
+        moves: &[Move],
+        comments: &[Option<String>],
+    ) -> Result<Self, String> {
+        let mut board = Board::try_read_fen(fen).ok_or(format!("Invalid FEN: {}", fen))?;
+        let mut game = PgnGame::init();
+        if fen != FEN_START {
+            game.set_tag("SetUp", "1");
+            game.set_tag("FEN", fen);
+        }
+
+        for (idx, mv) in moves.iter().enumerate() {
+            if !board.legal_moves().contains(mv) {
+                return Err(format!("Illegal move at ply {}: {:?}", idx, mv));
+            }
+
+            let mut pgn_move = PgnMove::init(*mv, board.move_to_san(mv));
+            pgn_move.comment = comments.get(idx).cloned().flatten();
+            board.play_root_move(mv);
+            game.moves.push(pgn_move);
+        }
+        Ok(game)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn start_fen(&self) -> &str {
+        self.tag("FEN").unwrap_or(FEN_START)
+    }
+
+    pub fn start_board(&self) -> Result<Board, String> {
+        Board::try_read_fen(self.start_fen()).ok_or(format!("Invalid FEN: {}", self.start_fen()))
+    }
+
+    pub fn mainline(&self) -> Vec<Move> {
+        self.moves.iter().map(|pgn_move| pgn_move.mv).collect()
+    }
+
+    ///
+    /// Position at the end of the main line
+    ///
+    pub fn board(&self) -> Result<Board, String> {
+        let mut board = self.start_board()?;
+        for pgn_move in &self.moves {
+            board.play_root_move(&pgn_move.mv);
+        }
+        Ok(board)
+    }
+
+    ///
+    /// Exports the game in the PGN export format (seven tag roster first, lines of at
+    /// most 80 characters)
+    ///
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.as_str(),
+                _ => self.tag(name).unwrap_or(default),
+            };
+            pgn.push_str(&format_tag(name, value));
+        }
+
+        let roster = |name: &str| SEVEN_TAG_ROSTER.iter().any(|(tag, _)| *tag == name);
+        for (name, value) in self.tags.iter().filter(|(name, _)| !roster(name)) {
+            pgn.push_str(&format_tag(name, value));
+        }
+        pgn.push('\n');
+
+        // Plies are counted from the first move of the game, so that odd plies are black moves
+        let fen: Vec<&str> = self.start_fen().split_whitespace().collect();
+        let full_move = fen.get(5).and_then(|n| n.parse::<usize>().ok()).unwrap_or(1).max(1);
+        let ply = 2 * (full_move - 1) + (fen.get(1) == Some(&"b")) as usize;
+
+        let mut tokens = Vec::new();
+        movetext_tokens(&self.moves, ply, &mut tokens);
+        tokens.push(self.result.clone());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push_str("\n\n");
+        pgn
+    }
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Words of the comment as separate tokens, so that long comments can be wrapped
+fn comment_tokens(comment: &str, tokens: &mut Vec<String>) {
+    let words: Vec<String> =
+        comment.replace('}', "").split_whitespace().map(String::from).collect();
+    match words.len() {
+        0 => tokens.push("{}".to_string()),
+        len => {
+            let start = tokens.len();
+            tokens.extend(words);
+            tokens[start] = format!("{{{}", tokens[start]);
+            tokens[start + len - 1].push('}');
+        }
+    }
+}
+
+fn movetext_tokens(moves: &[PgnMove], mut ply: usize, tokens: &mut Vec<String>) {
+    let mut move_number = true;
+    for pgn_move in moves {
+        if let Some(comment) = &pgn_move.comment_before {
+            comment_tokens(comment, tokens);
+            move_number = true;
+        }
+
+        // Black moves are numbered only at the start of a line, or after a comment or variation
+        if ply.is_multiple_of(2) {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        } else if move_number {
+            tokens.push(format!("{}...", ply / 2 + 1));
+        }
+
+        tokens.push(pgn_move.san.clone());
+        tokens.extend(pgn_move.nags.iter().map(|nag| format!("${}", nag)));
+        move_number = false;
+
+        if let Some(comment) = &pgn_move.comment {
+            comment_tokens(comment, tokens);
+            move_number = true;
+        }
+
+        for variation in &pgn_move.variations {
+            let start = tokens.len();
+            movetext_tokens(variation, ply, tokens);
+            tokens[start] = format!("({}", tokens[start]);
+            tokens.last_mut().unwrap().push(')');
+            move_number = true;
+        }
+        ply += 1;
+    }
+}
+
+///
+/// Streams the games from the reader, every game is either parsed completely or returned
+/// as an error (e.g. an illegal move), so that one bad game doesn't stop the whole file
+///
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    lexer: Lexer,
+    parser: GameParser,
+    ready: VecDeque<Result<PgnGame, String>>,
+    line_number: usize,
+    done: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn init(reader: R) -> Self {
+        Self {
+            reader,
+            lexer: Lexer { comment: None },
+            parser: GameParser::default(),
+            ready: VecDeque::new(),
+            line_number: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(game) = self.ready.pop_front() {
+                return Some(game);
+            }
+            if self.done {
+                return None;
+            }
+
+            let mut bytes = Vec::new();
+            match self.reader.read_until(b'\n', &mut bytes) {
+                Ok(0) => {
+                    self.done = true;
+                    if !self.parser.is_empty() {
+                        self.ready.push_back(self.parser.finish());
+                    }
+                }
+                Ok(_) => {
+                    self.line_number += 1;
+                    let line = String::from_utf8_lossy(&bytes);
+                    for token in self.lexer.tokens(&line) {
+                        if let Some(game) = self.parser.push(token, self.line_number) {
+                            self.ready.push_back(game);
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.done = true;
+                    self.ready.push_back(Err(format!("Line {}: {}", self.line_number + 1, err)));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    VariationStart,
+    VariationEnd,
+    Move(String),
+    Result(String),
+    Invalid(String),
+}
+
+struct Lexer {
+    // Text of a {comment} that continues on the next line
+    comment: Option<String>,
+}
+
+impl Lexer {
+    fn tokens(&mut self, line: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        if self.comment.is_none() && line.starts_with('%') {
+            return tokens;
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        let mut idx = 0;
+        while idx < chars.len() {
+            if let Some(comment) = &mut self.comment {
+                match chars[idx..].iter().position(|&ch| ch == '}') {
+                    Some(end) => {
+                        comment.extend(&chars[idx..idx + end]);
+                        tokens.push(Token::Comment(
+                            comment.split_whitespace().collect::<Vec<_>>().join(" "),
+                        ));
+                        self.comment = None;
+                        idx += end + 1;
+                    }
+                    None => {
+                        comment.extend(&chars[idx..]);
+                        comment.push(' ');
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            let ch = chars[idx];
+            idx += 1;
+            match ch {
+                _ if ch.is_whitespace() => {}
+                '{' => self.comment = Some(String::new()),
+                ';' => {
+                    let comment: String = chars[idx..].iter().collect();
+                    tokens.push(Token::Comment(comment.trim().to_string()));
+                    break;
+                }
+                '(' => tokens.push(Token::VariationStart),
+                ')' => tokens.push(Token::VariationEnd),
+                '[' => {
+                    let end = tag_end(&chars, idx);
+                    let tag: String = chars[idx..end.unwrap_or(chars.len())].iter().collect();
+                    tokens.push(match end.and_then(|_| parse_tag(&tag)) {
+                        Some((name, value)) => Token::Tag(name, value),
+                        None => Token::Invalid(format!("Invalid tag: [{}", tag)),
+                    });
+                    idx = end.map_or(chars.len(), |end| end + 1);
+                }
+                '$' => {
+                    let digits: String =
+                        chars[idx..].iter().take_while(|ch| ch.is_ascii_digit()).collect();
+                    idx += digits.len();
+                    tokens.push(match digits.parse() {
+                        Ok(nag) => Token::Nag(nag),
+                        Err(_) => Token::Invalid(format!("Invalid NAG: ${}", digits)),
+                    });
+                }
+                _ => {
+                    let symbol: String = std::iter::once(ch)
+                        .chain(chars[idx..].iter().copied().take_while(|&ch| !is_delimiter(ch)))
+                        .collect();
+                    idx += symbol.chars().count() - 1;
+                    symbol_tokens(&symbol, &mut tokens);
+                }
+            }
+        }
+        tokens
+    }
+}
+
+fn is_delimiter(ch: char) -> bool {
+    ch.is_whitespace() || "{}()[];$".contains(ch)
+}
+
+// Index of the "]" that closes the tag, skipping the quoted value
+fn tag_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (idx, &ch) in chars.iter().enumerate().skip(start) {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ']' if !quoted => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Parses the inside of the tag: Name "Value"
+fn parse_tag(tag: &str) -> Option<(String, String)> {
+    let (name, value) = tag.trim().split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+// Splits the symbol into the result, or the move (without its number) and its annotation
+fn symbol_tokens(symbol: &str, tokens: &mut Vec<Token>) {
+    if RESULTS.contains(&symbol) {
+        tokens.push(Token::Result(symbol.to_string()));
+        return;
+    }
+
+    // Move numbers can be attached to the move ("12.e4", "12...e5")
+    let mut san = symbol;
+    let without_number = symbol.trim_start_matches(|ch: char| ch.is_ascii_digit());
+    if without_number.starts_with('.') {
+        san = without_number.trim_start_matches('.');
+    }
+    if san.is_empty() {
+        return;
+    }
+
+    let annotation_start = san.trim_end_matches(['!', '?']).len();
+    let (san, annotation) = san.split_at(annotation_start);
+    if san.chars().any(|ch| !ch.is_ascii_alphanumeric() && !"+#=-x".contains(ch)) {
+        tokens.push(Token::Invalid(format!("Invalid move: {}", symbol)));
+        return;
+    }
+
+    tokens.push(Token::Move(san.to_string()));
+    if let Some((_, nag)) = SUFFIX_NAGS.iter().find(|(suffix, _)| *suffix == annotation) {
+        tokens.push(Token::Nag(*nag));
+    }
+}
+
+// Line of moves that is being parsed, with the board at its end
+struct Line {
+    moves: Vec<PgnMove>,
+    board: Board,
+    comment: Option<String>,
+}
+
+impl Line {
+    fn init(board: Board) -> Self {
+        Self { moves: Vec::new(), board, comment: None }
+    }
+}
+
+#[derive(Default)]
+struct GameParser {
+    game: PgnGame,
+    // The main line followed by the open variations
+    lines: Vec<Line>,
+    movetext: bool,
+    error: Option<String>,
+}
+
+impl GameParser {
+    fn is_empty(&self) -> bool {
+        self.game.tags.is_empty() && !self.movetext
+    }
+
+    ///
+    /// Returns the game when the token completes it: the result, or a tag after the movetext
+    ///
+    fn push(&mut self, token: Token, line_number: usize) -> Option<Result<PgnGame, String>> {
+        match token {
+            Token::Tag(..) if self.movetext => {
+                let game = self.finish();
+                self.push(token, line_number);
+                return Some(game);
+            }
+            Token::Tag(name, value) => self.game.tags.push((name, value)),
+            Token::Result(result) if self.lines.len() <= 1 => {
+                self.game.result = result;
+                return Some(self.finish());
+            }
+            // Some programs write the result at the end of variations
+            Token::Result(_) => {}
+            _ if self.error.is_some() => self.movetext = true,
+            token => {
+                self.movetext = true;
+                if let Err(err) = self.movetext(token) {
+                    self.error = Some(format!("Line {}: {}", line_number, err));
+                }
+            }
+        }
+        None
+    }
+
+    fn movetext(&mut self, token: Token) -> Result<(), String> {
+        if self.lines.is_empty() {
+            self.lines.push(Line::init(self.game.start_board()?));
+        }
+
+        let line = self.lines.last_mut().unwrap();
+        match token {
+            Token::Move(san) => {
+                let mv = line.board.parse_san(&san).ok_or(format!("Illegal move: {}", san))?;
+                let mut pgn_move = PgnMove::init(mv, line.board.move_to_san(&mv));
+                pgn_move.comment_before = line.comment.take();
+                line.board.play_root_move(&mv);
+                line.moves.push(pgn_move);
+            }
+            Token::Nag(nag) => {
+                let pgn_move = line.moves.last_mut().ok_or("NAG before the first move")?;
+                pgn_move.nags.push(nag);
+            }
+            Token::Comment(text) => {
+                let comment = match line.moves.last_mut() {
+                    Some(pgn_move) => &mut pgn_move.comment,
+                    None => &mut line.comment,
+                };
+                *comment = Some(match comment.take() {
+                    Some(old) => format!("{} {}", old, text),
+                    None => text,
+                });
+            }
+            Token::VariationStart => {
+                if line.moves.is_empty() {
+                    return Err("Variation before the first move".to_string());
+                }
+
+                // The variation is played instead of the last move
+                let mut board = line.board.clone();
+                board.undo_root_move(&line.moves.last().unwrap().mv);
+                self.lines.push(Line::init(board));
+            }
+            Token::VariationEnd => {
+                if self.lines.len() < 2 {
+                    return Err("Unexpected end of variation".to_string());
+                }
+
+                let variation = self.lines.pop().unwrap();
+                if !variation.moves.is_empty() {
+                    let parent = self.lines.last_mut().unwrap();
+                    parent.moves.last_mut().unwrap().variations.push(variation.moves);
+                }
+            }
+            Token::Invalid(err) => return Err(err),
+            Token::Tag(..) | Token::Result(_) => unreachable!("Handled by the caller"),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<PgnGame, String> {
+        let parser = std::mem::take(self);
+        if let Some(err) = parser.error {
+            return Err(err);
+        }
+        if parser.lines.len() > 1 {
+            return Err("Unterminated variation".to_string());
+        }
+
+        let mut game = parser.game;
+        if let Some(line) = parser.lines.into_iter().next() {
+            game.moves = line.moves;
+        }
+        if game.result.is_empty() {
+            game.result = game.tag("Result").unwrap_or("*").to_string();
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::piece::BLACK_KING;
+    use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+    const PGN: &str = r#"
+[Event "Casual \"Game\""]
+[White "A"]
+[Black "B"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6 $1 3. Bb5!? a6
+; the Ruy Lopez
+4. Ba4 1-0
+
+[Event "Broken"]
+[Result "*"]
+
+1. e4 e5 2. Ke3 Nc6 *
+
+[Event "Setup"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 30"]
+[Result "1/2-1/2"]
+
+30... Kd7 31. e4 {a long comment
+over two lines} Ke6 1/2-1/2
+"#;
+
+    fn read(pgn: &str) -> Vec<Result<PgnGame, String>> {
+        PgnReader::init(pgn.as_bytes()).collect()
+    }
+
+    #[test]
+    fn test_read_games() {
+        let games = read(PGN);
+        assert_eq!(games.len(), 3);
+        assert!(games[1].as_ref().unwrap_err().contains("Ke3"));
+
+        let game = games[0].as_ref().unwrap();
+        assert_eq!(game.tag("Event"), Some("Casual \"Game\""));
+        assert_eq!(game.result, "1-0");
+        assert_eq!(game.moves.len(), 7);
+        assert_eq!(game.moves[0].comment.as_deref(), Some("best by test"));
+        assert_eq!(game.moves[3].nags, vec![1]);
+        assert_eq!(game.moves[4].nags, vec![5]);
+        assert_eq!(game.moves[5].comment.as_deref(), Some("the Ruy Lopez"));
+
+        let variation = &game.moves[2].variations[0];
+        assert_eq!(variation.len(), 3);
+        assert_eq!(variation[1].variations[0][0].san, "d5");
+
+        let game = games[2].as_ref().unwrap();
+        assert_eq!(game.moves.len(), 3);
+        assert_eq!(game.moves[1].comment.as_deref(), Some("a long comment over two lines"));
+        assert_eq!(game.board().unwrap().squares[44], BLACK_KING);
+    }
+
+    #[test]
+    fn test_read_repetition() {
+        let pgn = "1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 (3. e4 e5) 3... Nf6 4. Ng1 Ng8 *";
+        let game = read(pgn).remove(0).unwrap();
+        assert_eq!(game.moves.len(), 8);
+        assert_eq!(game.moves[4].variations[0][1].san, "e5");
+        assert!(game.board().unwrap().is_repetition());
+    }
+
+    #[test]
+    fn test_write_roundtrip() {
+        for game in read(PGN).into_iter().flatten() {
+            let pgn = game.to_pgn();
+            assert!(pgn.lines().all(|line| line.len() <= LINE_WIDTH));
+
+            let read_again = read(&pgn);
+            assert_eq!(read_again.len(), 1);
+
+            let read_again = read_again[0].as_ref().unwrap();
+            assert_eq!(read_again.moves, game.moves);
+            assert_eq!(read_again.result, game.result);
+            assert!(game.tags.iter().all(|(name, value)| read_again.tag(name) == Some(value)));
+        }
+    }
+
+    #[test]
+    fn test_write_from_moves() {
+        let mut board = Board::read_fen(FEN_START);
+        let mut moves = Vec::new();
+        for san in ["f3", "e5", "g4", "Qh4"] {
+            let mv = board.parse_san(san).unwrap();
+            board.play_root_move(&mv);
+            moves.push(mv);
+        }
+
+        let comments = [None, Some("+0.30/12".to_string())];
+        let mut game = PgnGame::from_moves(FEN_START, &moves, &comments).unwrap();
+        game.result = "0-1".to_string();
+
+        let pgn = game.to_pgn();
+        assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]"));
+        assert!(pgn.contains("[Result \"0-1\"]\n\n1. f3 e5 {+0.30/12} 2. g4 Qh4# 0-1\n"));
+    }
+}
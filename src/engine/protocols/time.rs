@@ -30,5 +30,6 @@ pub fn time_over(search: &Search) -> bool {
     let elapsed = search.uci.start_time.elapsed();
     let limit = search.uci.time_limit.unwrap_or(Duration::from_millis(u64::MAX));
     let stopped = search.uci.stopped.load(Ordering::Relaxed);
-    elapsed >= limit || stopped
+    let nodes_over = search.uci.max_nodes.is_some_and(|max| search.info.nodes >= max);
+    elapsed >= limit || stopped || nodes_over
 }
@@ -1,10 +1,13 @@
+use super::options::UCIOptions;
 use super::time::set_time_limit;
 use crate::engine::board::board::Board;
 use crate::engine::board::color::ColorTrait;
 use crate::engine::board::fen::FenTrait;
 use crate::engine::board::moves::Move;
+use crate::engine::book::polyglot::BOOK;
+use crate::engine::evaluation::breakdown_eval::BreakdownEvalTrait;
 use crate::engine::misc::const_utility::FEN_START;
-use crate::engine::misc::display::display_moves::{from_move_notation, move_notation};
+use crate::engine::misc::display::display_moves::{move_notation, try_from_move_notation};
 use crate::engine::move_generator::make_move::BoardMoveTrait;
 use crate::engine::search::iter_deepening::Search;
 use std::io::BufRead;
@@ -21,6 +24,7 @@ pub struct UCITime {
     pub moves_togo: usize,
     pub infinite: bool,
     pub max_depth: i8,
+    pub max_nodes: Option<usize>,
     pub quit: bool,
     pub stopped: Arc<AtomicBool>,
 }
@@ -33,6 +37,7 @@ impl UCITime {
             moves_togo: 0,
             infinite: false,
             max_depth: 63,
+            max_nodes: None,
             quit: false,
             stopped: Arc::new(AtomicBool::new(false)),
         }
@@ -43,6 +48,7 @@ impl UCITime {
 pub struct UCI {
     pub board: Board,
     pub uci: UCITime,
+    pub options: UCIOptions,
     pub search_thread: Option<JoinHandle<()>>,
     pub is_searching: Arc<AtomicBool>,
 }
@@ -52,6 +58,7 @@ impl UCI {
         UCI {
             board: Board::initialize(),
             uci: UCITime::init(),
+            options: UCIOptions::init(),
             search_thread: None,
             is_searching: Arc::new(AtomicBool::new(false)),
         }
@@ -91,8 +98,10 @@ impl UCI {
                         "stop" => self.uci_stop(),
                         "isready" => self.uci_is_ready(),
                         "ucinewgame" => self.uci_new_game(),
+                        "setoption" => self.uci_set_option(&args[1..]),
                         "position" => self.uci_position(&args[1..]),
                         "go" => self.uci_go(&args[1..]),
+                        "eval" => self.uci_eval(&args[1..]),
                         _ => eprintln!("[Main Loop Thread]: Unknown command: {}", args[0]),
                     }
                 }
@@ -113,9 +122,24 @@ impl UCI {
     fn uci_metadata(&mut self) {
         println!("id name {}", "FRI Challenger 0.5.0");
         println!("id author Nikola Simjanovski");
+        self.options.print();
         println!("uciok");
     }
 
+    // Set the value of an engine option
+    fn uci_set_option(&mut self, args: &[&str]) {
+        self.abort_search();
+        let previous = self.options.clone();
+        self.options.set_option(args);
+
+        // The hash tables hold scores of the previous evaluation
+        if !self.options.same_evaluation(&previous) {
+            self.board.tt.write().unwrap().clear();
+            self.board.pawn_tt.write().unwrap().clear();
+            self.board.eval_tt.clear();
+        }
+    }
+
     // Stop the current search
     fn uci_stop(&mut self) {
         self.stop_search();
@@ -126,6 +150,15 @@ impl UCI {
         println!("readyok");
     }
 
+    // Print the evaluation of the current position term by term [Non UCI: "eval [json]"]
+    fn uci_eval(&mut self, args: &[&str]) {
+        let breakdown = self.board.clone().evaluation_breakdown();
+        match args.first() {
+            Some(&"json") => println!("{}", breakdown.to_json()),
+            _ => println!("{}", breakdown),
+        }
+    }
+
     // Start a new game
     fn uci_new_game(&mut self) {
         self.abort_search();
@@ -157,14 +190,20 @@ impl UCI {
             }
         }
 
-        // Apply FEN on to the board
-        self.board = Board::read_fen(&fen.join(" "));
+        // Apply FEN on to the board, an invalid one leaves the position unchanged
+        let fen = fen.join(" ");
+        let Some(board) = Board::try_read_fen(&fen) else {
+            eprintln!("[UCI Position]: Invalid FEN: {}", fen);
+            return;
+        };
+        self.board = board;
 
         for str_mv in moves {
-            let mv = from_move_notation(str_mv, &mut self.board);
-            self.board.make_move(&mv);
-            // Must be removed every time so that it does not exceed ply (64 moves)
-            self.board.moves.pop();
+            let mv = try_from_move_notation(str_mv, &mut self.board);
+            if !mv.is_some_and(|mv| self.board.play_root_move(&mv)) {
+                eprintln!("[UCI Position]: Illegal move: {}", str_mv);
+                return;
+            }
         }
     }
 
@@ -172,6 +211,7 @@ impl UCI {
         self.abort_search();
 
         let mut depth: Option<i8> = None;
+        let mut nodes: Option<usize> = None;
         let mut infinite = false;
         let mut time_limit: Option<Duration> = None;
 
@@ -186,8 +226,13 @@ impl UCI {
             match *arg {
                 "searchmoves" => (), // TODO:
                 "ponder" => (),      // TODO:
-                "nodes" => (),       // TODO:
-                "mate" => (),        // TODO:
+                "nodes" => {
+                    if let Some(n) = iter.next().and_then(|v| v.parse().ok()) {
+                        nodes = Some(n);
+                        infinite = false;
+                    }
+                }
+                "mate" => (), // TODO:
                 "wtime" => wtime = iter.next().and_then(|v| v.parse().ok()),
                 "btime" => btime = iter.next().and_then(|v| v.parse().ok()),
                 "winc" => winc = iter.next().and_then(|v| v.parse().ok()),
@@ -213,8 +258,11 @@ impl UCI {
         self.uci.start_time = Instant::now();
         self.uci.infinite = infinite;
         self.uci.max_depth = depth.unwrap_or(63);
+        self.uci.max_nodes = nodes;
 
-        if !infinite && matches!(time_limit, None) && self.board.state.color.is_white() {
+        if nodes.is_some() && time_limit.is_none() {
+            self.uci.time_limit = Some(Duration::from_millis(u64::MAX));
+        } else if !infinite && matches!(time_limit, None) && self.board.state.color.is_white() {
             self.uci.time_limit = Some(set_time_limit(
                 moves_togo.unwrap_or(30),
                 wtime.unwrap_or(0),
@@ -232,9 +280,33 @@ impl UCI {
 
         self.is_searching.store(false, Ordering::Relaxed);
 
+        if !infinite && self.play_book_move() {
+            return;
+        }
+
         self.create_search_thread();
     }
 
+    ///
+    /// Plays the move from the opening book without searching, if there is one
+    ///
+    fn play_book_move(&mut self) -> bool {
+        if !self.options.own_book {
+            return false;
+        }
+
+        let mut board = self.board.clone();
+        let book_move = BOOK.read().unwrap().probe(&mut board, self.options.book_variety);
+        match book_move {
+            Some(mv) => {
+                let mv_notation = move_notation(mv.from, mv.to, mv.flag.get_promo_piece());
+                println!("bestmove {}", mv_notation);
+                true
+            }
+            None => false,
+        }
+    }
+
     ///
     /// Creates and starts a new search thread
     ///
@@ -243,6 +315,7 @@ impl UCI {
         let board_clone = self.board.clone();
         let uci_clone = self.uci.clone();
         let mut search = Search::init(board_clone, uci_clone);
+        search.options = self.options.clone();
 
         let handle = thread::spawn(move || {
             let best_move: Option<Move> = search.iterative_deepening();
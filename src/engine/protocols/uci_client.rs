@@ -0,0 +1,451 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// NOTE: UCI client, drives an external engine (or another build of this one) as a subprocess
+//
+// A thread reads the engine's output line by line, so that every command can wait for its
+// answer with a timeout. When the engine exits, the output closes and the waiting command
+// fails with a crash error.
+
+// Time given to the engine for the "uci" and "isready" answers
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    // No answer to the command in time
+    Timeout(String),
+    // The engine exited, with its exit status if it is known
+    Crashed(Option<ExitStatus>),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Io(err) => write!(f, "I/O error: {}", err),
+            ClientError::Timeout(cmd) => write!(f, "No answer to \"{}\" in time", cmd),
+            ClientError::Crashed(Some(status)) => write!(f, "Engine exited ({})", status),
+            ClientError::Crashed(None) => write!(f, "Engine exited"),
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Cp(i32),
+    // Moves to mate, negative if the engine is getting mated
+    Mate(i32),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InfoLine {
+    pub depth: Option<u32>,
+    pub seldepth: Option<u32>,
+    pub multipv: Option<u32>,
+    pub score: Option<Score>,
+    pub lowerbound: bool,
+    pub upperbound: bool,
+    pub nodes: Option<u64>,
+    pub nps: Option<u64>,
+    pub tbhits: Option<u64>,
+    pub hashfull: Option<u32>,
+    // Milliseconds
+    pub time: Option<u64>,
+    pub currmove: Option<String>,
+    pub pv: Vec<String>,
+    pub string: Option<String>,
+}
+
+impl InfoLine {
+    ///
+    /// Parses the line after "info", the unknown tokens are skipped
+    ///
+    pub fn parse(line: &str) -> Self {
+        let mut info = InfoLine::default();
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let tokens = tokens.strip_prefix(&["info"]).unwrap_or(&tokens);
+
+        let mut idx = 0;
+        while idx < tokens.len() {
+            let next = tokens.get(idx + 1).copied().unwrap_or_default();
+            // Number of tokens used: the name and its value by default
+            let mut step = 2;
+            match tokens[idx] {
+                "depth" => info.depth = next.parse().ok(),
+                "seldepth" => info.seldepth = next.parse().ok(),
+                "multipv" => info.multipv = next.parse().ok(),
+                "nodes" => info.nodes = next.parse().ok(),
+                "nps" => info.nps = next.parse().ok(),
+                "tbhits" => info.tbhits = next.parse().ok(),
+                "hashfull" => info.hashfull = next.parse().ok(),
+                "time" => info.time = next.parse().ok(),
+                "currmove" => info.currmove = Some(next.to_string()),
+                "score" => {
+                    let value = tokens.get(idx + 2).and_then(|v| v.parse().ok());
+                    info.score = match (next, value) {
+                        ("cp", Some(cp)) => Some(Score::Cp(cp)),
+                        ("mate", Some(mate)) => Some(Score::Mate(mate)),
+                        _ => None,
+                    };
+                    step = 3;
+                }
+                "lowerbound" => (info.lowerbound, step) = (true, 1),
+                "upperbound" => (info.upperbound, step) = (true, 1),
+                // The rest of the line
+                "pv" => {
+                    info.pv = tokens[idx + 1..].iter().map(|mv| mv.to_string()).collect();
+                    break;
+                }
+                "string" => {
+                    info.string = Some(tokens[idx + 1..].join(" "));
+                    break;
+                }
+                _ => step = 1,
+            }
+            idx += step;
+        }
+        info
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestMove {
+    pub mv: String,
+    pub ponder: Option<String>,
+}
+
+impl BestMove {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("bestmove") {
+            return None;
+        }
+
+        let mv = tokens.next()?.to_string();
+        let ponder = match tokens.next() {
+            Some("ponder") => tokens.next().map(String::from),
+            _ => None,
+        };
+        Some(Self { mv, ponder })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best: BestMove,
+    // All the info lines of the search, the last one usually holds the final score
+    pub infos: Vec<InfoLine>,
+}
+
+impl SearchResult {
+    ///
+    /// Score of the deepest iteration
+    ///
+    pub fn score(&self) -> Option<Score> {
+        self.infos.iter().rev().find_map(|info| info.score)
+    }
+}
+
+///
+/// Limits of the "go" command, the times are in milliseconds
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoParams {
+    pub wtime: Option<u64>,
+    pub btime: Option<u64>,
+    pub winc: Option<u64>,
+    pub binc: Option<u64>,
+    pub movestogo: Option<u64>,
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<u64>,
+    pub infinite: bool,
+}
+
+impl GoParams {
+    pub fn to_command(&self) -> String {
+        let params = [
+            ("wtime", self.wtime),
+            ("btime", self.btime),
+            ("winc", self.winc),
+            ("binc", self.binc),
+            ("movestogo", self.movestogo),
+            ("depth", self.depth.map(u64::from)),
+            ("nodes", self.nodes),
+            ("movetime", self.movetime),
+        ];
+
+        let mut cmd = String::from("go");
+        for (name, value) in params {
+            if let Some(value) = value {
+                cmd.push_str(&format!(" {} {}", name, value));
+            }
+        }
+        if self.infinite {
+            cmd.push_str(" infinite");
+        }
+        cmd
+    }
+}
+
+pub struct UciClient {
+    pub name: String,
+    pub author: String,
+    // The "option name ..." lines of the handshake
+    pub options: Vec<String>,
+
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl UciClient {
+    ///
+    /// Starts the engine and waits for the end of the "uci" handshake
+    ///
+    pub fn spawn(path: &str, args: &[&str]) -> Result<Self, ClientError> {
+        let mut child = Command::new(path)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("Engine stdin is piped");
+        let stdout = child.stdout.take().expect("Engine stdout is piped");
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Self {
+            name: String::new(),
+            author: String::new(),
+            options: Vec::new(),
+            child,
+            stdin,
+            lines,
+        };
+
+        client.send("uci")?;
+        for line in client.read_until("uci", HANDSHAKE_TIMEOUT, |line| line == "uciok")? {
+            if let Some(name) = line.strip_prefix("id name ") {
+                client.name = name.trim().to_string();
+            } else if let Some(author) = line.strip_prefix("id author ") {
+                client.author = author.trim().to_string();
+            } else if line.starts_with("option ") {
+                client.options.push(line);
+            }
+        }
+        Ok(client)
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), ClientError> {
+        self.send(&format!("setoption name {} value {}", name, value))
+    }
+
+    ///
+    /// Waits until the engine has processed all the previous commands
+    ///
+    pub fn is_ready(&mut self) -> Result<(), ClientError> {
+        self.send("isready")?;
+        self.read_until("isready", HANDSHAKE_TIMEOUT, |line| line == "readyok")?;
+        Ok(())
+    }
+
+    pub fn new_game(&mut self) -> Result<(), ClientError> {
+        self.send("ucinewgame")?;
+        self.is_ready()
+    }
+
+    ///
+    /// Sets the position from the FEN (the starting position if None) and the UCI moves
+    ///
+    pub fn position(&mut self, fen: Option<&str>, moves: &[String]) -> Result<(), ClientError> {
+        let mut cmd = match fen {
+            Some(fen) => format!("position fen {}", fen),
+            None => "position startpos".to_string(),
+        };
+        if !moves.is_empty() {
+            cmd.push_str(" moves ");
+            cmd.push_str(&moves.join(" "));
+        }
+        self.send(&cmd)
+    }
+
+    ///
+    /// Starts the search and waits for the best move. The timeout should leave the engine
+    /// some margin over its own limits (e.g. the remaining clock plus a second)
+    ///
+    pub fn go(
+        &mut self,
+        params: &GoParams,
+        timeout: Duration,
+    ) -> Result<SearchResult, ClientError> {
+        // Output of a previous search that timed out (e.g. its late "bestmove")
+        while self.lines.try_recv().is_ok() {}
+
+        let cmd = params.to_command();
+        self.send(&cmd)?;
+
+        self.read_result(&cmd, timeout)
+    }
+
+    ///
+    /// Stops the running search and waits for its best move, so a late "bestmove" can't be
+    /// taken for the result of the next search
+    ///
+    pub fn stop(&mut self, timeout: Duration) -> Result<SearchResult, ClientError> {
+        self.send("stop")?;
+        self.read_result("stop", timeout)
+    }
+
+    ///
+    /// True while the engine process is running
+    ///
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    ///
+    /// Sends "quit" and waits for the engine to exit, it is killed if it doesn't exit in time
+    ///
+    pub fn quit(mut self, timeout: Duration) -> Result<ExitStatus, ClientError> {
+        // The engine may already be gone
+        let _ = self.send("quit");
+
+        let start = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(status);
+            }
+            if start.elapsed() >= timeout {
+                self.child.kill()?;
+                return Ok(self.child.wait()?);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    pub fn send(&mut self, cmd: &str) -> Result<(), ClientError> {
+        let sent = writeln!(self.stdin, "{}", cmd).and_then(|_| self.stdin.flush());
+        match sent {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Err(self.crashed()),
+            Err(err) => Err(ClientError::Io(err)),
+        }
+    }
+
+    // Collects the lines until (and including) the one that ends the answer to the command
+    fn read_until(
+        &mut self,
+        cmd: &str,
+        timeout: Duration,
+        mut is_last: impl FnMut(&str) -> bool,
+    ) -> Result<Vec<String>, ClientError> {
+        let deadline = Instant::now() + timeout;
+        let mut lines = Vec::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(remaining) {
+                Ok(line) => {
+                    let line = line.trim().to_string();
+                    let last = is_last(&line);
+                    lines.push(line);
+                    if last {
+                        return Ok(lines);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(ClientError::Timeout(cmd.to_string()));
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(self.crashed()),
+            }
+        }
+    }
+
+    fn read_result(&mut self, cmd: &str, timeout: Duration) -> Result<SearchResult, ClientError> {
+        let lines = self.read_until(cmd, timeout, |line| line.starts_with("bestmove"))?;
+        let infos =
+            lines.iter().filter(|line| line.starts_with("info")).map(|l| InfoLine::parse(l));
+        let best = lines.last().and_then(|line| BestMove::parse(line));
+        match best {
+            Some(best) => Ok(SearchResult { best, infos: infos.collect() }),
+            None => Err(ClientError::Timeout(cmd.to_string())),
+        }
+    }
+
+    fn crashed(&mut self) -> ClientError {
+        // The output closes slightly before the process is gone
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(500) {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return ClientError::Crashed(Some(status));
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        ClientError::Crashed(None)
+    }
+}
+
+impl Drop for UciClient {
+    fn drop(&mut self) {
+        if self.is_alive() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_info() {
+        let info = InfoLine::parse(
+            "info depth 7 seldepth 12 multipv 1 score cp -35 lowerbound nodes 1234 nps 5000 time 250 pv e2e4 e7e5 g1f3",
+        );
+        assert_eq!(info.depth, Some(7));
+        assert_eq!(info.seldepth, Some(12));
+        assert_eq!(info.score, Some(Score::Cp(-35)));
+        assert!(info.lowerbound && !info.upperbound);
+        assert_eq!(info.nodes, Some(1234));
+        assert_eq!(info.time, Some(250));
+        assert_eq!(info.pv, vec!["e2e4", "e7e5", "g1f3"]);
+
+        let info = InfoLine::parse("info score mate -3 string pv san Qh5 is mate");
+        assert_eq!(info.score, Some(Score::Mate(-3)));
+        assert_eq!(info.string.as_deref(), Some("pv san Qh5 is mate"));
+        assert!(info.pv.is_empty());
+
+        assert_eq!(
+            BestMove::parse("bestmove e7e8q ponder d2d1"),
+            Some(BestMove { mv: "e7e8q".to_string(), ponder: Some("d2d1".to_string()) })
+        );
+        assert_eq!(BestMove::parse("info depth 1"), None);
+    }
+
+    #[test]
+    fn test_go_command() {
+        let params =
+            GoParams { wtime: Some(1000), winc: Some(10), depth: Some(5), ..Default::default() };
+        assert_eq!(params.to_command(), "go wtime 1000 winc 10 depth 5");
+        assert_eq!(GoParams { infinite: true, ..Default::default() }.to_command(), "go infinite");
+    }
+}
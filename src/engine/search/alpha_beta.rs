@@ -1,12 +1,18 @@
 use super::iter_deepening::Search;
-use crate::engine::board::moves::Move;
+use crate::engine::board::color::{BLACK, WHITE};
+use crate::engine::board::moves::{Flag, Move};
 use crate::engine::board::piece::PieceTrait;
 use crate::engine::evaluation::evaluation::EvaluationTrait;
+use crate::engine::misc::bitboard::BitboardTrait;
 use crate::engine::move_generator::make_move::BoardMoveTrait;
 use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
 use crate::engine::move_generator::mv_oredering::MoveOrderingTrait;
+use crate::engine::move_generator::mv_picker::MovePicker;
 use crate::engine::protocols::time::time_over;
-use crate::engine::search::transposition_table::{Bound, TT};
+use crate::engine::search::extensions::Singular;
+use crate::engine::search::transposition_table::Bound;
+use crate::engine::tablebase::dtm::{DTM, DTM_MAX_PIECES, Dtm};
+use crate::engine::tablebase::syzygy::{ProbeState, TB, TB_WIN, TB_WIN_IN_MAX_PLY, Wdl};
 
 impl Search {
     #[inline(always)]
@@ -18,10 +24,9 @@ impl Search {
     }
 
     #[inline(always)]
-    pub fn add_history(&mut self, mv: Move, depth: i8) {
-        if !mv.flag.is_capture() {
-            self.board.s_history[mv.piece.idx()][mv.to as usize] += depth as isize * depth as isize;
-        }
+    pub fn add_history(&mut self, mv: Move, tried: &[Move], depth: i8, cutoff: bool) {
+        let malus = cutoff && depth >= self.options.history_malus_depth;
+        self.board.s_history.update(&self.board.moves, &mv, tried, depth, malus);
     }
 
     #[inline(always)]
@@ -62,7 +67,139 @@ impl Search {
         self.board.sq_attack(self.board.king_sq(self.board.color()), self.board.color()) != 0
     }
 
-    pub const FUTILITY_MARGINS: [isize; 5] = [0, 200, 800, 1250, 1600];
+    ///
+    /// Probes the native distance to mate tables, which give the exact mate score. The tables
+    /// ignore the fifty-move rule, so a mate that comes too late is left to the WDL tables.
+    ///
+    fn probe_dtm(&mut self) -> Option<isize> {
+        let ply = self.board.ply() as isize;
+        if ply == 0 || self.board.castling() != 0 || self.board.ep().is_some() {
+            return None;
+        }
+
+        let pieces = (self.board.occ_bb(WHITE) | self.board.occ_bb(BLACK)).count();
+        if pieces > DTM_MAX_PIECES {
+            return None;
+        }
+
+        let dtm = DTM.read().unwrap();
+        if dtm.is_empty() {
+            return None;
+        }
+
+        let score = Self::dtm_score(dtm.probe(&self.board)?, ply, self.board.half_move())?;
+        self.info.tb_hits += 1;
+        Some(score)
+    }
+
+    ///
+    /// Mate score of the table value, None if the mate comes after the fifty-move rule
+    ///
+    #[inline(always)]
+    fn dtm_score(dtm: Dtm, ply: isize, half_move: u8) -> Option<isize> {
+        let in_time = |plies: u8| (plies as usize + half_move as usize) < 100;
+        match dtm {
+            Dtm::Win(plies) if in_time(plies) => Some(Self::MATE - ply - plies as isize),
+            Dtm::Loss(plies) if in_time(plies) => Some(-Self::MATE + ply + plies as isize),
+            Dtm::Draw => Some(0),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Probes the WDL tablebases after a zeroing move (captures and pawn moves).
+    /// Returns the score if the bound of the tablebase result allows a cutoff.
+    ///
+    fn probe_tb(&mut self, alpha: isize, beta: isize) -> Option<isize> {
+        let ply = self.board.ply();
+        if ply == 0 || self.board.half_move() != 0 || self.board.castling() != 0 {
+            return None;
+        }
+
+        let pieces = (self.board.occ_bb(WHITE) | self.board.occ_bb(BLACK)).count();
+        if pieces > self.options.syzygy_probe_limit {
+            return None;
+        }
+
+        let tb = TB.read().unwrap();
+        if pieces > tb.max_pieces {
+            return None;
+        }
+
+        let mut result = ProbeState::Ok;
+        let wdl = tb.probe_wdl(&mut self.board, &mut result);
+        if result == ProbeState::Fail {
+            return None;
+        }
+        self.info.tb_hits += 1;
+
+        let (score, bound) = match wdl {
+            Wdl::Loss => (-TB_WIN + ply as isize, Bound::Upper),
+            Wdl::Win => (TB_WIN - ply as isize, Bound::Lower),
+            _ => (2 * wdl as isize, Bound::Exact),
+        };
+
+        match bound {
+            Bound::Exact => Some(score),
+            Bound::Lower if score >= beta => Some(score),
+            Bound::Upper if score <= alpha => Some(score),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Searches the captures that win enough material at a reduced depth, against a beta raised
+    /// by the ProbCut margin. A capture that still beats it makes a cutoff of the node likely.
+    ///
+    fn probcut(&mut self, beta: isize, depth: i8, eval: isize) -> Option<isize> {
+        let ply = self.board.ply();
+        let probcut_beta = beta + Self::PROBCUT_MARGIN;
+        let probcut_depth = depth - Self::PROBCUT_REDUCTION;
+
+        let mut moves = self.board.gen_cap_promo();
+        self.board.score_moves(&mut moves);
+        while let Some(mv) = self.board.next_move(&mut moves) {
+            let is_capture = matches!(mv.flag, Flag::Capture(_) | Flag::Promotion(_, Some(_)));
+            if !is_capture || eval + self.board.see(mv.from as usize, mv.to as usize) < probcut_beta
+            {
+                continue;
+            }
+            if !self.board.make_move(&mv) {
+                continue;
+            }
+            self.extensions[ply + 1] = self.extensions[ply];
+
+            // The quiescence search drops the captures that don't hold first
+            let mut score = -self.quiescence_search(-probcut_beta, -probcut_beta + 1, 0);
+            if score >= probcut_beta {
+                score = -self.alpha_beta(-probcut_beta, -probcut_beta + 1, probcut_depth, false);
+            }
+            self.board.undo_move();
+
+            if score >= probcut_beta {
+                self.board.tt.write().unwrap().set(
+                    self.board.state.key,
+                    mv,
+                    score,
+                    probcut_depth + 1,
+                    Bound::Lower,
+                );
+                return Some(score);
+            }
+        }
+        None
+    }
+
+    pub const MATE: isize = 1000000;
+    // Pruning margins by depth, the pruning is only done at the depths of the table
+    pub const FUTILITY_MARGINS: [isize; 5] = [0, 250, 500, 750, 1000];
+    pub const RFP_MARGINS: [isize; 7] = [0, 150, 300, 450, 600, 750, 900];
+    pub const RAZOR_MARGINS: [isize; 4] = [0, 500, 800, 1100];
+    pub const PROBCUT_MARGIN: isize = 200;
+    pub const PROBCUT_DEPTH: i8 = 5;
+    pub const PROBCUT_REDUCTION: i8 = 4;
+    // History score that changes the late move reduction by a ply
+    pub const LMR_HISTORY_DIVISOR: isize = 6144;
 
     pub fn alpha_beta(
         &mut self,
@@ -88,38 +225,78 @@ impl Search {
             return self.board.inc_eval();
         }
 
+        let ply = self.board.ply();
         let in_check: bool = self.in_check();
 
         // NOTE: Check extension
-        if in_check {
+        if in_check && self.can_extend(depth) {
             depth += 1;
+            self.extensions[ply] += 1;
         }
 
         let is_pvs = alpha != beta - 1;
+        // Singular search, the TT move of the node is left out
+        let excluded = self.excluded[ply];
 
         if !is_pvs
             && !is_nmp
+            && excluded.is_none()
             && let Some((score, _)) =
-                // self.board.tt.probe(self.board.state.key, depth, alpha as i16, beta as i16)
-                TT.read().unwrap().probe(
-                    self.board.state.key,
-                    depth,
-                    alpha as i16,
-                    beta as i16,
-                )
+                // self.board.tt.probe(self.board.state.key, depth, alpha, beta)
+                self.board.tt.read().unwrap().probe(self.board.state.key, depth, alpha, beta)
         {
-            return score as isize;
+            return score;
+        }
+
+        // NOTE: Tablebase probe
+        if let Some(score) = self.probe_dtm() {
+            return score;
+        }
+
+        if let Some(score) = self.probe_tb(alpha, beta) {
+            return score;
         }
 
         self.info.nodes += 1;
 
+        // Static evaluation for the pruning, only in non-PV nodes and when not in check
+        let static_eval = match is_pvs || in_check {
+            true => None,
+            false => Some(self.board.inc_eval()),
+        };
+        let depth_idx = depth as usize;
+        let is_mate_window = beta.abs() >= TB_WIN_IN_MAX_PLY;
+
+        if let Some(eval) = static_eval
+            && excluded.is_none()
+        {
+            // NOTE: Reverse futility pruning, the static eval is so far above beta that a move
+            // is very unlikely to bring it back
+            if depth_idx < Self::RFP_MARGINS.len()
+                && !is_mate_window
+                && eval - Self::RFP_MARGINS[depth_idx] >= beta
+            {
+                return eval;
+            }
+
+            // NOTE: Razoring, only the captures can bring back a static eval far below alpha
+            if depth_idx < Self::RAZOR_MARGINS.len()
+                && eval + Self::RAZOR_MARGINS[depth_idx] < alpha
+            {
+                let score = self.quiescence_search(alpha, beta, 0);
+                if score < alpha {
+                    return score;
+                }
+            }
+        }
+
         // Futility Pruning
-        let do_futility_pruning = if depth > 4 || is_pvs || in_check {
-            // Only apply at shallow depths, in non-PV nodes, and when not in check.
-            false
-        } else {
-            // Prune if the static eval is significantly worse than alpha.
-            self.board.inc_eval() + 250 * depth as isize <= alpha
+        // Prune if the static eval is significantly worse than alpha.
+        let do_futility_pruning = match static_eval {
+            Some(eval) if depth_idx < Self::FUTILITY_MARGINS.len() => {
+                eval + Self::FUTILITY_MARGINS[depth_idx] <= alpha
+            }
+            _ => false,
         };
 
         // NOTE: Null move Pruning
@@ -127,7 +304,7 @@ impl Search {
         let is_pawn_ending = self.board.occ_bb(color)
             & !(self.board.pawn_bb(color) | self.board.king_bb(color))
             == 0;
-        let nmp_allowed = !in_check && !is_nmp && !is_pawn_ending && !is_pvs;
+        let nmp_allowed = !in_check && !is_nmp && !is_pawn_ending && !is_pvs && excluded.is_none();
 
         // Remove Pruning FIXME:
         let test_pruning = false;
@@ -137,34 +314,76 @@ impl Search {
             let mv = Move::null_move();
 
             self.board.make_move(&mv);
+            self.extensions[ply + 1] = self.extensions[ply];
             let score = -self.alpha_beta(-beta, -beta + 1, depth - 1 - r, true);
             self.board.undo_move();
+            // A mate found after passing isn't a proven mate
             if score >= beta {
-                return beta;
+                return if score >= TB_WIN_IN_MAX_PLY { beta } else { score };
             }
         }
 
+        // NOTE: ProbCut
+        if let Some(eval) = static_eval
+            && excluded.is_none()
+            && depth >= Self::PROBCUT_DEPTH
+            && !is_mate_window
+            && let Some(score) = self.probcut(beta, depth, eval)
+        {
+            return score;
+        }
+
         let mut best_mv = None;
-        let mut best_score = alpha;
+        let mut best_score = -Self::MATE;
         let mut legal_mv_num = 0;
         let old_alpha: isize = alpha;
+        // Moves searched until now, they get a history malus when a later one raises alpha
+        let mut tried: Vec<Move> = Vec::new();
 
-        let mut moves = self.board.gen_moves();
-        self.board.score_moves(&mut moves);
+        let mut picker = MovePicker::init(&self.board);
+        let singular_entry = self.singular_entry(depth);
 
-        let ply = self.board.ply();
         self.board.pv_len[ply] = 0;
 
-        while let Some(mv) = self.board.next_move(&mut moves) {
+        while let Some(mv) = picker.next(&mut self.board) {
             // Check Time every 8192 Nodes
             if (self.info.nodes & 8192) == 0 && time_over(&self) {
                 return 0;
             }
 
+            // Only search the root moves that keep the tablebase result
+            if ply == 0 && !self.root_moves.is_empty() && !self.root_moves.contains(&mv) {
+                continue;
+            }
+            if excluded == Some(mv) {
+                continue;
+            }
+
+            // NOTE: Extensions
+            let mut extension = 0;
+            if let Some(entry) = singular_entry
+                && entry.mv == mv
+            {
+                match self.singular_extension(&entry, depth, beta) {
+                    Singular::Extend => extension = 1,
+                    Singular::MultiCut(score) => return score,
+                    Singular::None => (),
+                }
+            }
+            if extension == 0 && self.can_extend(depth) {
+                extension = self.move_extension(&mv);
+            }
+            let new_depth = depth - 1 + extension;
+            let history = match mv.flag.is_capture() {
+                true => 0,
+                false => self.board.s_history.quiet_score(&self.board.moves, &mv),
+            };
+
             if !self.board.make_move(&mv) {
                 continue;
             }
             legal_mv_num += 1;
+            self.extensions[ply + 1] = self.extensions[ply] + extension;
 
             // Don't prune captures, promotions, or checks.
             // Also, don't prune the first move, as it's likely the best.
@@ -180,7 +399,7 @@ impl Search {
 
             let mut score: isize;
             if legal_mv_num == 1 {
-                score = -self.alpha_beta(-beta, -alpha, depth - 1, false);
+                score = -self.alpha_beta(-beta, -alpha, new_depth, false);
             } else {
                 // Late Move Reductions, Add if enemy king is in check
                 if legal_mv_num >= 5
@@ -195,9 +414,11 @@ impl Search {
                     let mut r: i8 = (0.7844
                         + ((depth as f32).ln() * (legal_mv_num as f32).ln() / 2.4696))
                         as i8;
+                    // Moves with a good history are reduced less, the bad ones more
+                    r -= (history / Self::LMR_HISTORY_DIVISOR) as i8;
                     r = r.max(1).min(depth - 2);
                     // let reduction = 1;
-                    score = -self.alpha_beta(-alpha - 1, -alpha, depth - 1 - r, false);
+                    score = -self.alpha_beta(-alpha - 1, -alpha, new_depth - r, false);
                 } else {
                     score = alpha + 1; // To enter the PVS search
                 }
@@ -205,16 +426,21 @@ impl Search {
 
                 // FIXME: TEST: Shouldn't the above true for pvs be in this line here ????
                 if score > alpha {
-                    score = -self.alpha_beta(-alpha - 1, -alpha, depth - 1, false);
+                    score = -self.alpha_beta(-alpha - 1, -alpha, new_depth, false);
 
                     if alpha < score && score < beta {
-                        score = -self.alpha_beta(-beta, -alpha, depth - 1, false);
+                        score = -self.alpha_beta(-beta, -alpha, new_depth, false);
                     }
                 }
             }
 
             self.board.undo_move();
+            tried.push(mv);
 
+            if score > best_score {
+                best_score = score;
+                best_mv = Some(mv);
+            }
             if score > alpha {
                 // NOTE: Adding Alpha Raise info. (Comment Out before release)
                 // NOTE: Used for checking how good the move ordering is.
@@ -222,6 +448,7 @@ impl Search {
 
                 if score >= beta {
                     self.add_killer(mv);
+                    self.add_history(mv, &tried, depth, true);
 
                     // NOTE: Adding Beta Cut info. (Comment Out before release)
                     // NOTE: Used for checking how good the move ordering is.
@@ -229,50 +456,92 @@ impl Search {
                     self.add_fail_hard_first_info(legal_mv_num);
                     self.add_fail_hard_info();
 
-                    if !is_pvs && !is_nmp {
+                    if !is_pvs && !is_nmp && excluded.is_none() {
                         // self.board.tt.set(
-                        TT.write().unwrap().set(
+                        self.board.tt.write().unwrap().set(
                             self.board.state.key,
                             mv,
-                            score as i16,
+                            score,
                             depth,
                             Bound::Lower,
                         );
                     }
-                    return beta;
+                    return score;
                 }
 
                 alpha = score;
-                best_score = score;
-                best_mv = Some(mv);
 
                 self.add_to_pv(mv, ply);
-                self.add_history(mv, depth);
             }
         }
 
         // NOTE: Checking if the position is draw or checkmate
         if legal_mv_num == 0 {
+            // Only the TT move is legal, it is singular
+            if excluded.is_some() {
+                return alpha;
+            }
             return match in_check {
-                true => -1000000 + (self.board.ply() as isize),
+                true => -Self::MATE + (self.board.ply() as isize),
                 false => 0,
             };
         }
 
+        if best_score > old_alpha
+            && let Some(mv) = best_mv
+        {
+            self.add_history(mv, &tried, depth, false);
+        }
+
         // // NOTE: Storing the best value in the transposition table
-        if !is_pvs && !is_nmp {
+        if !is_pvs && !is_nmp && excluded.is_none() {
             if let Some(mv) = best_mv {
                 let bound = if best_score > old_alpha { Bound::Exact } else { Bound::Upper };
-                // self.board.tt.set(self.board.state.key, mv, alpha as i16, depth, bound);
-                TT.write().unwrap().set(self.board.state.key, mv, alpha as i16, depth, bound);
+                // self.board.tt.set(self.board.state.key, mv, best_score, depth, bound);
+                self.board.tt.write().unwrap().set(
+                    self.board.state.key,
+                    mv,
+                    best_score,
+                    depth,
+                    bound,
+                );
             }
         }
 
-        alpha
+        best_score
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::board::board::Board;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::misc::const_utility::FEN_START;
+    use crate::engine::protocols::uci::UCITime;
+
+    #[test]
+    fn test_probcut() {
+        let probcut = |fen: &str| {
+            let mut search = Search::init(Board::read_fen(fen), UCITime::init());
+            let eval = search.board.inc_eval();
+            search.probcut(0, Search::PROBCUT_DEPTH, eval)
+        };
+
+        // Taking the hanging queen beats the raised beta
+        let score = probcut("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1");
+        assert!(score.is_some_and(|score| score >= Search::PROBCUT_MARGIN), "{:?}", score);
+        assert_eq!(probcut(FEN_START), None);
+    }
+
+    #[test]
+    fn test_dtm_score() {
+        assert_eq!(Search::dtm_score(Dtm::Win(19), 2, 0), Some(Search::MATE - 21));
+        assert_eq!(Search::dtm_score(Dtm::Loss(18), 2, 81), Some(-Search::MATE + 20));
+        assert_eq!(Search::dtm_score(Dtm::Draw, 2, 99), Some(0));
+
+        // Mated on the 100th half move, the fifty-move rule comes first
+        assert_eq!(Search::dtm_score(Dtm::Win(19), 2, 81), None);
+        assert_eq!(Search::dtm_score(Dtm::Loss(30), 2, 70), None);
+    }
 }
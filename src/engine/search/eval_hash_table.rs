@@ -0,0 +1,138 @@
+use once_cell::sync::Lazy;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MAX_TT_ENTRIES: usize = 262139;
+
+// Table used by the engine, boards cloned from each other share the same table
+pub type SharedEvalTT = Arc<EvalHashTable>;
+
+pub static EVAL_TT: Lazy<SharedEvalTT> = Lazy::new(|| Arc::new(EvalHashTable::init()));
+
+// NOTE: Evaluation cache
+//
+// Static evaluation of the side to move, by the zobrist key. Every entry is a single atomic
+// word, the upper 32 bits of the key and the evaluation, so the threads can share the table
+// without a lock: an entry is either read whole or not at all.
+// NOTE: 8 Bytes per entry, currently around 2Mb
+pub struct EvalHashTable {
+    pub table: Box<[AtomicU64]>,
+    pub lookups: AtomicU64,
+    pub inserts: AtomicU64,
+    pub hits: AtomicU64,
+}
+
+// The entries are left out, the table is part of the board
+impl fmt::Debug for EvalHashTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvalHashTable")
+            .field("entries", &self.table.len())
+            .field("inserts", &self.inserts)
+            .field("hits", &self.hits)
+            .finish()
+    }
+}
+
+impl EvalHashTable {
+    ///
+    /// New table that is not shared with the engine's table
+    ///
+    pub fn shared() -> SharedEvalTT {
+        Arc::new(Self::init())
+    }
+
+    pub fn init() -> Self {
+        Self {
+            table: (0..MAX_TT_ENTRIES).map(|_| AtomicU64::new(0)).collect(),
+            lookups: AtomicU64::new(0),
+            inserts: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn idx(key: u64) -> usize {
+        (key % MAX_TT_ENTRIES as u64) as usize
+    }
+
+    #[inline(always)]
+    pub fn set(&self, key: u64, eval: isize) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+        let entry = (key & 0xFFFF_FFFF_0000_0000) | (eval as i32 as u32 as u64);
+        self.table[Self::idx(key)].store(entry, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn get(&self, key: u64) -> Option<isize> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+
+        let entry = self.table[Self::idx(key)].load(Ordering::Relaxed);
+        if entry != 0 && (entry ^ key) >> 32 == 0 {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(entry as u32 as i32 as isize);
+        }
+        None
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.lookups.load(Ordering::Relaxed);
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits.load(Ordering::Relaxed) as f64 * 100.0 / lookups as f64
+    }
+
+    pub fn print_stats(&self) {
+        println!(
+            "EVAL_TT -> lookups: {}; inserts: {}; hits: {}; hit rate: {:.1}%;",
+            self.lookups.load(Ordering::Relaxed),
+            self.inserts.load(Ordering::Relaxed),
+            self.hits.load(Ordering::Relaxed),
+            self.hit_rate()
+        );
+    }
+
+    pub fn clear(&self) {
+        for entry in self.table.iter() {
+            entry.store(0, Ordering::Relaxed);
+        }
+        self.clear_stats();
+    }
+
+    pub fn clear_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.inserts.store(0, Ordering::Relaxed);
+        self.lookups.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_hash_table() {
+        let table = EvalHashTable::init();
+        let key = 0x1234_5678_9ABC_DEF0;
+        assert_eq!(table.get(key), None);
+
+        table.set(key, -1234);
+        assert_eq!(table.get(key), Some(-1234));
+        table.set(key, 56);
+        assert_eq!(table.get(key), Some(56));
+
+        // Same slot, different key
+        let other = key + MAX_TT_ENTRIES as u64 * (1 << 32);
+        assert_eq!(EvalHashTable::idx(other), EvalHashTable::idx(key));
+        assert_eq!(table.get(other), None);
+        assert_eq!(
+            (table.lookups.load(Ordering::Relaxed), table.hits.load(Ordering::Relaxed)),
+            (4, 2)
+        );
+        assert_eq!(table.hit_rate(), 50.0);
+
+        table.clear();
+        assert_eq!(table.get(key), None);
+    }
+}
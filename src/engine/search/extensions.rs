@@ -0,0 +1,171 @@
+use super::iter_deepening::Search;
+use crate::engine::board::board::MAX_PLY;
+use crate::engine::board::color::ColorTrait;
+use crate::engine::board::moves::{Flag, Move};
+use crate::engine::board::piece::PieceTrait;
+use crate::engine::board::square::get_rank;
+use crate::engine::generated::pawn::{FORWARD_SPANS_LR, PAWN_FORWARD_SPANS};
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+use crate::engine::search::transposition_table::{Bound, TTEntry};
+use crate::engine::tablebase::syzygy::TB_WIN_IN_MAX_PLY;
+
+// Singular extensions: minimal depth of the node, and how much shallower the TT entry can be
+const SINGULAR_DEPTH: i8 = 6;
+const SINGULAR_TT_DEPTH: i8 = 3;
+
+// NOTE: Extensions
+//
+// A move is searched one ply deeper if it is the only good move of the node (singular), takes
+// back the piece just captured, or pushes a safe passed pawn to the rank of the PassedPawnRank
+// option or further. A position in check is extended on entry.
+//
+// Every path from the root gets at most as many extensions as the depth of the iteration, and
+// an extended search never goes past MAX_PLY.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Singular {
+    // Every other move failed low, the TT move is extended
+    Extend,
+    // Another move beats beta too, the node can be cut with the score
+    MultiCut(isize),
+    None,
+}
+
+impl Search {
+    ///
+    /// The path to the node can be extended once more
+    ///
+    #[inline(always)]
+    pub fn can_extend(&self, depth: i8) -> bool {
+        let ply = self.board.ply();
+        self.extensions[ply] < self.info.curr_depth && ply + depth as usize + 1 < MAX_PLY
+    }
+
+    ///
+    /// TT entry of the node if its move can be tested for singularity: a lower bound (or
+    /// exact score) that isn't much shallower than the node, and not a mate score
+    ///
+    pub fn singular_entry(&self, depth: i8) -> Option<TTEntry> {
+        let ply = self.board.ply();
+        if ply == 0 || depth < SINGULAR_DEPTH || self.excluded[ply].is_some() {
+            return None;
+        }
+        if !self.can_extend(depth) {
+            return None;
+        }
+
+        let entry = self.board.tt.read().unwrap().get(self.board.state.key)?;
+        let usable = entry.category != Bound::Upper
+            && entry.depth >= depth - SINGULAR_TT_DEPTH
+            && (entry.score as isize).abs() < TB_WIN_IN_MAX_PLY;
+        usable.then_some(entry)
+    }
+
+    ///
+    /// Searches the node without the TT move, at half the depth and against a margin below the
+    /// TT score. The TT move is singular if all the other moves fail low.
+    ///
+    pub fn singular_extension(&mut self, entry: &TTEntry, depth: i8, beta: isize) -> Singular {
+        let ply = self.board.ply();
+        let singular_beta = entry.score as isize - self.options.singular_margin * depth as isize;
+
+        // The search runs on the same ply, the PV and extensions of the node are put back
+        let (pv_len, extensions) = (self.board.pv_len[ply], self.extensions[ply]);
+        self.excluded[ply] = Some(entry.mv);
+        let score = self.alpha_beta(singular_beta - 1, singular_beta, (depth - 1) / 2, false);
+        self.excluded[ply] = None;
+        self.board.pv_len[ply] = pv_len;
+        self.extensions[ply] = extensions;
+
+        if score < singular_beta {
+            Singular::Extend
+        } else if singular_beta >= beta + self.options.multi_cut_margin {
+            Singular::MultiCut(singular_beta)
+        } else {
+            Singular::None
+        }
+    }
+
+    ///
+    /// Recapture and passed pawn push extensions of the move, before it is made
+    ///
+    pub fn move_extension(&self, mv: &Move) -> i8 {
+        let clr = self.board.color();
+
+        // Takes back on the square of the last capture, about the same material
+        if let (Flag::Capture(captured), Some(last)) = (mv.flag, self.board.moves.last())
+            && let Flag::Capture(last_captured) = last.flag
+            && last.to == mv.to
+            && (captured.weight() - last_captured.weight()).abs() <= self.options.recapture_margin
+        {
+            return 1;
+        }
+
+        // Pushes a passed pawn that the enemy can't just take
+        let to = mv.to as usize;
+        let rank = if clr.is_white() { get_rank(to) } else { 7 - get_rank(to) };
+        if mv.piece.is_pawn() && !mv.flag.is_capture() && rank + 1 >= self.options.passed_pawn_rank
+        {
+            let span = PAWN_FORWARD_SPANS[clr.idx()][to] | FORWARD_SPANS_LR[clr.idx()][to];
+            let is_passed = self.board.pawn_bb(clr.opp()) & span == 0;
+            let is_safe =
+                self.board.sq_attack(to, clr) == 0 || self.board.sq_attack(to, clr.opp()) != 0;
+            if is_passed && is_safe {
+                return 1;
+            }
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::board::Board;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::move_generator::make_move::BoardMoveTrait;
+    use crate::engine::protocols::uci::UCITime;
+
+    fn find_move(board: &mut Board, from: u8, to: u8) -> Move {
+        let moves = board.gen_moves();
+        moves.iter().map(|(mv, _)| *mv).find(|mv| mv.from == from && mv.to == to).unwrap()
+    }
+
+    fn recapture_extension(fen: &str, capture: (u8, u8), recapture: (u8, u8)) -> i8 {
+        let mut search = Search::init(Board::read_fen(fen), UCITime::init());
+        let mv = find_move(&mut search.board, capture.0, capture.1);
+        search.board.make_move(&mv);
+        let mv = find_move(&mut search.board, recapture.0, recapture.1);
+        search.move_extension(&mv)
+    }
+
+    #[test]
+    fn test_recapture_extension() {
+        // Nxd5 exd5 takes back a knight for a knight, Bxd5 exd5 a bishop for a knight
+        let fen = "4k3/8/4p3/3n4/8/1BN5/8/4K3 w - - 0 1";
+        assert_eq!(recapture_extension(fen, (18, 35), (44, 35)), 1);
+        assert_eq!(recapture_extension(fen, (17, 35), (44, 35)), 0);
+
+        let fen = "4k3/8/4p3/3p4/8/2N5/8/4K3 w - - 0 1";
+        assert_eq!(recapture_extension(fen, (18, 35), (44, 35)), 0);
+    }
+
+    #[test]
+    fn test_passed_pawn_extension() {
+        let board = Board::read_fen("4kr2/p7/5P1P/1P6/8/8/8/4K3 w - - 0 1");
+        let mut search = Search::init(board, UCITime::init());
+        let [b6, f7, h7] =
+            [(33, 41), (45, 53), (47, 55)].map(|(from, to)| find_move(&mut search.board, from, to));
+
+        // f7 is taken by the rook
+        assert_eq!(search.move_extension(&b6), 0);
+        assert_eq!(search.move_extension(&f7), 0);
+        assert_eq!(search.move_extension(&h7), 1);
+
+        // The b-pawn isn't passed
+        search.options.passed_pawn_rank = 6;
+        assert_eq!(search.move_extension(&b6), 0);
+        search.options.passed_pawn_rank = 8;
+        assert_eq!(search.move_extension(&h7), 0);
+    }
+}
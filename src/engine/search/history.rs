@@ -0,0 +1,220 @@
+use crate::engine::board::moves::{Flag, Move};
+use crate::engine::board::piece::{Piece, PieceTrait};
+use std::fmt;
+
+// Largest absolute value of a history entry, the gravity keeps them inside
+pub const MAX_HISTORY: isize = 8192;
+const MAX_BONUS: isize = 1536;
+const CONT_SIZE: usize = 14 * 64 * 14 * 64;
+
+// NOTE: History heuristics
+//
+// Quiet moves are scored by the piece and to square (main history), and by the same pair
+// together with the piece and to square of the move one and two plies before (continuation
+// histories, one shared table). Captures are scored by the piece, to square and captured piece.
+// The counter move is the quiet reply that last refuted the previous move.
+//
+// The move that raises alpha gets a bonus. If it also causes a beta cutoff, the moves searched
+// before it get a malus of the same size: the captures always, the quiet moves only if the
+// cutoff is quiet (a capture that refutes the move says nothing about the quiets). The search
+// leaves out the malus below the HistoryMalusDepth option, by default at every depth, since it
+// gained nothing in self-play. The gravity makes the change smaller the closer the entry is to the bound, so the entries never leave
+// -MAX_HISTORY..=MAX_HISTORY. Between searches the entries are halved instead of cleared.
+#[derive(Clone)]
+pub struct History {
+    pub quiet: [[i16; 64]; 14],
+    pub capture: [[[i16; 14]; 64]; 14],
+    pub counter: [[Option<Move>; 64]; 14],
+    // Indexed by cont_idx, on the heap as the table is around 1.6Mb
+    pub continuation: Box<[i16]>,
+}
+
+// The tables are left out, they are part of the board
+impl fmt::Debug for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History").finish_non_exhaustive()
+    }
+}
+
+///
+/// Bonus (or malus) of a move that raised alpha at the depth
+///
+#[inline(always)]
+pub fn history_bonus(depth: i8) -> isize {
+    (16 * depth as isize * depth as isize).min(MAX_BONUS)
+}
+
+///
+/// Moves the entry by the bonus, less of it the closer the entry is to the bound
+///
+#[inline(always)]
+fn gravity(entry: &mut i16, bonus: isize) {
+    let value = *entry as isize;
+    *entry = (value + bonus - value * bonus.abs() / MAX_HISTORY) as i16;
+}
+
+///
+/// Piece captured by the move, if any
+///
+#[inline(always)]
+fn captured(mv: &Move) -> Option<Piece> {
+    match mv.flag {
+        Flag::Capture(piece) | Flag::Promotion(_, Some(piece)) => Some(piece),
+        Flag::EP => Some(mv.piece ^ 1),
+        _ => None,
+    }
+}
+
+#[inline(always)]
+fn cont_idx(prev: &Move, mv: &Move) -> usize {
+    ((prev.piece.idx() * 64 + prev.to as usize) * 14 + mv.piece.idx()) * 64 + mv.to as usize
+}
+
+///
+/// The moves one and two plies before, null moves have no follow-up
+///
+#[inline(always)]
+fn follow_ups(moves: &[Move]) -> impl Iterator<Item = &Move> {
+    moves.iter().rev().take(2).filter(|prev| prev.flag != Flag::NullMove)
+}
+
+impl History {
+    pub fn init() -> Self {
+        Self {
+            quiet: [[0; 64]; 14],
+            capture: [[[0; 14]; 64]; 14],
+            counter: [[None; 64]; 14],
+            continuation: vec![0; CONT_SIZE].into_boxed_slice(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.quiet.iter_mut().for_each(|arr| arr.fill(0));
+        self.capture.iter_mut().flatten().for_each(|arr| arr.fill(0));
+        self.counter.iter_mut().for_each(|arr| arr.fill(None));
+        self.continuation.fill(0);
+    }
+
+    ///
+    /// Halves the histories, what was learned in the last search still counts but less
+    ///
+    pub fn age(&mut self) {
+        let halve = |entry: &mut i16| *entry /= 2;
+        self.quiet.iter_mut().flatten().for_each(halve);
+        self.capture.iter_mut().flatten().flatten().for_each(halve);
+        self.continuation.iter_mut().for_each(halve);
+    }
+
+    ///
+    /// Main and continuation history of a quiet move, the moves until now come before it
+    ///
+    #[inline(always)]
+    pub fn quiet_score(&self, moves: &[Move], mv: &Move) -> isize {
+        let main = self.quiet[mv.piece.idx()][mv.to as usize] as isize;
+        follow_ups(moves)
+            .fold(main, |sum, prev| sum + self.continuation[cont_idx(prev, mv)] as isize)
+    }
+
+    #[inline(always)]
+    pub fn capture_score(&self, mv: &Move) -> isize {
+        match captured(mv) {
+            Some(piece) => self.capture[mv.piece.idx()][mv.to as usize][piece.idx()] as isize,
+            None => 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn counter_move(&self, moves: &[Move]) -> Option<Move> {
+        let prev = moves.last().filter(|prev| prev.flag != Flag::NullMove)?;
+        self.counter[prev.piece.idx()][prev.to as usize]
+    }
+
+    ///
+    /// Updates the histories after the best move of a node raised alpha. The other moves are
+    /// the ones searched before it, they get the malus only with malus set.
+    ///
+    pub fn update(&mut self, moves: &[Move], best: &Move, tried: &[Move], depth: i8, malus: bool) {
+        let bonus = history_bonus(depth);
+
+        if best.flag.is_capture() {
+            self.update_capture(best, bonus);
+        } else {
+            self.update_quiet(moves, best, bonus);
+            if let Some(prev) = moves.last().filter(|prev| prev.flag != Flag::NullMove) {
+                self.counter[prev.piece.idx()][prev.to as usize] = Some(*best);
+            }
+        }
+
+        if !malus {
+            return;
+        }
+        for mv in tried.iter().take_while(|mv| *mv != best) {
+            if mv.flag.is_capture() {
+                self.update_capture(mv, -bonus);
+            } else if !best.flag.is_capture() {
+                self.update_quiet(moves, mv, -bonus);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn update_quiet(&mut self, moves: &[Move], mv: &Move, bonus: isize) {
+        gravity(&mut self.quiet[mv.piece.idx()][mv.to as usize], bonus);
+        for prev in follow_ups(moves) {
+            gravity(&mut self.continuation[cont_idx(prev, mv)], bonus);
+        }
+    }
+
+    #[inline(always)]
+    fn update_capture(&mut self, mv: &Move, bonus: isize) {
+        if let Some(piece) = captured(mv) {
+            gravity(&mut self.capture[mv.piece.idx()][mv.to as usize][piece.idx()], bonus);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::piece::{BLACK_PAWN, WHITE_KNIGHT, WHITE_PAWN};
+
+    fn mv(from: u8, to: u8, piece: Piece, flag: Flag) -> Move {
+        Move { from, to, piece, flag }
+    }
+
+    #[test]
+    fn test_history_update() {
+        let mut history = History::init();
+        let prev = [mv(52, 36, BLACK_PAWN, Flag::Quiet)];
+        let best = mv(6, 21, WHITE_KNIGHT, Flag::Quiet);
+        let quiet = mv(12, 28, WHITE_PAWN, Flag::Quiet);
+        let capture = mv(27, 36, WHITE_PAWN, Flag::Capture(BLACK_PAWN));
+
+        history.update(&prev, &best, &[quiet, capture, best], 4, true);
+        let bonus = history_bonus(4);
+        assert_eq!(history.quiet_score(&prev, &best), 2 * bonus);
+        assert_eq!(history.quiet_score(&prev, &quiet), -2 * bonus);
+        assert_eq!(history.quiet_score(&[], &best), bonus);
+        assert_eq!(history.capture_score(&capture), -bonus);
+        assert_eq!(history.counter_move(&prev), Some(best));
+
+        history.age();
+        assert_eq!(history.quiet_score(&[], &best), bonus / 2);
+        assert_eq!(history.counter_move(&prev), Some(best));
+
+        // Without the malus only the best move changes
+        history.update(&prev, &best, &[quiet, best], 4, false);
+        assert_eq!(history.quiet_score(&prev, &quiet), -bonus);
+    }
+
+    #[test]
+    fn test_history_gravity() {
+        let mut history = History::init();
+        let best = mv(6, 21, WHITE_KNIGHT, Flag::Quiet);
+        for _ in 0..1000 {
+            history.update(&[], &best, &[], 60, true);
+        }
+        let score = history.quiet_score(&[], &best);
+        assert!(score > MAX_HISTORY - MAX_BONUS && score <= MAX_HISTORY, "{}", score);
+    }
+}
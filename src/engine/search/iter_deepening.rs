@@ -1,19 +1,31 @@
-use crate::engine::board::board::Board;
+use crate::engine::board::board::{Board, MAX_PLY};
+use crate::engine::board::color::{BLACK, WHITE};
 use crate::engine::board::moves::Move;
+use crate::engine::evaluation::evaluation::EvaluationTrait;
+use crate::engine::evaluation::nnue::NnueTrait;
+use crate::engine::misc::bitboard::BitboardTrait;
 use crate::engine::misc::display::display_moves::get_move_list;
 use crate::engine::misc::display::display_stats::DisplayStatsTrait;
+use crate::engine::protocols::options::UCIOptions;
 use crate::engine::protocols::time::safe_to_start_next_iter;
 use crate::engine::protocols::time::time_over;
 use crate::engine::protocols::uci::UCITime;
-use crate::engine::search::pawn_hash_table::PAWN_TT;
-use crate::engine::search::transposition_table::TT;
+use crate::engine::search::transposition_table::Bound;
+use crate::engine::tablebase::syzygy::{TB, TB_WIN_IN_MAX_PLY};
+use std::sync::Arc;
+use std::time::Duration;
 
 const MAX_INF: isize = isize::MAX / 2;
 const MIN_INF: isize = isize::MIN / 2;
 
+// Aspiration windows: first depth searched with a window, and its initial half width
+const ASPIRATION_DEPTH: i8 = 6;
+const ASPIRATION_WINDOW: isize = 200;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SearchInfo {
     pub nodes: usize,
+    pub tb_hits: usize,
     pub curr_depth: i8,
     pub curr_key: u64,
 
@@ -33,6 +45,7 @@ impl SearchInfo {
             // DEPRECATE: It is not used
             curr_key: 0,
             nodes: 0,
+            tb_hits: 0,
             fail_hard: 0,
             fail_hard_first: 0,
             beta_cut_count: [0; 64],
@@ -43,26 +56,81 @@ impl SearchInfo {
     }
 }
 
+///
+/// Result of a completed iteration of the iterative deepening
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Iteration {
+    pub depth: i8,
+    pub score: isize,
+    pub nodes: usize,
+    pub time: Duration,
+    pub best_mv: Move,
+}
+
 #[derive(Debug)]
 pub struct Search {
     pub board: Board,
     pub uci: UCITime,
     pub info: SearchInfo,
+    pub options: UCIOptions,
+
+    // Root moves allowed by the tablebase probe (empty means all moves)
+    pub root_moves: Vec<Move>,
+    pub tb_score: Option<isize>,
+
+    // Extensions on the path to every ply, and the move left out by a singular search of the ply
+    pub extensions: [i8; MAX_PLY + 1],
+    pub excluded: [Option<Move>; MAX_PLY + 1],
+
+    // Completed iterations of the last search, used by the test suite runners
+    pub iterations: Vec<Iteration>,
+    // Doesn't print the info lines
+    pub silent: bool,
 }
 
 // Common Search Function
 impl Search {
     pub fn init(board: Board, uci: UCITime) -> Self {
-        Self { board, uci, info: SearchInfo::init() }
+        Self {
+            board,
+            uci,
+            info: SearchInfo::init(),
+            options: UCIOptions::init(),
+            root_moves: Vec::new(),
+            tb_score: None,
+            extensions: [0; MAX_PLY + 1],
+            excluded: [None; MAX_PLY + 1],
+            iterations: Vec::new(),
+            silent: false,
+        }
     }
 
     pub fn clear_search(&mut self) {
         self.board.s_killers.iter_mut().for_each(|arr| arr.fill(None));
-        self.board.s_history.iter_mut().for_each(|arr| arr.fill(0));
+        self.board.s_history.age();
+
+        // Evaluation weights of the EvalParamsFile option
+        if !Arc::ptr_eq(&self.board.params, &self.options.eval_params) {
+            self.board.set_params(Arc::clone(&self.options.eval_params));
+        }
+        // Network of the EvalFile and Use NNUE options
+        let network = self.options.active_network();
+        let same_network = match (&network, &self.board.nnue.network) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if !same_network {
+            self.board.set_network(network);
+        }
+        // Accumulators of the moves before the search are never restored
+        self.board.nnue.stack.clear();
 
         self.info.nodes = 0;
+        self.info.tb_hits = 0;
         self.info.curr_key = self.board.state.key;
         self.info.curr_depth = 0;
+        self.iterations.clear();
 
         // self.board.tt.clear_stats();
         self.board.pv_clear();
@@ -71,17 +139,50 @@ impl Search {
     pub fn set_curr_depth(&mut self, depth: i8) {
         self.info.curr_depth = depth;
     }
+
+    ///
+    /// Ranks the root moves with the tablebases and keeps only the best ranked ones,
+    /// so that the search stays on the winning (or drawing) lines.
+    ///
+    pub fn probe_root_tb(&mut self) {
+        self.root_moves.clear();
+        self.tb_score = None;
+
+        let pieces = (self.board.occ_bb(WHITE) | self.board.occ_bb(BLACK)).count();
+        if pieces > self.options.syzygy_probe_limit || self.board.castling() != 0 {
+            return;
+        }
+
+        let tb = TB.read().unwrap();
+        if pieces > tb.max_pieces {
+            return;
+        }
+
+        // Fallback to WDL ranking if the DTZ tables are missing
+        let ranked = tb.root_probe(&mut self.board).or_else(|| tb.root_probe_wdl(&mut self.board));
+        if let Some(ranked) = ranked
+            && let Some(best) = ranked.iter().map(|&(_, rank, _)| rank).max()
+        {
+            self.info.tb_hits += ranked.len();
+            for (mv, rank, score) in ranked {
+                if rank == best {
+                    self.tb_score.get_or_insert(score);
+                    self.root_moves.push(mv);
+                }
+            }
+        }
+    }
 }
 
 // Iterative Deepening
 impl Search {
     pub fn iterative_deepening(&mut self) -> Option<Move> {
         self.clear_search();
+        self.probe_root_tb();
 
         let max_depth = self.uci.max_depth;
-        let alpha = MIN_INF;
-        let beta = MAX_INF;
         let mut best_mv = None;
+        let mut prev_score = 0;
 
         for depth in 1..max_depth + 1 {
             if !safe_to_start_next_iter(&self) {
@@ -89,12 +190,12 @@ impl Search {
             }
 
             self.set_curr_depth(depth);
-            // NOTE: Don't allow Null move if it doesn't made any move
-            let score = self.alpha_beta(alpha, beta, depth, true);
+            let score = self.aspiration_search(depth, prev_score);
 
             if time_over(&self) {
                 break;
             }
+            prev_score = score;
 
             // Get Best Line from current position and print info
             self.board.pv_line = self.board.get_pv();
@@ -102,21 +203,79 @@ impl Search {
                 best_mv = Some(self.board.pv_line[0]);
             }
 
-            self.print_info(score, get_move_list(&self.board.pv_line, self.info.curr_depth));
+            // Report the tablebase score, unless the search found something more precise
+            let score = match self.tb_score {
+                Some(tb_score) if score.abs() < TB_WIN_IN_MAX_PLY => tb_score,
+                _ => score,
+            };
+
+            if let Some(best_mv) = best_mv {
+                self.iterations.push(Iteration {
+                    depth,
+                    score,
+                    nodes: self.info.nodes,
+                    time: self.uci.start_time.elapsed(),
+                    best_mv,
+                });
+            }
+
+            if !self.silent {
+                self.print_info(score, get_move_list(&self.board.pv_line, self.info.curr_depth));
+            }
             // self.print_ordering_info(depth);
 
-            // self.board.pawn_tt.print_stats();
-            // PAWN_TT.read().unwrap().print_stats();
+            if !self.silent && self.options.hash_stats {
+                self.print_hash_info();
+            }
+            self.board.pawn_tt.write().unwrap().clear_stats();
+            self.board.eval_tt.clear_stats();
+            self.board.tt.write().unwrap().clear_stats(); // Update the Current age
+        }
+        best_mv
+    }
+
+    ///
+    /// Searches the root with a window around the score of the previous iteration. The window
+    /// is widened on the side the score falls out of, until the score is inside it.
+    ///
+    fn aspiration_search(&mut self, depth: i8, prev_score: isize) -> isize {
+        // Mate and tablebase scores jump around too much to guess a window
+        // NOTE: Don't allow Null move if it doesn't made any move
+        if depth < ASPIRATION_DEPTH || prev_score.abs() >= TB_WIN_IN_MAX_PLY {
+            return self.alpha_beta(MIN_INF, MAX_INF, depth, true);
+        }
 
-            PAWN_TT.write().unwrap().clear_stats();
+        let mut delta = ASPIRATION_WINDOW;
+        let mut alpha = prev_score - delta;
+        let mut beta = prev_score + delta;
+        loop {
+            let score = self.alpha_beta(alpha, beta, depth, true);
+            if time_over(self) {
+                return score;
+            }
 
-            // self.board.pawn_tt.clear_stats(); // Update the Current age
-            // self.board.tt.clear_stats(); // Update the Current age
-            // TT.read().unwrap().print_stats();
-            TT.write().unwrap().clear_stats(); // Update the Current age
-            // search.tt.lock().unwrap().print_stats();
+            delta += delta / 2;
+            let bound = if score <= alpha {
+                beta = (alpha + beta) / 2;
+                alpha = score - delta;
+                Bound::Upper
+            } else if score >= beta {
+                beta = score + delta;
+                Bound::Lower
+            } else {
+                return score;
+            };
+
+            if alpha <= -TB_WIN_IN_MAX_PLY {
+                alpha = MIN_INF;
+            }
+            if beta >= TB_WIN_IN_MAX_PLY {
+                beta = MAX_INF;
+            }
+            if !self.silent {
+                self.print_bound_info(score, bound);
+            }
         }
-        best_mv
     }
 }
 
@@ -152,9 +311,8 @@ mod tests {
     fn test_iter_deep_exchange() {
         let depth = 7;
         let fen = "8/2P1P3/b1B2p2/1pPRp3/2k3P1/P4pK1/nP3p1p/N7 w - - 0 1";
-        // NOTE: Best Continuation after h2h1n: b5b4 or c3b2
-        let expected_pv = " d5d1 a2c3 b2c3 h2h1q d1h1 f2f1q h1f1"; // Depth 7
-        // let expected_pv = " b2b3 c4c3 d5d1 f2f1q d1f1 h2h1n"; // Depth 6
+        // Rd1 stops both black pawns on the first rank, after that nothing stops e8Q
+        let expected_pv = " d5d1 c4c5 e7e8Q c5b6 a1b3 f2f1q d1f1";
         test_search(fen, depth, expected_pv);
     }
 
@@ -1,4 +1,7 @@
 pub mod alpha_beta;
+pub mod eval_hash_table;
+pub mod extensions;
+pub mod history;
 pub mod iter_deepening;
 pub mod pawn_hash_table;
 pub mod quiescence;
@@ -1,13 +1,17 @@
 use once_cell::sync::Lazy;
+use std::fmt;
 use std::sync::{
-    RwLock,
+    Arc, RwLock,
     atomic::{AtomicI8, AtomicU64, Ordering},
 };
 
 // const MAX_TT_ENTRIES: u64 = 400211;
 const MAX_TT_ENTRIES: u64 = 403139;
 
-pub static PAWN_TT: Lazy<RwLock<PawnHashTable>> = Lazy::new(|| RwLock::new(PawnHashTable::init()));
+// Table used by the engine, boards cloned from each other share the same table
+pub type SharedPawnTT = Arc<RwLock<PawnHashTable>>;
+
+pub static PAWN_TT: Lazy<SharedPawnTT> = Lazy::new(|| Arc::new(RwLock::new(PawnHashTable::init())));
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Bound {
@@ -40,7 +44,6 @@ impl PawnEntry {
     }
 }
 
-#[derive(Debug)]
 pub struct PawnHashTable {
     pub table: Box<[Option<PawnEntry>]>,
     pub lookups: AtomicU64,
@@ -50,7 +53,25 @@ pub struct PawnHashTable {
     pub curr_age: AtomicI8,
 }
 
+// The entries are left out, the table is part of the board
+impl fmt::Debug for PawnHashTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PawnHashTable")
+            .field("entries", &self.table.len())
+            .field("inserts", &self.inserts)
+            .field("hits", &self.hits)
+            .finish()
+    }
+}
+
 impl PawnHashTable {
+    ///
+    /// New table that is not shared with the engine's table
+    ///
+    pub fn shared() -> SharedPawnTT {
+        Arc::new(RwLock::new(Self::init()))
+    }
+
     pub fn init() -> Self {
         Self {
             table: vec![None; MAX_TT_ENTRIES as usize].into_boxed_slice(), //Box::new([None; MAX_TT_ENTRIES]),
@@ -112,6 +133,14 @@ impl PawnHashTable {
         return None;
     }
 
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.lookups.load(Ordering::Relaxed);
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits.load(Ordering::Relaxed) as f64 * 100.0 / lookups as f64
+    }
+
     pub fn print_stats(&self) {
         println!(
             "PAWN_TT -> lookups: {}; inserts: {}; hits: {}; collisions: {};",
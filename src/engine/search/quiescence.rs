@@ -4,7 +4,7 @@ use crate::engine::move_generator::make_move::BoardMoveTrait;
 use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
 use crate::engine::move_generator::mv_oredering::MoveOrderingTrait;
 use crate::engine::protocols::time::time_over;
-use crate::engine::search::transposition_table::{Bound, TT};
+use crate::engine::search::transposition_table::Bound;
 
 impl Search {
     pub fn quiescence_search(&mut self, mut alpha: isize, beta: isize, depth: i8) -> isize {
@@ -17,10 +17,11 @@ impl Search {
             return eval;
         }
 
+        // Stand pat, the side to move doesn't have to capture
+        if eval >= beta {
+            return eval;
+        }
         if eval > alpha {
-            if eval >= beta {
-                return eval;
-            }
             alpha = eval;
         }
 
@@ -37,13 +38,13 @@ impl Search {
         }
 
         if let Some((score, _)) =
-            // self.board.tt.probe(self.board.state.key, depth, alpha as i16, beta as i16)
-            TT.read().unwrap().probe(self.board.state.key, depth, alpha as i16, beta as i16)
+            // self.board.tt.probe(self.board.state.key, depth, alpha, beta)
+            self.board.tt.read().unwrap().probe(self.board.state.key, depth, alpha, beta)
         {
-            return score as isize;
+            return score;
         }
         let mut best_mv = None;
-        let mut best_score = alpha;
+        let mut best_score = eval;
         let old_alpha: isize = alpha;
         let mut moves = self.board.gen_cap_promo();
         self.board.score_moves(&mut moves);
@@ -59,30 +60,32 @@ impl Search {
             let score = -self.quiescence_search(-beta, -alpha, depth - 1);
             self.board.undo_move();
 
+            if score > best_score {
+                best_score = score;
+                best_mv = Some(mv);
+            }
             if score > alpha {
                 if score >= beta {
-                    // self.board.tt.set(self.board.state.key, mv, score as i16, depth, Bound::Lower);
-                    TT.write().unwrap().set(
+                    // self.board.tt.set(self.board.state.key, mv, score, depth, Bound::Lower);
+                    self.board.tt.write().unwrap().set(
                         self.board.state.key,
                         mv,
-                        score as i16,
+                        score,
                         depth,
                         Bound::Lower,
                     );
-                    return beta;
+                    return score;
                 }
                 alpha = score;
-                best_score = score;
-                best_mv = Some(mv);
             }
         }
 
         if let Some(mv) = best_mv {
             let bound = if best_score > old_alpha { Bound::Exact } else { Bound::Upper };
-            // self.board.tt.set(self.board.state.key, mv, alpha as i16, depth, bound);
-            TT.write().unwrap().set(self.board.state.key, mv, alpha as i16, depth, bound);
+            // self.board.tt.set(self.board.state.key, mv, best_score, depth, bound);
+            self.board.tt.write().unwrap().set(self.board.state.key, mv, best_score, depth, bound);
         }
-        alpha
+        best_score
     }
 }
 
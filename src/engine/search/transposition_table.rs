@@ -1,8 +1,9 @@
 use once_cell::sync::Lazy;
-use std::sync::RwLock;
+use std::fmt;
 use std::sync::atomic::AtomicI16;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
 
 use crate::engine::board::board::Board;
 use crate::engine::board::moves::ExtendedMove;
@@ -10,7 +11,10 @@ use crate::engine::board::moves::Move;
 use crate::engine::move_generator::make_move::BoardMoveTrait;
 use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
 
-pub static TT: Lazy<RwLock<TTTable>> = Lazy::new(|| RwLock::new(TTTable::init()));
+// Table used by the engine, boards cloned from each other share the same table
+pub type SharedTT = Arc<RwLock<TTTable>>;
+
+pub static TT: Lazy<SharedTT> = Lazy::new(|| Arc::new(RwLock::new(TTTable::init())));
 
 const MAX_TT_ENTRIES: usize = 403139;
 
@@ -21,26 +25,25 @@ pub enum Bound {
     Upper,
 }
 
-// NOTE: 64 + 32 + 16 + 8 + 8 = 128 BITS = 16 Bytes
-// NOTE: 1Mb = 1000000 Bytes = 166,666 Entries
-// NOTE: Currently Around 15Mb
+// NOTE: 64 + 48 + 32 + 8 + 8 + 16 = 176 BITS, 24 Bytes with the padding
+// NOTE: 1Mb = 1000000 Bytes = 41,666 Entries
+// NOTE: Currently Around 10Mb
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TTEntry {
     pub key: u64,        // 8 Bytes Max
     pub mv: Move,        // 6 Bytes Max
-    pub score: i16,      // 2 Bytes
+    pub score: i32,      // 4 Bytes
     pub depth: i8,       // 1 Byte
     pub category: Bound, // 1 Byte
     pub age: i16,        // 2 Bytes
 }
 
 impl TTEntry {
-    pub fn init(key: u64, mv: Move, score: i16, depth: i8, category: Bound, age: i16) -> Self {
+    pub fn init(key: u64, mv: Move, score: i32, depth: i8, category: Bound, age: i16) -> Self {
         Self { key, mv, score, depth, category, age }
     }
 }
 
-#[derive(Debug)]
 pub struct TTTable {
     pub table: Box<[Option<TTEntry>]>,
     pub lookups: AtomicU64,
@@ -50,7 +53,25 @@ pub struct TTTable {
     pub curr_age: AtomicI16,
 }
 
+// The entries are left out, the table is part of the board
+impl fmt::Debug for TTTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TTTable")
+            .field("entries", &self.table.len())
+            .field("inserts", &self.inserts)
+            .field("hits", &self.hits)
+            .finish()
+    }
+}
+
 impl TTTable {
+    ///
+    /// New table that is not shared with the engine's table
+    ///
+    pub fn shared() -> SharedTT {
+        Arc::new(RwLock::new(Self::init()))
+    }
+
     pub fn init() -> Self {
         Self {
             table: vec![None; MAX_TT_ENTRIES].into_boxed_slice(), //Box::new([None; MAX_TT_ENTRIES]),
@@ -67,8 +88,13 @@ impl TTTable {
         return (key % MAX_TT_ENTRIES as u64) as usize;
     }
 
-    pub fn set(&mut self, key: u64, mv: Move, score: i16, depth: i8, category: Bound) {
+    ///
+    /// Stores the score of the position with its bound: Exact inside the window, Lower for a
+    /// fail-high (the score is at least that), Upper for a fail-low (the score is at most that)
+    ///
+    pub fn set(&mut self, key: u64, mv: Move, score: isize, depth: i8, category: Bound) {
         self.inserts.fetch_add(1, Ordering::Relaxed);
+        let score = score as i32;
 
         if let Some(entry) = self.table[Self::idx(key)] {
             self.collisions.fetch_add(1, Ordering::Relaxed);
@@ -95,7 +121,11 @@ impl TTTable {
         ));
     }
 
-    pub fn probe(&self, key: u64, depth: i8, mut alpha: i16, mut beta: i16) -> Option<(i16, Move)> {
+    ///
+    /// Score of the position if the stored entry is deep enough and its bound settles the
+    /// window: an exact score, a lower bound at or above beta or an upper bound at or below alpha
+    ///
+    pub fn probe(&self, key: u64, depth: i8, alpha: isize, beta: isize) -> Option<(isize, Move)> {
         self.lookups.fetch_add(1, Ordering::Relaxed);
         let idx = Self::idx(key);
         if let Some(e) = self.table[idx] {
@@ -103,17 +133,15 @@ impl TTTable {
                 && (e.depth as i16 + e.age)
                     >= (depth as i16 + self.curr_age.load(Ordering::Relaxed))
             {
-                match e.category {
-                    Bound::Lower => alpha = alpha.max(e.score),
-                    Bound::Exact => {
-                        self.hits.fetch_add(1, Ordering::Relaxed);
-                        return Some((e.score, e.mv));
-                    }
-                    Bound::Upper => beta = beta.min(e.score),
-                }
-                if alpha >= beta {
+                let score = e.score as isize;
+                let cutoff = match e.category {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                };
+                if cutoff {
                     self.hits.fetch_add(1, Ordering::Relaxed);
-                    return Some((e.score, e.mv));
+                    return Some((score, e.mv));
                 }
             }
         }
@@ -131,6 +159,14 @@ impl TTTable {
         return None;
     }
 
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.lookups.load(Ordering::Relaxed);
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits.load(Ordering::Relaxed) as f64 * 100.0 / lookups as f64
+    }
+
     pub fn print_stats(&self) {
         println!(
             "TT -> lookups: {}; inserts: {}; hits: {}; collisions: {};",
@@ -164,9 +200,8 @@ impl TTTable {
                 break;
             }
 
-            if board.move_exists(&entry.mv) {
+            if board.is_pseudo_legal(&entry.mv) && board.make_move(&entry.mv) {
                 // println!("Before Key: {:?}", board.state.key);
-                board.make_move(&entry.mv);
                 line.push(ExtendedMove { mv: entry.mv, key: board.state.key });
                 // println!("After Key: {:?}", board.state.key);
                 moves_made += 1;
@@ -183,3 +218,29 @@ impl TTTable {
         line
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tt_bounds() {
+        let mut tt = TTTable::init();
+        let mv = Move::null_move();
+        let (key, mate) = (0x1234_5678_9ABC_DEF0, 1_000_000 - 5);
+
+        // Cuts only if the bound settles the window
+        tt.set(key, mv, 150, 4, Bound::Lower);
+        assert_eq!(tt.probe(key, 4, 0, 100), Some((150, mv)));
+        assert_eq!(tt.probe(key, 4, 100, 200), None);
+        assert_eq!(tt.probe(key, 5, 0, 100), None);
+
+        tt.set(key, mv, -50, 4, Bound::Upper);
+        assert_eq!(tt.probe(key, 4, 0, 100), Some((-50, mv)));
+        assert_eq!(tt.probe(key, 4, -100, 0), None);
+
+        // Mate scores and infinite windows don't fit in an i16
+        tt.set(key, mv, -mate, 4, Bound::Exact);
+        assert_eq!(tt.probe(key, 4, isize::MIN / 2, isize::MAX / 2), Some((-mate, mv)));
+    }
+}
@@ -0,0 +1,415 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::RwLock;
+
+use super::syzygy::{TABLES, board_key, material_key, off_a1h8, parse_name};
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, Color, WHITE};
+use crate::engine::board::piece::*;
+use crate::engine::misc::bitboard::BitboardTrait;
+
+// NOTE: Native distance to mate tables, generated with tablebase/generator.rs
+//
+// File Format (.fdtm), all numbers are little endian:
+//   [0..4]  Magic "FDTM"
+//   [4]     Version
+//   [5]     Name Length (n), followed by n bytes of the name (e.g. "KQvKR")
+//   [..+4]  Number of entries
+//   [..]    Run length encoded values as (run length: u8, value: u8) pairs
+//
+// Value per position (side to move): 0 = draw, odd = win in n plies, even = loss in (n - 2) plies
+
+pub static DTM: Lazy<RwLock<DtmTables>> = Lazy::new(|| RwLock::new(DtmTables::init()));
+
+pub const DTM_MAX_PIECES: usize = 4;
+pub const DTM_EXTENSION: &str = "fdtm";
+
+const DTM_MAGIC: [u8; 4] = *b"FDTM";
+const DTM_VERSION: u8 = 1;
+
+// Used only while generating, for indices that don't represent a legal position
+pub(super) const INVALID: u8 = 255;
+
+// Number of king pairs: 462 without pawns (8 fold symmetry), 32 * 64 with pawns (file mirror)
+const KK_PAWNLESS: usize = 462;
+const KK_PAWNS: usize = 32 * 64;
+
+// Piece kinds in the order of the material counts (P, N, B, R, Q, K)
+const COUNT_PIECES: [Piece; 6] = [PAWN, KNIGHT, BISHOP, ROOK, QUEEN, KING];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dtm {
+    Win(u8),
+    Loss(u8),
+    Draw,
+}
+
+impl Dtm {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 | INVALID => Dtm::Draw,
+            c if c & 1 == 1 => Dtm::Win(c),
+            c => Dtm::Loss(c - 2),
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            Dtm::Win(plies) => plies,
+            Dtm::Loss(plies) => plies + 2,
+            Dtm::Draw => 0,
+        }
+    }
+}
+
+// Inverse of the king pair index for the pawnless tables
+static KK_SQUARES: Lazy<Vec<(usize, usize)>> = Lazy::new(|| {
+    let mut squares = vec![(0, 0); KK_PAWNLESS];
+    for s1 in 0..28 {
+        if s1 % 8 > 3 || off_a1h8(s1) > 0 {
+            continue;
+        }
+
+        for s2 in 0..64usize {
+            let adjacent = (s1 % 8).abs_diff(s2 % 8) <= 1 && (s1 / 8).abs_diff(s2 / 8) <= 1;
+            if adjacent || (off_a1h8(s1) == 0 && off_a1h8(s2) > 0) {
+                continue;
+            }
+            let idx = TABLES.map_kk[TABLES.map_a1d1d4[s1] as usize][s2] as usize;
+            squares[idx] = (s1, s2);
+        }
+    }
+    squares
+});
+
+#[inline(always)]
+fn transpose(sq: usize) -> usize {
+    ((sq >> 3) | (sq << 3)) & 63
+}
+
+#[derive(Debug, Clone)]
+pub struct DtmTable {
+    pub name: String,
+    pub key: u64,
+    pub key2: u64,
+    pub has_pawns: bool,
+    pub pieces: Vec<Piece>,
+    pub values: Vec<u8>,
+}
+
+impl DtmTable {
+    pub fn init(name: &str) -> Option<Self> {
+        let counts = parse_name(name)?;
+
+        // White king, black king and then the rest of the white and black pieces
+        let mut pieces = vec![KING | WHITE, KING | BLACK];
+        for color in [WHITE, BLACK] {
+            for kind in (0..5).rev() {
+                let count = counts[color as usize][kind];
+                pieces.extend(std::iter::repeat_n(COUNT_PIECES[kind] | color, count));
+            }
+        }
+
+        if pieces.len() > DTM_MAX_PIECES {
+            return None;
+        }
+
+        let mut table = Self {
+            name: name.to_string(),
+            key: material_key(&counts, false),
+            key2: material_key(&counts, true),
+            has_pawns: counts[0][0] + counts[1][0] > 0,
+            pieces,
+            values: Vec::new(),
+        };
+        table.values = vec![0; table.len()];
+        Some(table)
+    }
+
+    #[inline(always)]
+    pub fn side_size(&self) -> usize {
+        let kk = if self.has_pawns { KK_PAWNS } else { KK_PAWNLESS };
+        kk << (6 * (self.pieces.len() - 2))
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        2 * self.side_size()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    ///
+    /// Index of the position, flip should be set if the colors of the board are swapped
+    /// compared to the table (for example probing KvKQ with the KQvK table)
+    ///
+    pub fn encode(&self, board: &Board, flip: bool) -> usize {
+        let mut squares = [0usize; DTM_MAX_PIECES];
+        let mut n = 0;
+        let mut prev = EMPTY;
+        for &piece in self.pieces.iter() {
+            if piece == prev {
+                continue;
+            }
+            prev = piece;
+
+            let mut bb = board.bb(piece ^ flip as u8);
+            while bb != 0 {
+                squares[n] = bb.pop_lsb() ^ if flip { 56 } else { 0 };
+                n += 1;
+            }
+        }
+        let squares = &mut squares[..n];
+
+        // Symmetry Reduction
+        if squares[0] % 8 > 3 {
+            squares.iter_mut().for_each(|sq| *sq ^= 7);
+        }
+        if !self.has_pawns {
+            if squares[0] / 8 > 3 {
+                squares.iter_mut().for_each(|sq| *sq ^= 56);
+            }
+            if off_a1h8(squares[0]) > 0 {
+                squares.iter_mut().for_each(|sq| *sq = transpose(*sq));
+            }
+            if off_a1h8(squares[0]) == 0 && off_a1h8(squares[1]) > 0 {
+                squares.iter_mut().for_each(|sq| *sq = transpose(*sq));
+            }
+        }
+
+        // Identical pieces are stored in ascending order
+        let mut start = 2;
+        while start < n {
+            let end = (start..n).find(|&i| self.pieces[i] != self.pieces[start]).unwrap_or(n);
+            squares[start..end].sort_unstable();
+            start = end;
+        }
+
+        let kk = match self.has_pawns {
+            true => ((squares[0] / 8) * 4 + squares[0] % 8) * 64 + squares[1],
+            false => TABLES.map_kk[TABLES.map_a1d1d4[squares[0]] as usize][squares[1]] as usize,
+        };
+
+        let stm = (board.color() ^ flip as u8) as usize;
+        let idx = squares[2..].iter().fold(kk, |idx, &sq| (idx << 6) | sq);
+        stm * self.side_size() + idx
+    }
+
+    ///
+    /// Returns the side to move and the squares of the pieces for the index,
+    /// or None if the index doesn't represent a valid (canonical) position
+    ///
+    pub fn decode(&self, idx: usize) -> Option<(Color, [usize; DTM_MAX_PIECES])> {
+        let n = self.pieces.len();
+        let stm = (idx / self.side_size()) as Color;
+        let mut rest = idx % self.side_size();
+
+        let mut squares = [0usize; DTM_MAX_PIECES];
+        for i in (2..n).rev() {
+            squares[i] = rest & 63;
+            rest >>= 6;
+        }
+
+        if self.has_pawns {
+            let king = rest / 64;
+            squares[0] = (king / 4) * 8 + king % 4;
+            squares[1] = rest % 64;
+            let (k1, k2) = (squares[0], squares[1]);
+            if (k1 % 8).abs_diff(k2 % 8) <= 1 && (k1 / 8).abs_diff(k2 / 8) <= 1 {
+                return None;
+            }
+        } else {
+            (squares[0], squares[1]) = KK_SQUARES[rest];
+        }
+
+        for i in 2..n {
+            if squares[..i].contains(&squares[i]) {
+                return None;
+            }
+            if self.pieces[i].is_pawn() && (squares[i] < 8 || squares[i] >= 56) {
+                return None;
+            }
+            if self.pieces[i] == self.pieces[i - 1] && squares[i] < squares[i - 1] {
+                return None;
+            }
+        }
+
+        Some((stm, squares))
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut data = Vec::with_capacity(self.values.len() / 8);
+        data.extend_from_slice(&DTM_MAGIC);
+        data.push(DTM_VERSION);
+        data.push(self.name.len() as u8);
+        data.extend_from_slice(self.name.as_bytes());
+        data.extend_from_slice(&(self.values.len() as u32).to_le_bytes());
+
+        let mut iter = self.values.iter().peekable();
+        while let Some(&value) = iter.next() {
+            let mut run = 1u8;
+            while run < u8::MAX && iter.peek() == Some(&&value) {
+                iter.next();
+                run += 1;
+            }
+            data.push(run);
+            data.push(value);
+        }
+
+        fs::write(path, data)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let data = fs::read(path)?;
+
+        if data.len() < 6 || data[0..4] != DTM_MAGIC || data[4] != DTM_VERSION {
+            return Err(invalid("Not a DTM table"));
+        }
+
+        let name_end = 6 + data[5] as usize;
+        let name = data.get(6..name_end).ok_or_else(|| invalid("Truncated table"))?;
+        let name = String::from_utf8_lossy(name).to_string();
+        let mut table = Self::init(&name).ok_or_else(|| invalid("Unsupported material"))?;
+
+        let size = data.get(name_end..name_end + 4).ok_or_else(|| invalid("Truncated table"))?;
+        let size = u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize;
+        if size != table.len() {
+            return Err(invalid("Wrong table size"));
+        }
+
+        table.values.clear();
+        for pair in data[name_end + 4..].chunks_exact(2) {
+            table.values.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+        }
+
+        if table.values.len() != size {
+            return Err(invalid("Corrupted table"));
+        }
+        Ok(table)
+    }
+}
+
+#[derive(Debug)]
+pub struct DtmTables {
+    tables: Vec<DtmTable>,
+    keys: HashMap<u64, (usize, bool)>,
+}
+
+impl DtmTables {
+    pub fn init() -> Self {
+        Self { tables: Vec::new(), keys: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn contains(&self, key: u64) -> bool {
+        self.keys.contains_key(&key)
+    }
+
+    pub fn tables(&self) -> &[DtmTable] {
+        &self.tables
+    }
+
+    pub fn insert(&mut self, table: DtmTable) {
+        self.keys.insert(table.key2, (self.tables.len(), true));
+        self.keys.insert(table.key, (self.tables.len(), false));
+        self.tables.push(table);
+    }
+
+    ///
+    /// Loads all the tables found in the directory and returns their count
+    ///
+    pub fn init_path(&mut self, path: &str) -> usize {
+        self.tables.clear();
+        self.keys.clear();
+
+        let Ok(files) = fs::read_dir(path.trim()) else {
+            return 0;
+        };
+
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(DTM_EXTENSION) {
+                continue;
+            }
+
+            match DtmTable::read(&path) {
+                Ok(table) => self.insert(table),
+                Err(err) => eprintln!("[DTM]: Failed to read {}: {}", path.display(), err),
+            }
+        }
+
+        self.tables.len()
+    }
+
+    ///
+    /// Probes the position, en passant rights are ignored
+    ///
+    pub fn probe(&self, board: &Board) -> Option<Dtm> {
+        let key = board_key(board);
+        let (idx, flip) = *self.keys.get(&key)?;
+        let table = &self.tables[idx];
+        Some(Dtm::from_code(table.values[table.encode(board, flip)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::board::fen::FenTrait;
+
+    use super::*;
+
+    #[test]
+    fn test_dtm_code() {
+        for dtm in [Dtm::Win(1), Dtm::Win(19), Dtm::Loss(0), Dtm::Loss(18), Dtm::Draw] {
+            assert_eq!(Dtm::from_code(dtm.code()), dtm);
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let table = DtmTable::init("KRvKN").unwrap();
+        assert_eq!(table.pieces, vec![WHITE_KING, BLACK_KING, WHITE_ROOK, BLACK_KNIGHT]);
+
+        let mut valid = 0;
+        for idx in (0..table.len()).step_by(97) {
+            let Some((stm, squares)) = table.decode(idx) else {
+                continue;
+            };
+            valid += 1;
+
+            let mut board = Board::create();
+            board.state.color = stm;
+            for (i, &sq) in squares.iter().enumerate() {
+                board.squares[sq] = table.pieces[i];
+                board.bitboard[table.pieces[i] as usize].set_bit(sq);
+            }
+            assert_eq!(table.encode(&board, false), idx);
+        }
+        assert!(valid > 0);
+    }
+
+    #[test]
+    fn test_encode_symmetry() {
+        let table = DtmTable::init("KQvK").unwrap();
+        let board = Board::read_fen("8/8/8/3k4/8/8/1Q6/K7 w - - 0 1");
+        let mirror = Board::read_fen("8/8/8/4k3/8/8/6Q1/7K w - - 0 1");
+        let flipped = Board::read_fen("k7/1q6/8/8/3K4/8/8/8 b - - 0 1");
+
+        assert_eq!(table.encode(&board, false), table.encode(&mirror, false));
+        assert_eq!(table.encode(&board, false), table.encode(&flipped, true));
+    }
+}
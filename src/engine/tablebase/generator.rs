@@ -0,0 +1,329 @@
+use std::path::Path;
+
+use super::dtm::{DTM_EXTENSION, DTM_MAX_PIECES, Dtm, DtmTable, DtmTables, INVALID};
+use super::syzygy::{board_key, in_check, legal_moves, parse_name};
+use crate::engine::board::board::Board;
+use crate::engine::board::color::ColorTrait;
+use crate::engine::board::moves::Flag;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+const TB_PIECE_CHARS: [char; 6] = ['P', 'N', 'B', 'R', 'Q', 'K'];
+
+// Result of the 1-ply search over the (already known) values of the children
+struct Children {
+    any_move: bool,
+    best_win: Option<u8>,
+    all_lose: bool,
+    worst_loss: u8,
+}
+
+///
+/// Generates distance to mate tables with retrograde analysis. The positions are resolved
+/// in passes, pass n assigns the wins in n plies (odd n) and the losses in n plies (even n),
+/// so every value is the exact distance to mate.
+///
+pub struct Generator {
+    pub tables: DtmTables,
+    board: Board,
+    placed: Vec<usize>,
+    pub verbose: bool,
+}
+
+impl Generator {
+    pub fn init() -> Self {
+        Self {
+            tables: DtmTables::init(),
+            board: Board::create(),
+            placed: Vec::new(),
+            verbose: false,
+        }
+    }
+
+    ///
+    /// Generates the table with all the tables it depends on (captures and promotions)
+    ///
+    pub fn generate(&mut self, name: &str) -> Result<(), String> {
+        let counts = parse_name(name).ok_or(format!("Invalid material: {}", name))?;
+        if counts.iter().flatten().sum::<usize>() > DTM_MAX_PIECES {
+            return Err(format!("Only tables up to {} pieces are supported", DTM_MAX_PIECES));
+        }
+        self.generate_counts(counts);
+        Ok(())
+    }
+
+    fn generate_counts(&mut self, counts: [[usize; 6]; 2]) {
+        let name = material_name(&counts);
+        let table = DtmTable::init(&name).expect("Valid material");
+        if self.tables.contains(table.key) || counts.iter().flatten().sum::<usize>() == 2 {
+            return;
+        }
+
+        for sub in sub_materials(&counts) {
+            self.generate_counts(sub);
+        }
+
+        let table = self.generate_table(table);
+        self.tables.insert(table);
+    }
+
+    ///
+    /// Writes all the generated tables in the directory
+    ///
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        for table in self.tables.tables() {
+            table.write(&dir.join(format!("{}.{}", table.name, DTM_EXTENSION)))?;
+        }
+        Ok(())
+    }
+
+    fn generate_table(&mut self, mut table: DtmTable) -> DtmTable {
+        let mut values = vec![INVALID; table.len()];
+
+        // Mark the legal positions and the checkmates
+        for (idx, value) in values.iter_mut().enumerate() {
+            if !self.setup(&table, idx) {
+                continue;
+            }
+
+            let mated = in_check(&self.board) && legal_moves(&mut self.board).is_empty();
+            *value = if mated { Dtm::Loss(0).code() } else { 0 };
+        }
+
+        // Wins and losses are resolved in alternating passes
+        let max_sub_plies = self.max_sub_plies();
+        let mut pass = 1;
+        let mut idle = 0;
+        while idle < 2 && pass < INVALID - 2 {
+            let mut changed = 0;
+            for idx in 0..values.len() {
+                if values[idx] != 0 || !self.setup(&table, idx) {
+                    continue;
+                }
+
+                let children = self.children(&table, &values);
+                if !children.any_move {
+                    continue;
+                }
+
+                if children.best_win == Some(pass) {
+                    values[idx] = Dtm::Win(pass).code();
+                    changed += 1;
+                } else if children.best_win.is_none()
+                    && children.all_lose
+                    && children.worst_loss == pass
+                {
+                    values[idx] = Dtm::Loss(pass).code();
+                    changed += 1;
+                }
+            }
+
+            if self.verbose && changed > 0 {
+                println!("info string {} pass {} resolved {}", table.name, pass, changed);
+            }
+
+            // Sub tables can have longer mates, so we only stop after them
+            idle = if changed == 0 && pass > max_sub_plies { idle + 1 } else { 0 };
+            pass += 1;
+        }
+
+        self.clear();
+        values.iter_mut().filter(|v| **v == INVALID).for_each(|v| *v = 0);
+        table.values = values;
+        table
+    }
+
+    fn max_sub_plies(&self) -> u8 {
+        let max = self.tables.tables().iter().flat_map(|t| t.values.iter()).max();
+        max.copied().unwrap_or(0)
+    }
+
+    ///
+    /// Places the pieces of the index on the board, returns false if the position is illegal
+    ///
+    fn setup(&mut self, table: &DtmTable, idx: usize) -> bool {
+        self.clear();
+
+        let Some((stm, squares)) = table.decode(idx) else {
+            return false;
+        };
+
+        for (&piece, &sq) in table.pieces.iter().zip(squares.iter()) {
+            self.board.add_piece(sq, piece);
+            self.placed.push(sq);
+        }
+        self.board.state.color = stm;
+
+        // The side that is not on move can't be in check
+        self.board.sq_attack(self.board.king_sq(stm.opp()), stm.opp()) == 0
+    }
+
+    fn clear(&mut self) {
+        while let Some(sq) = self.placed.pop() {
+            self.board.clear_piece(sq, self.board.squares[sq]);
+        }
+    }
+
+    fn children(&mut self, table: &DtmTable, values: &[u8]) -> Children {
+        let mut children =
+            Children { any_move: false, best_win: None, all_lose: true, worst_loss: 0 };
+
+        for (mv, _) in self.board.gen_moves() {
+            if !self.board.make_move(&mv) {
+                continue;
+            }
+            children.any_move = true;
+
+            match Dtm::from_code(self.child_code(table, values)) {
+                Dtm::Loss(plies) => {
+                    let win = plies + 1;
+                    children.best_win = Some(children.best_win.map_or(win, |w| w.min(win)));
+                }
+                Dtm::Win(plies) => children.worst_loss = children.worst_loss.max(plies + 1),
+                Dtm::Draw => children.all_lose = false,
+            }
+
+            self.board.undo_move();
+        }
+
+        children
+    }
+
+    ///
+    /// Value of the current position (after a move), as seen by the side to move
+    ///
+    fn child_code(&mut self, table: &DtmTable, values: &[u8]) -> u8 {
+        // En passant rights are not part of the index, so search one ply deeper
+        if self.board.ep().is_some() && self.has_ep_capture() {
+            let children = self.children(table, values);
+            return match children {
+                Children { any_move: false, .. } if in_check(&self.board) => Dtm::Loss(0).code(),
+                Children { best_win: Some(win), .. } => Dtm::Win(win).code(),
+                Children { any_move: true, all_lose: true, worst_loss, .. } => {
+                    Dtm::Loss(worst_loss).code()
+                }
+                _ => 0,
+            };
+        }
+
+        let key = board_key(&self.board);
+        if key == table.key || key == table.key2 {
+            let code = values[table.encode(&self.board, key != table.key)];
+            return if code == INVALID { 0 } else { code };
+        }
+
+        match self.tables.probe(&self.board) {
+            Some(dtm) => dtm.code(),
+            None => 0, // Bare kings
+        }
+    }
+
+    fn has_ep_capture(&mut self) -> bool {
+        let moves = self.board.gen_captures();
+        moves.iter().filter(|(mv, _)| mv.flag == Flag::EP).any(|(mv, _)| {
+            let legal = self.board.make_move(mv);
+            if legal {
+                self.board.undo_move();
+            }
+            legal
+        })
+    }
+}
+
+fn material_name(counts: &[[usize; 6]; 2]) -> String {
+    let side = |count: &[usize; 6]| -> String {
+        (0..6).rev().flat_map(|k| std::iter::repeat_n(TB_PIECE_CHARS[k], count[k])).collect()
+    };
+    format!("{}v{}", side(&counts[0]), side(&counts[1]))
+}
+
+///
+/// Materials reachable with one capture or one promotion
+///
+fn sub_materials(counts: &[[usize; 6]; 2]) -> Vec<[[usize; 6]; 2]> {
+    let mut subs = Vec::new();
+    for clr in 0..2 {
+        for kind in 0..5 {
+            if counts[clr][kind] == 0 {
+                continue;
+            }
+
+            let mut sub = *counts;
+            sub[clr][kind] -= 1;
+            subs.push(sub);
+
+            if kind == 0 {
+                for promo in 1..5 {
+                    let mut sub = *counts;
+                    sub[clr][0] -= 1;
+                    sub[clr][promo] += 1;
+                    subs.push(sub);
+                }
+            }
+        }
+    }
+    subs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::board::fen::FenTrait;
+
+    use super::*;
+
+    fn max_win(generator: &Generator, name: &str) -> u8 {
+        let table = generator.tables.tables().iter().find(|t| t.name == name).unwrap();
+        *table.values.iter().filter(|&&v| v & 1 == 1).max().unwrap()
+    }
+
+    #[test]
+    fn test_material_name() {
+        assert_eq!(material_name(&parse_name("KQRvKP").unwrap()), "KQRvKP");
+        assert_eq!(sub_materials(&parse_name("KPvK").unwrap()).len(), 5);
+    }
+
+    #[test]
+    fn test_generate_kqk() {
+        let mut generator = Generator::init();
+        generator.generate("KQvK").unwrap();
+
+        // The longest mate with KQK is mate in 10 moves
+        assert_eq!(max_win(&generator, "KQvK"), 19);
+
+        let board = Board::read_fen("k7/8/1K6/8/8/8/7Q/8 w - - 0 1");
+        assert_eq!(generator.tables.probe(&board), Some(Dtm::Win(1)));
+
+        let board = Board::read_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1");
+        assert_eq!(generator.tables.probe(&board), Some(Dtm::Draw));
+
+        // Colors swapped: the black queen mates in 1
+        let board = Board::read_fen("8/7q/8/8/8/1k6/8/K7 b - - 0 1");
+        assert_eq!(generator.tables.probe(&board), Some(Dtm::Win(1)));
+    }
+
+    #[test]
+    fn test_generate_krk() {
+        let mut generator = Generator::init();
+        generator.generate("KRvK").unwrap();
+
+        // The longest mate with KRK is mate in 16 moves
+        assert_eq!(max_win(&generator, "KRvK"), 31);
+    }
+
+    #[test]
+    fn test_generate_kpk() {
+        let mut generator = Generator::init();
+        generator.generate("KPvK").unwrap();
+
+        // The longest mate with KPK is mate in 28 moves (including the promotion)
+        assert_eq!(max_win(&generator, "KPvK"), 55);
+
+        let board = Board::read_fen("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1");
+        assert_eq!(generator.tables.probe(&board), Some(Dtm::Draw));
+
+        let board = Board::read_fen("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1");
+        let flipped = Board::read_fen("8/8/8/8/4p3/4k3/8/4K3 b - - 0 1");
+        assert!(matches!(generator.tables.probe(&board), Some(Dtm::Win(_))));
+        assert_eq!(generator.tables.probe(&board), generator.tables.probe(&flipped));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod dtm;
+pub mod generator;
+pub mod syzygy;
@@ -0,0 +1,1362 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, Color, ColorTrait, WHITE};
+use crate::engine::board::moves::Move;
+use crate::engine::board::piece::*;
+use crate::engine::generated::king::KING_LOOKUP;
+use crate::engine::misc::bitboard::BitboardTrait;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+
+// NOTE: Port of the Syzygy probing code (originally by Ronald de Man, as used in Stockfish)
+// Tables are read lazily into memory the first time a material combination is probed.
+
+pub static TB: Lazy<RwLock<Syzygy>> = Lazy::new(|| RwLock::new(Syzygy::init()));
+
+pub const TB_MAX_PIECES: usize = 7;
+pub const TB_WIN: isize = 20000;
+pub const TB_WIN_IN_MAX_PLY: isize = TB_WIN - 64;
+
+const MAX_DTZ: i32 = 1 << 18;
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// TB Piece Codes: P=1 N=2 B=3 R=4 Q=5 K=6 (+8 for black)
+const TB_PIECE_CHARS: [char; 6] = ['P', 'N', 'B', 'R', 'Q', 'K'];
+
+// Pairs Data Flags
+const FLAG_STM: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss = -2,
+    BlessedLoss = -1,
+    Draw = 0,
+    CursedWin = 1,
+    Win = 2,
+}
+
+impl Wdl {
+    pub fn from_value(value: i32) -> Self {
+        match value {
+            ..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    pub fn sign(self) -> i32 {
+        (self as i32).signum()
+    }
+}
+
+impl std::ops::Neg for Wdl {
+    type Output = Wdl;
+
+    fn neg(self) -> Self::Output {
+        Wdl::from_value(-(self as i32))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeState {
+    Fail,
+    Ok,
+    ChangeStm,
+    ZeroingBestMove,
+}
+
+// Index tables used for the encoding of the positions
+pub(super) struct IndexTables {
+    map_b1h1h7: [u64; 64],
+    pub(super) map_a1d1d4: [u64; 64],
+    pub(super) map_kk: [[u64; 64]; 10],
+    map_pawns: [u64; 64],
+    binomial: [[u64; 64]; 6],
+    lead_pawn_idx: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+pub(super) static TABLES: Lazy<IndexTables> = Lazy::new(IndexTables::init);
+
+#[inline(always)]
+pub(super) fn off_a1h8(sq: usize) -> isize {
+    (sq / 8) as isize - (sq % 8) as isize
+}
+
+impl IndexTables {
+    fn init() -> Self {
+        let mut t = Self {
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            map_pawns: [0; 64],
+            binomial: [[0; 64]; 6],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+
+        // Encodes a square below the a1-h8 diagonal to 0..27
+        let mut code = 0;
+        for sq in 0..64 {
+            if off_a1h8(sq) < 0 {
+                t.map_b1h1h7[sq] = code;
+                code += 1;
+            }
+        }
+
+        // Encodes a square in the a1-d1-d4 triangle to 0..9 (diagonal squares last)
+        let mut diagonal = Vec::new();
+        code = 0;
+        for sq in 0..=27 {
+            if off_a1h8(sq) < 0 && sq % 8 <= 3 {
+                t.map_a1d1d4[sq] = code;
+                code += 1;
+            } else if off_a1h8(sq) == 0 && sq % 8 <= 3 {
+                diagonal.push(sq);
+            }
+        }
+        for sq in diagonal {
+            t.map_a1d1d4[sq] = code;
+            code += 1;
+        }
+
+        // Encodes the 462 legal positions of two kings with the first one in a1-d1-d4
+        let mut both_on_diagonal = Vec::new();
+        code = 0;
+        for idx in 0..10 {
+            for (s1, king_mask) in KING_LOOKUP.iter().enumerate().take(28) {
+                if t.map_a1d1d4[s1] != idx || (idx == 0 && s1 != 1) {
+                    continue;
+                }
+
+                for s2 in 0..64 {
+                    let is_illegal = (king_mask | (1u64 << s1)) & (1u64 << s2) != 0;
+                    if is_illegal || (off_a1h8(s1) == 0 && off_a1h8(s2) > 0) {
+                        continue;
+                    } else if off_a1h8(s1) == 0 && off_a1h8(s2) == 0 {
+                        both_on_diagonal.push((idx as usize, s2));
+                    } else {
+                        t.map_kk[idx as usize][s2] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, sq) in both_on_diagonal {
+            t.map_kk[idx][sq] = code;
+            code += 1;
+        }
+
+        // Binomial coefficients: binomial[k][n] ways to choose k out of n
+        t.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..6.min(n + 1) {
+                t.binomial[k][n] = if k > 0 { t.binomial[k - 1][n - 1] } else { 0 }
+                    + if k < n { t.binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        // Encodes the squares a2-h7 to 0..47 and the leading pawn groups per file
+        let mut available = 47;
+        for lead_cnt in 1..=5 {
+            for file in 0..4 {
+                let mut idx = 0;
+                for rank in 1..7 {
+                    let sq = rank * 8 + file;
+                    if lead_cnt == 1 {
+                        t.map_pawns[sq] = available;
+                        t.map_pawns[sq ^ 7] = available.saturating_sub(1);
+                        available = available.saturating_sub(2);
+                    }
+                    t.lead_pawn_idx[lead_cnt][sq] = idx;
+                    idx += t.binomial[lead_cnt - 1][t.map_pawns[sq] as usize];
+                }
+                t.lead_pawns_size[lead_cnt][file] = idx;
+            }
+        }
+
+        t
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PairsData {
+    flags: u8,
+    block_size: usize,
+    span: usize,
+    num_blocks: usize,
+    max_sym_len: u8,
+    min_sym_len: u8,
+    lowest_sym: usize,
+    btree: usize,
+    block_length: usize,
+    block_length_size: usize,
+    sparse_index: usize,
+    sparse_index_size: usize,
+    data: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    pieces: [u8; TB_MAX_PIECES],
+    group_idx: [u64; TB_MAX_PIECES + 1],
+    group_len: [usize; TB_MAX_PIECES + 1],
+    map_idx: [usize; 4],
+}
+
+// Little/Big endian readers that never panic on truncated files
+#[inline(always)]
+fn u8_at(data: &[u8], pos: usize) -> u8 {
+    data.get(pos).copied().unwrap_or(0)
+}
+
+#[inline(always)]
+fn u16_le(data: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([u8_at(data, pos), u8_at(data, pos + 1)])
+}
+
+#[inline(always)]
+fn u32_le(data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(std::array::from_fn(|i| u8_at(data, pos + i)))
+}
+
+#[inline(always)]
+fn u32_be(data: &[u8], pos: usize) -> u32 {
+    u32::from_be_bytes(std::array::from_fn(|i| u8_at(data, pos + i)))
+}
+
+#[inline(always)]
+fn u64_be(data: &[u8], pos: usize) -> u64 {
+    u64::from_be_bytes(std::array::from_fn(|i| u8_at(data, pos + i)))
+}
+
+#[inline(always)]
+fn btree_left(data: &[u8], btree: usize, sym: usize) -> usize {
+    let pos = btree + sym * 3;
+    (((u8_at(data, pos + 1) & 0xF) as usize) << 8) | u8_at(data, pos) as usize
+}
+
+#[inline(always)]
+fn btree_right(data: &[u8], btree: usize, sym: usize) -> usize {
+    let pos = btree + sym * 3;
+    ((u8_at(data, pos + 2) as usize) << 4) | (u8_at(data, pos + 1) >> 4) as usize
+}
+
+#[derive(Debug)]
+struct TbTable {
+    data: Vec<u8>,
+    is_dtz: bool,
+    map: usize,
+    items: [[PairsData; 4]; 2],
+}
+
+#[derive(Debug)]
+struct TbEntry {
+    key: u64,
+    key2: u64,
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique_pieces: bool,
+    pawn_count: [usize; 2],
+    wdl_path: PathBuf,
+    dtz_path: Option<PathBuf>,
+    wdl: OnceLock<Option<TbTable>>,
+    dtz: OnceLock<Option<TbTable>>,
+}
+
+impl TbTable {
+    fn get(&self, stm: usize, file: usize, has_pawns: bool) -> &PairsData {
+        let sides = if self.is_dtz { 1 } else { 2 };
+        &self.items[stm % sides][if has_pawns { file } else { 0 }]
+    }
+
+    fn load(entry: &TbEntry, path: &Path, is_dtz: bool) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        let magic = if is_dtz { DTZ_MAGIC } else { WDL_MAGIC };
+        if data.len() < 5 || data[0..4] != magic {
+            eprintln!("[Syzygy]: Corrupted table: {}", path.display());
+            return None;
+        }
+
+        let mut table = Self { data, is_dtz, map: 0, items: Default::default() };
+        table.setup(entry)?;
+        Some(table)
+    }
+
+    fn setup(&mut self, entry: &TbEntry) -> Option<()> {
+        let data = std::mem::take(&mut self.data);
+        let mut pos = 4;
+
+        let flags = u8_at(&data, pos);
+        pos += 1;
+        if entry.has_pawns != (flags & 2 != 0) || (entry.key != entry.key2) != (flags & 1 != 0) {
+            return None;
+        }
+
+        let sides = if !self.is_dtz && entry.key != entry.key2 { 2 } else { 1 };
+        let max_file = if entry.has_pawns { 3 } else { 0 };
+        let pp = entry.has_pawns && entry.pawn_count[1] > 0;
+
+        for f in 0..=max_file {
+            let b0 = u8_at(&data, pos);
+            let b1 = u8_at(&data, pos + 1);
+            let order = [
+                [b0 & 0xF, if pp { b1 & 0xF } else { 0xF }],
+                [b0 >> 4, if pp { b1 >> 4 } else { 0xF }],
+            ];
+            pos += 1 + pp as usize;
+
+            for k in 0..entry.piece_count {
+                let byte = u8_at(&data, pos);
+                for i in 0..sides {
+                    self.items[i][f].pieces[k] = if i == 1 { byte >> 4 } else { byte & 0xF };
+                }
+                pos += 1;
+            }
+
+            for (i, &ord) in order.iter().enumerate().take(sides) {
+                Self::set_groups(entry, &mut self.items[i][f], ord, f);
+            }
+        }
+
+        // Word alignment
+        pos += pos & 1;
+
+        for f in 0..=max_file {
+            for i in 0..sides {
+                pos = Self::set_sizes(&mut self.items[i][f], &data, pos);
+            }
+        }
+
+        if self.is_dtz {
+            pos = self.set_dtz_map(&data, pos, max_file);
+        }
+
+        for f in 0..=max_file {
+            for i in 0..sides {
+                self.items[i][f].sparse_index = pos;
+                pos += self.items[i][f].sparse_index_size * 6;
+            }
+        }
+
+        for f in 0..=max_file {
+            for i in 0..sides {
+                self.items[i][f].block_length = pos;
+                pos += self.items[i][f].block_length_size * 2;
+            }
+        }
+
+        for f in 0..=max_file {
+            for i in 0..sides {
+                pos = (pos + 0x3F) & !0x3F; // 64 byte alignment
+                self.items[i][f].data = pos;
+                pos += self.items[i][f].num_blocks * self.items[i][f].block_size;
+            }
+        }
+
+        let valid = pos <= data.len();
+        self.data = data;
+        valid.then_some(())
+    }
+
+    fn set_groups(entry: &TbEntry, d: &mut PairsData, order: [u8; 2], file: usize) {
+        let mut n = 0;
+        let mut first_len: isize = match (entry.has_pawns, entry.has_unique_pieces) {
+            (true, _) => 0,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+        d.group_len[0] = 1;
+
+        for i in 1..entry.piece_count {
+            first_len -= 1;
+            if first_len > 0 || d.pieces[i] == d.pieces[i - 1] {
+                d.group_len[n] += 1;
+            } else {
+                n += 1;
+                d.group_len[n] = 1;
+            }
+        }
+        n += 1;
+        d.group_len[n] = 0;
+
+        // The ordering of the groups is stored in order[] and the indices are
+        // multiplied in the same order to get the final index of the position.
+        let pp = entry.has_pawns && entry.pawn_count[1] > 0;
+        let mut next = if pp { 2 } else { 1 };
+        let mut free_squares = 64 - d.group_len[0] - if pp { d.group_len[1] } else { 0 };
+        let mut idx: u64 = 1;
+
+        let mut k = 0;
+        while next < n || k == order[0] as usize || k == order[1] as usize {
+            if k == order[0] as usize {
+                d.group_idx[0] = idx;
+                idx *= match (entry.has_pawns, entry.has_unique_pieces) {
+                    (true, _) => TABLES.lead_pawns_size[d.group_len[0]][file],
+                    (false, true) => 31332,
+                    (false, false) => 462,
+                };
+            } else if k == order[1] as usize {
+                d.group_idx[1] = idx;
+                idx *= TABLES.binomial[d.group_len[1]][48 - d.group_len[0]];
+            } else {
+                d.group_idx[next] = idx;
+                idx *= TABLES.binomial[d.group_len[next]][free_squares];
+                free_squares -= d.group_len[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        d.group_idx[n] = idx;
+    }
+
+    fn set_sizes(d: &mut PairsData, data: &[u8], mut pos: usize) -> usize {
+        d.flags = u8_at(data, pos);
+        pos += 1;
+
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            d.num_blocks = 0;
+            d.block_length_size = 0;
+            d.span = 0;
+            d.sparse_index_size = 0;
+            d.min_sym_len = u8_at(data, pos);
+            return pos + 1;
+        }
+
+        // The last group index holds the biggest index, that is the table size
+        let n = d.group_len.iter().position(|&len| len == 0).unwrap_or(TB_MAX_PIECES);
+        let tb_size = d.group_idx[n] as usize;
+
+        d.block_size = 1 << u8_at(data, pos);
+        d.span = 1 << u8_at(data, pos + 1);
+        d.sparse_index_size = tb_size.div_ceil(d.span);
+        let padding = u8_at(data, pos + 2) as usize;
+        d.num_blocks = u32_le(data, pos + 3) as usize;
+        d.block_length_size = d.num_blocks + padding;
+        d.max_sym_len = u8_at(data, pos + 7);
+        d.min_sym_len = u8_at(data, pos + 8);
+        pos += 9;
+        d.lowest_sym = pos;
+
+        // Canonical Huffman: base64[i] is the lowest code (left aligned) with length i + min_sym_len
+        let base_len = (d.max_sym_len as usize + 1).saturating_sub(d.min_sym_len as usize);
+        d.base64 = vec![0; base_len];
+        for i in (0..base_len.saturating_sub(1)).rev() {
+            let lowest = u16_le(data, d.lowest_sym + i * 2) as u64;
+            let lowest_next = u16_le(data, d.lowest_sym + (i + 1) * 2) as u64;
+            d.base64[i] = (d.base64[i + 1].wrapping_add(lowest).wrapping_sub(lowest_next)) / 2;
+        }
+        for i in 0..base_len {
+            let shift = (64 - i as u32).saturating_sub(d.min_sym_len as u32);
+            d.base64[i] = d.base64[i].checked_shl(shift).unwrap_or(0);
+        }
+        pos += base_len * 2;
+
+        let symlen_size = u16_le(data, pos) as usize;
+        pos += 2;
+        d.btree = pos;
+        d.symlen = vec![0; symlen_size];
+
+        let mut visited = vec![false; symlen_size];
+        for sym in 0..symlen_size {
+            if !visited[sym] {
+                let len = Self::set_symlen(d, data, sym, &mut visited);
+                d.symlen[sym] = len;
+            }
+        }
+
+        pos + symlen_size * 3 + (symlen_size & 1)
+    }
+
+    fn set_symlen(d: &mut PairsData, data: &[u8], sym: usize, visited: &mut [bool]) -> u8 {
+        visited[sym] = true;
+        let sr = btree_right(data, d.btree, sym);
+        if sr == 0xFFF {
+            return 0;
+        }
+
+        let sl = btree_left(data, d.btree, sym);
+        if sl >= visited.len() || sr >= visited.len() {
+            return 0;
+        }
+
+        if !visited[sl] {
+            let len = Self::set_symlen(d, data, sl, visited);
+            d.symlen[sl] = len;
+        }
+        if !visited[sr] {
+            let len = Self::set_symlen(d, data, sr, visited);
+            d.symlen[sr] = len;
+        }
+
+        d.symlen[sl].wrapping_add(d.symlen[sr]).wrapping_add(1)
+    }
+
+    fn set_dtz_map(&mut self, data: &[u8], mut pos: usize, max_file: usize) -> usize {
+        self.map = pos;
+
+        for f in 0..=max_file {
+            let flags = self.items[0][f].flags;
+            if flags & FLAG_MAPPED == 0 {
+                continue;
+            }
+
+            if flags & FLAG_WIDE != 0 {
+                pos += pos & 1;
+                for i in 0..4 {
+                    self.items[0][f].map_idx[i] = (pos - self.map) / 2 + 1;
+                    pos += 2 * u16_le(data, pos) as usize + 2;
+                }
+            } else {
+                for i in 0..4 {
+                    self.items[0][f].map_idx[i] = pos - self.map + 1;
+                    pos += u8_at(data, pos) as usize + 1;
+                }
+            }
+        }
+
+        pos + (pos & 1)
+    }
+
+    ///
+    /// Decompresses the value stored at index idx using the canonical Huffman tables
+    ///
+    fn decompress_pairs(&self, d: &PairsData, idx: u64) -> i32 {
+        let data = &self.data;
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            return d.min_sym_len as i32;
+        }
+
+        // Find the block using the sparse index and the offset inside the block
+        let k = idx as usize / d.span;
+        let mut block = u32_le(data, d.sparse_index + 6 * k) as usize;
+        let mut offset = u16_le(data, d.sparse_index + 6 * k + 4) as isize;
+        offset += (idx as usize % d.span) as isize - (d.span / 2) as isize;
+
+        let block_len = |b: usize| u16_le(data, d.block_length + 2 * b) as isize;
+        while offset < 0 && block > 0 {
+            block -= 1;
+            offset += block_len(block) + 1;
+        }
+        while offset > block_len(block) && block < d.block_length_size {
+            offset -= block_len(block) + 1;
+            block += 1;
+        }
+
+        let mut ptr = d.data + block * d.block_size;
+        let mut buf64 = u64_be(data, ptr);
+        ptr += 8;
+        let mut buf64_size = 64;
+
+        let mut sym;
+        loop {
+            let mut len = 0;
+            while len + 1 < d.base64.len() && buf64 < d.base64[len] {
+                len += 1;
+            }
+
+            let shift = (64 - len as u32).saturating_sub(d.min_sym_len as u32);
+            sym = (buf64.wrapping_sub(d.base64[len]).checked_shr(shift).unwrap_or(0)) as usize;
+            sym += u16_le(data, d.lowest_sym + 2 * len) as usize;
+
+            let sym_len = d.symlen.get(sym).copied().unwrap_or(0) as isize;
+            if offset < sym_len + 1 {
+                break;
+            }
+            offset -= sym_len + 1;
+
+            len += d.min_sym_len as usize;
+            buf64 = buf64.checked_shl(len as u32).unwrap_or(0);
+            buf64_size -= len as isize;
+
+            if buf64_size <= 32 {
+                buf64_size += 32;
+                buf64 |= (u32_be(data, ptr) as u64) << (64 - buf64_size);
+                ptr += 4;
+            }
+        }
+
+        // Walk down the tree until we reach the symbol at the offset
+        while d.symlen.get(sym).copied().unwrap_or(0) != 0 {
+            let left = btree_left(data, d.btree, sym);
+            let left_len = d.symlen.get(left).copied().unwrap_or(0) as isize;
+            if offset < left_len + 1 {
+                sym = left;
+            } else {
+                offset -= left_len + 1;
+                sym = btree_right(data, d.btree, sym);
+            }
+        }
+
+        btree_left(data, d.btree, sym) as i32
+    }
+
+    fn map_score(&self, d: &PairsData, value: i32, wdl: Wdl) -> i32 {
+        if !self.is_dtz {
+            return value - 2;
+        }
+
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+        let mut value = value as usize;
+
+        if d.flags & FLAG_MAPPED != 0 {
+            let idx = d.map_idx[WDL_MAP[(wdl as i32 + 2) as usize]];
+            value = match d.flags & FLAG_WIDE != 0 {
+                true => u16_le(&self.data, self.map + 2 * (idx + value)) as usize,
+                false => u8_at(&self.data, self.map + idx + value) as usize,
+            };
+        }
+
+        // DTZ tables store the distance in moves or in plies, we always return plies
+        if (wdl == Wdl::Win && d.flags & FLAG_WIN_PLIES == 0)
+            || (wdl == Wdl::Loss && d.flags & FLAG_LOSS_PLIES == 0)
+            || wdl == Wdl::CursedWin
+            || wdl == Wdl::BlessedLoss
+        {
+            value *= 2;
+        }
+
+        value as i32 + 1
+    }
+}
+
+#[inline(always)]
+fn tb_piece(piece: Piece) -> u8 {
+    let kind = match piece.kind() {
+        PAWN => 1,
+        KNIGHT => 2,
+        BISHOP => 3,
+        ROOK => 4,
+        QUEEN => 5,
+        _ => 6,
+    };
+    kind + 8 * piece.color()
+}
+
+#[inline(always)]
+fn tb_count(board: &Board, color: Color) -> [usize; 6] {
+    [
+        board.pawn_count(color),
+        board.knight_count(color),
+        board.bishop_count(color),
+        board.rook_count(color),
+        board.queen_count(color),
+        board.king_count(color),
+    ]
+}
+
+///
+/// Material key of the position in the form used to index the tables (4 bits per piece count)
+///
+pub(super) fn material_key(counts: &[[usize; 6]; 2], flip: bool) -> u64 {
+    let mut key = 0;
+    for (clr, count) in counts.iter().enumerate() {
+        let side = clr ^ flip as usize;
+        for (kind, &cnt) in count.iter().enumerate() {
+            key |= (cnt as u64 & 0xF) << (4 * (6 * side + kind));
+        }
+    }
+    key
+}
+
+#[inline(always)]
+pub(super) fn board_key(board: &Board) -> u64 {
+    material_key(&[tb_count(board, WHITE), tb_count(board, BLACK)], false)
+}
+
+#[inline(always)]
+pub(super) fn in_check(board: &Board) -> bool {
+    board.sq_attack(board.king_sq(board.color()), board.color()) != 0
+}
+
+#[inline(always)]
+fn is_zeroing(mv: &Move) -> bool {
+    mv.flag.is_capture() || mv.piece.is_pawn()
+}
+
+pub(super) fn legal_moves(board: &mut Board) -> Vec<Move> {
+    let mut legal = Vec::new();
+    for (mv, _) in board.gen_moves() {
+        if board.make_move(&mv) {
+            board.undo_move();
+            legal.push(mv);
+        }
+    }
+    legal
+}
+
+///
+/// Parses table names like "KQvKR" into piece counts per color (P, N, B, R, Q, K)
+///
+pub(super) fn parse_name(name: &str) -> Option<[[usize; 6]; 2]> {
+    let (white, black) = name.split_once('v')?;
+    let mut counts = [[0; 6]; 2];
+    for (clr, side) in [white, black].iter().enumerate() {
+        for c in side.chars() {
+            let kind = TB_PIECE_CHARS.iter().position(|&p| p == c)?;
+            counts[clr][kind] += 1;
+        }
+        if counts[clr][5] != 1 {
+            return None;
+        }
+    }
+    Some(counts)
+}
+
+impl TbEntry {
+    fn init(counts: [[usize; 6]; 2], wdl_path: PathBuf, dtz_path: Option<PathBuf>) -> Self {
+        let pawns = [counts[0][0], counts[1][0]];
+        // The leading color is the side with less pawns (better compression)
+        let lead = pawns[1] == 0 || (pawns[0] != 0 && pawns[1] >= pawns[0]);
+        let pawn_count = if lead { pawns } else { [pawns[1], pawns[0]] };
+
+        Self {
+            key: material_key(&counts, false),
+            key2: material_key(&counts, true),
+            piece_count: counts.iter().flatten().sum(),
+            has_pawns: pawns[0] + pawns[1] > 0,
+            has_unique_pieces: counts.iter().any(|c| c[..5].contains(&1)),
+            pawn_count,
+            wdl_path,
+            dtz_path,
+            wdl: OnceLock::new(),
+            dtz: OnceLock::new(),
+        }
+    }
+
+    fn table(&self, is_dtz: bool) -> Option<&TbTable> {
+        match is_dtz {
+            true => {
+                self.dtz.get_or_init(|| TbTable::load(self, self.dtz_path.as_ref()?, true)).as_ref()
+            }
+            false => self.wdl.get_or_init(|| TbTable::load(self, &self.wdl_path, false)).as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Syzygy {
+    entries: Vec<TbEntry>,
+    keys: HashMap<u64, usize>,
+    pub max_pieces: usize,
+}
+
+impl Syzygy {
+    pub fn init() -> Self {
+        Self { entries: Vec::new(), keys: HashMap::new(), max_pieces: 0 }
+    }
+
+    ///
+    /// Scans the given directories (separated with ':' or ';' on windows) for tables
+    /// and returns the number of WDL tables that were found
+    ///
+    pub fn init_path(&mut self, paths: &str) -> usize {
+        self.entries.clear();
+        self.keys.clear();
+        self.max_pieces = 0;
+
+        let separator = if cfg!(windows) { ';' } else { ':' };
+        for dir in paths.split(separator).filter(|d| !d.trim().is_empty()) {
+            let Ok(files) = fs::read_dir(dir.trim()) else {
+                continue;
+            };
+
+            for file in files.flatten() {
+                let path = file.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("rtbw") {
+                    continue;
+                }
+                let Some(counts) = path.file_stem().and_then(|s| s.to_str()).and_then(parse_name)
+                else {
+                    continue;
+                };
+
+                let dtz_path = path.with_extension("rtbz");
+                let dtz_path = dtz_path.exists().then_some(dtz_path);
+                let entry = TbEntry::init(counts, path, dtz_path);
+                if entry.piece_count > TB_MAX_PIECES || self.keys.contains_key(&entry.key) {
+                    continue;
+                }
+
+                self.max_pieces = self.max_pieces.max(entry.piece_count);
+                self.keys.insert(entry.key, self.entries.len());
+                self.keys.insert(entry.key2, self.entries.len());
+                self.entries.push(entry);
+            }
+        }
+
+        self.entries.len()
+    }
+
+    ///
+    /// Probes the table and returns the WDL value or the DTZ value (if is_dtz) for the position
+    ///
+    fn probe_table(&self, board: &Board, wdl: Wdl, result: &mut ProbeState, is_dtz: bool) -> i32 {
+        // KvK
+        if (board.occ_bb(WHITE) | board.occ_bb(BLACK)).count() == 2 {
+            return 0;
+        }
+
+        let key = board_key(board);
+        let Some(entry) = self.keys.get(&key).map(|&idx| &self.entries[idx]) else {
+            *result = ProbeState::Fail;
+            return 0;
+        };
+        let Some(table) = entry.table(is_dtz) else {
+            *result = ProbeState::Fail;
+            return 0;
+        };
+
+        // Tables are stored for the stronger side to move (white), so flip colors when needed
+        let sym_btm = entry.key == entry.key2 && board.color().is_black();
+        let black_stronger = key != entry.key;
+        let flip = sym_btm || black_stronger;
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_sq = if flip { 56 } else { 0 };
+        let stm = (flip as usize) ^ board.color() as usize;
+
+        let mut squares = [0usize; TB_MAX_PIECES];
+        let mut pieces = [0u8; TB_MAX_PIECES];
+        let mut size = 0;
+        let mut lead_pawns = 0;
+        let mut lead_cnt = 0;
+        let mut tb_file = 0;
+
+        // The leading pawn is the one with the highest map_pawns (nearest to the edge, lowest rank)
+        if entry.has_pawns {
+            let piece = table.get(0, 0, true).pieces[0] ^ flip_color;
+            let color = piece >> 3;
+            lead_pawns = board.pawn_bb(color);
+
+            let mut bb = lead_pawns;
+            while bb != 0 {
+                squares[size] = bb.pop_lsb() ^ flip_sq;
+                size += 1;
+            }
+            lead_cnt = size;
+
+            let mut max_idx = 0;
+            for i in 1..lead_cnt {
+                if TABLES.map_pawns[squares[i]] > TABLES.map_pawns[squares[max_idx]] {
+                    max_idx = i;
+                }
+            }
+            squares.swap(0, max_idx);
+            tb_file = (squares[0] % 8).min(7 - squares[0] % 8);
+        }
+
+        // DTZ tables are one-sided, so the probe must be done from the other side
+        if is_dtz {
+            let flags = table.get(0, tb_file, entry.has_pawns).flags;
+            let is_symmetric = entry.key == entry.key2 && !entry.has_pawns;
+            if !is_symmetric && (flags & FLAG_STM) as usize != stm {
+                *result = ProbeState::ChangeStm;
+                return 0;
+            }
+        }
+
+        let mut bb = (board.occ_bb(WHITE) | board.occ_bb(BLACK)) ^ lead_pawns;
+        while bb != 0 {
+            let sq = bb.pop_lsb();
+            squares[size] = sq ^ flip_sq;
+            pieces[size] = tb_piece(board.squares[sq]) ^ flip_color;
+            size += 1;
+        }
+
+        let d = table.get(stm, tb_file, entry.has_pawns);
+
+        // Reorder the pieces to have the same sequence as the one stored in the table
+        for i in lead_cnt..size - 1 {
+            for j in i + 1..size {
+                if d.pieces[i] == pieces[j] {
+                    pieces.swap(i, j);
+                    squares.swap(i, j);
+                    break;
+                }
+            }
+        }
+
+        // Map the lead piece to the a1-d4 triangle
+        if squares[0] % 8 > 3 {
+            squares[..size].iter_mut().for_each(|sq| *sq ^= 7);
+        }
+
+        let mut idx: u64;
+        if entry.has_pawns {
+            idx = TABLES.lead_pawn_idx[lead_cnt][squares[0]];
+            squares[1..lead_cnt].sort_by_key(|&sq| TABLES.map_pawns[sq]);
+            for (i, &sq) in squares.iter().enumerate().take(lead_cnt).skip(1) {
+                idx += TABLES.binomial[i][TABLES.map_pawns[sq] as usize];
+            }
+        } else {
+            if squares[0] / 8 > 3 {
+                squares[..size].iter_mut().for_each(|sq| *sq ^= 56);
+            }
+
+            // Ensure the first piece of the leading group not on the diagonal is below it
+            for i in 0..d.group_len[0] {
+                if off_a1h8(squares[i]) == 0 {
+                    continue;
+                }
+                if off_a1h8(squares[i]) > 0 {
+                    for sq in squares[i..size].iter_mut() {
+                        *sq = ((*sq >> 3) | (*sq << 3)) & 63;
+                    }
+                }
+                break;
+            }
+
+            idx = if entry.has_unique_pieces {
+                let (s0, s1, s2) = (squares[0], squares[1], squares[2]);
+                let adjust1 = (s1 > s0) as u64;
+                let adjust2 = (s2 > s0) as u64 + (s2 > s1) as u64;
+                let (r0, r1, r2) = ((s0 / 8) as u64, (s1 / 8) as u64, (s2 / 8) as u64);
+
+                if off_a1h8(s0) != 0 {
+                    (TABLES.map_a1d1d4[s0] * 63 + (s1 as u64 - adjust1)) * 62 + s2 as u64 - adjust2
+                } else if off_a1h8(s1) != 0 {
+                    (6 * 63 + r0 * 28 + TABLES.map_b1h1h7[s1]) * 62 + s2 as u64 - adjust2
+                } else if off_a1h8(s2) != 0 {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + r0 * 7 * 28
+                        + (r1 - adjust1) * 28
+                        + TABLES.map_b1h1h7[s2]
+                } else {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + r0 * 7 * 6
+                        + (r1 - adjust1) * 6
+                        + (r2 - adjust2)
+                }
+            } else {
+                TABLES.map_kk[TABLES.map_a1d1d4[squares[0]] as usize][squares[1]]
+            };
+        }
+
+        // Encode the remaining pawns and pieces in ascending square order
+        idx *= d.group_idx[0];
+        let mut group_start = d.group_len[0];
+        let mut remaining_pawns = entry.has_pawns && entry.pawn_count[1] > 0;
+        let mut next = 1;
+        while d.group_len[next] != 0 {
+            let len = d.group_len[next];
+            squares[group_start..group_start + len].sort_unstable();
+
+            let mut n = 0;
+            for i in 0..len {
+                let sq = squares[group_start + i];
+                let adjust = squares[..group_start].iter().filter(|&&s| sq > s).count();
+                n += TABLES.binomial[i + 1][sq - adjust - 8 * remaining_pawns as usize];
+            }
+
+            remaining_pawns = false;
+            idx += n * d.group_idx[next];
+            group_start += len;
+            next += 1;
+        }
+
+        table.map_score(d, table.decompress_pairs(d, idx), wdl)
+    }
+
+    ///
+    /// Searches the captures (and pawn moves if check_zeroing) before probing, as tables
+    /// don't store positions where the best move is a capture or where en passant is possible
+    ///
+    fn search(&self, board: &mut Board, result: &mut ProbeState, check_zeroing: bool) -> Wdl {
+        let moves = legal_moves(board);
+        let mut best = Wdl::Loss;
+        let mut move_count = 0;
+
+        for mv in moves.iter() {
+            if !mv.flag.is_capture() && (!check_zeroing || !mv.piece.is_pawn()) {
+                continue;
+            }
+            move_count += 1;
+
+            board.make_move(mv);
+            let value = -self.search(board, result, false);
+            board.undo_move();
+
+            if *result == ProbeState::Fail {
+                return Wdl::Draw;
+            }
+
+            if value > best {
+                best = value;
+                if value >= Wdl::Win {
+                    *result = ProbeState::ZeroingBestMove;
+                    return value;
+                }
+            }
+        }
+
+        let no_more_moves = move_count != 0 && move_count == moves.len();
+        let value = if no_more_moves {
+            best
+        } else {
+            let value = self.probe_table(board, Wdl::Draw, result, false);
+            if *result == ProbeState::Fail {
+                return Wdl::Draw;
+            }
+            Wdl::from_value(value)
+        };
+
+        // DTZ stores a "don't care" value if the best value is a win
+        if best >= value {
+            *result = match best > Wdl::Draw || no_more_moves {
+                true => ProbeState::ZeroingBestMove,
+                false => ProbeState::Ok,
+            };
+            return best;
+        }
+
+        *result = ProbeState::Ok;
+        value
+    }
+
+    pub fn probe_wdl(&self, board: &mut Board, result: &mut ProbeState) -> Wdl {
+        *result = ProbeState::Ok;
+        self.search(board, result, false)
+    }
+
+    ///
+    /// Returns the distance to zeroing (in plies) signed with the WDL result.
+    /// For cursed wins and blessed losses 100 is added to the absolute value.
+    ///
+    pub fn probe_dtz(&self, board: &mut Board, result: &mut ProbeState) -> i32 {
+        *result = ProbeState::Ok;
+        let wdl = self.search(board, result, true);
+
+        if *result == ProbeState::Fail || wdl == Wdl::Draw {
+            return 0;
+        }
+
+        if *result == ProbeState::ZeroingBestMove {
+            return dtz_before_zeroing(wdl);
+        }
+
+        let dtz = self.probe_table(board, wdl, result, true);
+        if *result == ProbeState::Fail {
+            return 0;
+        }
+
+        if *result != ProbeState::ChangeStm {
+            let cursed = matches!(wdl, Wdl::BlessedLoss | Wdl::CursedWin) as i32;
+            return (dtz + 100 * cursed) * wdl.sign();
+        }
+
+        // DTZ stores results for the other side, so find the move that minimizes DTZ
+        let mut min_dtz = 0xFFFF;
+        for mv in legal_moves(board) {
+            let zeroing = is_zeroing(&mv);
+            board.make_move(&mv);
+
+            let mut dtz = match zeroing {
+                true => -dtz_before_zeroing(self.search(board, result, false)),
+                false => -self.probe_dtz(board, result),
+            };
+
+            // If the move mates, force the min_dtz to 1
+            if dtz == 1 && in_check(board) && legal_moves(board).is_empty() {
+                min_dtz = 1;
+            }
+
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+
+            if dtz < min_dtz && dtz.signum() == wdl.sign() {
+                min_dtz = dtz;
+            }
+
+            board.undo_move();
+            if *result == ProbeState::Fail {
+                return 0;
+            }
+        }
+
+        if min_dtz == 0xFFFF { -1 } else { min_dtz }
+    }
+
+    ///
+    /// Ranks the root moves using the DTZ tables. Returns (move, rank, score) for every
+    /// legal move or None if the probe failed.
+    ///
+    pub fn root_probe(&self, board: &mut Board) -> Option<Vec<(Move, i32, isize)>> {
+        let mut result = ProbeState::Ok;
+        let cnt50 = board.half_move() as i32;
+        let rep = Board::is_repetition(board);
+        let bound = MAX_DTZ / 2 - 100;
+
+        let mut ranked = Vec::new();
+        for mv in legal_moves(board) {
+            board.make_move(&mv);
+
+            let mut dtz = if board.half_move() == 0 {
+                dtz_before_zeroing(-self.probe_wdl(board, &mut result))
+            } else if board.half_move() >= 100 || board.is_repetition() {
+                0
+            } else {
+                let dtz = -self.probe_dtz(board, &mut result);
+                dtz + dtz.signum()
+            };
+
+            // Make sure that a mating move is assigned a dtz value of 1
+            if dtz == 2 && in_check(board) && legal_moves(board).is_empty() {
+                dtz = 1;
+            }
+
+            board.undo_move();
+            if result == ProbeState::Fail {
+                return None;
+            }
+
+            // Better moves are ranked higher, certain wins are ranked equally
+            // and losses are ranked equally unless a 50 move draw is in sight
+            let rank = match dtz {
+                1.. if dtz + cnt50 <= 99 && !rep => MAX_DTZ - dtz,
+                1.. => MAX_DTZ / 2 - (dtz + cnt50),
+                ..0 if -dtz * 2 + cnt50 < 100 => -MAX_DTZ - dtz,
+                ..0 => -MAX_DTZ / 2 + (-dtz + cnt50),
+                0 => 0,
+            };
+
+            ranked.push((mv, rank, rank_to_score(rank, bound)));
+        }
+
+        Some(ranked)
+    }
+
+    ///
+    /// Ranks the root moves using only the WDL tables (used when the DTZ tables are missing)
+    ///
+    pub fn root_probe_wdl(&self, board: &mut Board) -> Option<Vec<(Move, i32, isize)>> {
+        const WDL_TO_RANK: [i32; 5] = [-MAX_DTZ, -MAX_DTZ + 101, 0, MAX_DTZ - 101, MAX_DTZ];
+        const WDL_TO_SCORE: [isize; 5] = [-TB_WIN_IN_MAX_PLY + 1, -2, 0, 2, TB_WIN_IN_MAX_PLY - 1];
+
+        let mut result = ProbeState::Ok;
+        let mut ranked = Vec::new();
+        for mv in legal_moves(board) {
+            board.make_move(&mv);
+            let wdl = match board.half_move() >= 100 || board.is_repetition() {
+                true => Wdl::Draw,
+                false => -self.probe_wdl(board, &mut result),
+            };
+            board.undo_move();
+
+            if result == ProbeState::Fail {
+                return None;
+            }
+
+            let idx = (wdl as i32 + 2) as usize;
+            ranked.push((mv, WDL_TO_RANK[idx], WDL_TO_SCORE[idx]));
+        }
+
+        Some(ranked)
+    }
+}
+
+#[inline(always)]
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => 1,
+        Wdl::CursedWin => 101,
+        Wdl::BlessedLoss => -101,
+        Wdl::Loss => -1,
+        Wdl::Draw => 0,
+    }
+}
+
+// Cursed wins get at least 1cp and grow up to 49cp as they get closer to a real win
+#[inline(always)]
+fn rank_to_score(rank: i32, bound: i32) -> isize {
+    let score = match rank {
+        _ if rank >= bound => return TB_WIN_IN_MAX_PLY - 1,
+        1.. => (3.max(rank - (MAX_DTZ / 2 - 200)) * 100) / 200,
+        0 => 0,
+        _ if rank > -bound => ((-3).min(rank + (MAX_DTZ / 2 - 200)) * 100) / 200,
+        _ => return -TB_WIN_IN_MAX_PLY + 1,
+    };
+    score as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::tablebase::dtm::{Dtm, DtmTable};
+    use crate::engine::tablebase::generator::Generator;
+
+    use super::*;
+
+    // Tables of the end-to-end test, downloaded on the first run, see tests/syzygy/README.md
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/syzygy");
+    const FIXTURE_URL: &str = "https://tablebase.lichess.ovh/tables/standard/3-4-5";
+    const FIXTURE_FILES: [&str; 6] =
+        ["KQvK.rtbw", "KQvK.rtbz", "KRvK.rtbw", "KRvK.rtbz", "KPvK.rtbw", "KPvK.rtbz"];
+
+    ///
+    /// Downloads the missing tables of the end-to-end test with curl
+    ///
+    fn fetch_fixtures() {
+        for file in FIXTURE_FILES {
+            let path = Path::new(FIXTURES).join(file);
+            if path.exists() {
+                continue;
+            }
+            // Into a temporary file first, so an interrupted download isn't taken for a table
+            let part = path.with_extension("part");
+            let status = Command::new("curl")
+                .args(["-sSfL", "--retry", "3", "-o"])
+                .arg(&part)
+                .arg(format!("{}/{}", FIXTURE_URL, file))
+                .status();
+            if !status.is_ok_and(|status| status.success()) {
+                let _ = fs::remove_file(&part);
+                panic!("Could not download {}, see tests/syzygy/README.md", file);
+            }
+            fs::rename(&part, &path).unwrap();
+        }
+    }
+
+    ///
+    /// Board of the decoded DTM index, None if the side that just moved is in check
+    ///
+    fn decoded_board(table: &DtmTable, stm: Color, squares: &[usize]) -> Option<Board> {
+        let mut board = Board::create();
+        for (&piece, &sq) in table.pieces.iter().zip(squares) {
+            board.squares[sq] = piece;
+        }
+        board.state.color = stm;
+
+        let board = Board::read_fen(&board.to_fen());
+        let opp = board.color().opp();
+        (board.sq_attack(board.king_sq(opp), opp) == 0).then_some(board)
+    }
+
+    #[test]
+    fn test_index_tables() {
+        let tables = &*TABLES;
+        assert_eq!(tables.map_kk.iter().flatten().max(), Some(&461));
+        assert_eq!(tables.map_a1d1d4[1], 0); // B1
+        assert_eq!(tables.map_a1d1d4[27], 9); // D4
+        assert_eq!(tables.map_b1h1h7.iter().max(), Some(&27));
+        assert_eq!(tables.map_pawns[8], 47); // A2
+        assert_eq!(tables.map_pawns[15], 46); // H2
+        assert_eq!(tables.binomial[2][6], 15);
+        assert_eq!(tables.binomial[5][63], 7028847);
+        assert_eq!(tables.lead_pawns_size[1][0], 6);
+    }
+
+    #[test]
+    fn test_parse_name() {
+        assert_eq!(parse_name("KQvK"), Some([[0, 0, 0, 0, 1, 1], [0, 0, 0, 0, 0, 1]]));
+        assert_eq!(parse_name("KRPvKR"), Some([[1, 0, 0, 1, 0, 1], [0, 0, 0, 1, 0, 1]]));
+        assert_eq!(parse_name("KQvR"), None);
+        assert_eq!(parse_name("KQK"), None);
+    }
+
+    #[test]
+    fn test_material_key() {
+        let entry = TbEntry::init(parse_name("KQvKR").unwrap(), PathBuf::new(), None);
+        let white_strong = Board::read_fen("8/8/8/3k4/8/5r2/1Q6/K7 w - - 0 1");
+        let black_strong = Board::read_fen("8/8/8/3K4/8/5R2/1q6/k7 w - - 0 1");
+
+        assert_eq!(board_key(&white_strong), entry.key);
+        assert_eq!(board_key(&black_strong), entry.key2);
+        assert!(entry.has_unique_pieces);
+        assert!(!entry.has_pawns);
+    }
+
+    #[test]
+    fn test_probe_without_tables() {
+        let syzygy = Syzygy::init();
+        let mut board = Board::read_fen("8/8/8/3k4/8/8/1Q6/K7 w - - 0 1");
+        let mut result = ProbeState::Ok;
+
+        syzygy.probe_wdl(&mut board, &mut result);
+        assert_eq!(result, ProbeState::Fail);
+        assert!(syzygy.root_probe(&mut board).is_none());
+        assert_eq!(board.ply(), 0);
+    }
+
+    #[test]
+    fn test_probe_bare_kings() {
+        let syzygy = Syzygy::init();
+        let mut board = Board::read_fen("8/8/8/3k4/8/8/8/K7 w - - 0 1");
+        let mut result = ProbeState::Ok;
+
+        assert_eq!(syzygy.probe_wdl(&mut board, &mut result), Wdl::Draw);
+        assert_eq!(result, ProbeState::Ok);
+    }
+
+    #[test]
+    fn test_probe_tables() {
+        fetch_fixtures();
+        let mut syzygy = Syzygy::init();
+        assert_eq!(syzygy.init_path(FIXTURES), 3);
+
+        let mut result = ProbeState::Ok;
+        let mut probe = |fen: &str| {
+            let mut board = Board::read_fen(fen);
+            let wdl = syzygy.probe_wdl(&mut board, &mut result);
+            (wdl, syzygy.probe_dtz(&mut board, &mut result))
+        };
+        // Mate in one, stalemate and the pawn that can't be escorted
+        assert_eq!(probe("k7/8/1K6/8/8/8/7Q/8 w - - 0 1"), (Wdl::Win, 1));
+        assert_eq!(probe("k7/8/1Q6/8/8/8/8/7K b - - 0 1"), (Wdl::Draw, 0));
+        assert_eq!(probe("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1"), (Wdl::Draw, 0));
+        assert_eq!(probe("8/8/8/8/4p3/4k3/8/4K3 b - - 0 1").0, Wdl::Win);
+
+        // Every fifth position against the DTM tables of the retrograde analysis. Without
+        // zeroing moves on the way to the mate the DTZ is the distance to mate, up to the
+        // rounding of the tables stored in moves.
+        let mut generator = Generator::init();
+        for name in ["KQvK", "KRvK", "KPvK"] {
+            generator.generate(name).unwrap();
+            let table = DtmTable::init(name).unwrap();
+
+            for idx in (0..table.len()).step_by(5) {
+                let Some((stm, squares)) = table.decode(idx) else {
+                    continue;
+                };
+                let Some(mut board) = decoded_board(&table, stm, &squares[..table.pieces.len()])
+                else {
+                    continue;
+                };
+
+                let fen = board.to_fen();
+                let wdl = syzygy.probe_wdl(&mut board, &mut result);
+                assert_ne!(result, ProbeState::Fail, "{}", fen);
+                let dtz = syzygy.probe_dtz(&mut board, &mut result);
+                assert_ne!(result, ProbeState::Fail, "{}", fen);
+
+                let (expected, plies) = match generator.tables.probe(&board).unwrap() {
+                    Dtm::Win(plies) => (Wdl::Win, plies as i32),
+                    Dtm::Loss(plies) => (Wdl::Loss, -(plies as i32)),
+                    Dtm::Draw => (Wdl::Draw, 0),
+                };
+                assert_eq!(wdl, expected, "{}", fen);
+                assert_eq!(dtz.signum(), wdl.sign(), "{}", fen);
+                match table.has_pawns {
+                    true => assert!(dtz.abs() <= plies.abs() + 1, "{} {} {}", fen, dtz, plies),
+                    false => assert!((dtz - plies).abs() <= 1, "{} {} {}", fen, dtz, plies),
+                }
+            }
+        }
+    }
+}
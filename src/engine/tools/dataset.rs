@@ -0,0 +1,194 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::packed::{PACKED_SIZE, PackedBoard};
+
+// NOTE: Dataset of packed positions
+//
+// The file starts with "FRPD" and the version (u32), followed by fixed size records:
+//  0..32  packed board
+//  32..34 score from white's point of view (i16)
+//  34..36 packed best move (u16)
+//  36..38 ply since the start of the game (u16)
+//  38     result for white: 0 loss, 1 draw, 2 win
+//  39     reserved (zero)
+// All the numbers are little endian.
+
+pub const RECORD_SIZE: usize = 40;
+
+const DATASET_MAGIC: &[u8; 4] = b"FRPD";
+const DATASET_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedEntry {
+    pub board: PackedBoard,
+    pub score: i16,
+    pub best: u16,
+    pub ply: u16,
+    // 0 loss, 1 draw, 2 win for white
+    pub result: u8,
+}
+
+impl PackedEntry {
+    pub fn init(board: &Board, score: isize, best: u16, ply: usize, result: f64) -> Self {
+        Self {
+            board: PackedBoard::encode(board),
+            score: score.clamp(i16::MIN as isize, i16::MAX as isize) as i16,
+            best,
+            ply: ply.min(u16::MAX as usize) as u16,
+            result: (result * 2.0).round().clamp(0.0, 2.0) as u8,
+        }
+    }
+
+    // Result of the game for white: 1.0 win, 0.5 draw, 0.0 loss
+    pub fn result(&self) -> f64 {
+        self.result as f64 / 2.0
+    }
+
+    pub fn to_bytes(&self) -> [u8; RECORD_SIZE] {
+        let mut bytes = [0; RECORD_SIZE];
+        bytes[..PACKED_SIZE].copy_from_slice(&self.board.0);
+        bytes[32..34].copy_from_slice(&self.score.to_le_bytes());
+        bytes[34..36].copy_from_slice(&self.best.to_le_bytes());
+        bytes[36..38].copy_from_slice(&self.ply.to_le_bytes());
+        bytes[38] = self.result;
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; RECORD_SIZE]) -> Result<Self, String> {
+        if bytes[38] > 2 {
+            return Err(format!("Invalid result: {}", bytes[38]));
+        }
+        Ok(Self {
+            board: PackedBoard(bytes[..PACKED_SIZE].try_into().unwrap()),
+            score: i16::from_le_bytes([bytes[32], bytes[33]]),
+            best: u16::from_le_bytes([bytes[34], bytes[35]]),
+            ply: u16::from_le_bytes([bytes[36], bytes[37]]),
+            result: bytes[38],
+        })
+    }
+}
+
+///
+/// Writes the entries one by one after the header
+///
+pub struct DatasetWriter<W: Write> {
+    writer: W,
+    pub count: usize,
+}
+
+impl DatasetWriter<BufWriter<File>> {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Self::init(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> DatasetWriter<W> {
+    pub fn init(mut writer: W) -> io::Result<Self> {
+        writer.write_all(DATASET_MAGIC)?;
+        writer.write_all(&DATASET_VERSION.to_le_bytes())?;
+        Ok(Self { writer, count: 0 })
+    }
+
+    pub fn write(&mut self, entry: &PackedEntry) -> io::Result<()> {
+        self.writer.write_all(&entry.to_bytes())?;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+///
+/// Reads the entries one by one, without loading the whole dataset
+///
+pub struct DatasetReader<R: Read> {
+    reader: R,
+}
+
+impl DatasetReader<BufReader<File>> {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        Self::init(BufReader::new(file))
+    }
+}
+
+impl<R: Read> DatasetReader<R> {
+    pub fn init(mut reader: R) -> Result<Self, String> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header).map_err(|_| "Missing header".to_string())?;
+        if &header[0..4] != DATASET_MAGIC {
+            return Err("Not a dataset file".to_string());
+        }
+        let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if version != DATASET_VERSION {
+            return Err(format!("Unsupported dataset version: {}", version));
+        }
+        Ok(Self { reader })
+    }
+}
+
+impl<R: Read> Iterator for DatasetReader<R> {
+    type Item = Result<PackedEntry, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; RECORD_SIZE];
+        match self.reader.read_exact(&mut bytes) {
+            Ok(()) => Some(PackedEntry::from_bytes(&bytes)),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => None,
+            Err(err) => Some(Err(err.to_string())),
+        }
+    }
+}
+
+///
+/// The file is a dataset of packed positions (and not a text file)
+///
+pub fn is_dataset(path: &Path) -> bool {
+    let mut magic = [0; 4];
+    File::open(path).and_then(|mut file| file.read_exact(&mut magic)).is_ok()
+        && &magic == DATASET_MAGIC
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::fen::FenTrait;
+    use crate::engine::board::packed::pack_move;
+    use crate::engine::board::san::SanTrait;
+    use crate::engine::misc::const_utility::{FEN_POS_FOUR, FEN_START};
+
+    #[test]
+    fn test_dataset() {
+        let mut board = Board::read_fen(FEN_POS_FOUR);
+        let best = pack_move(&board.parse_san("Kh1").unwrap());
+        let entries = [
+            PackedEntry::init(&board, -35, best, 20, 0.0),
+            PackedEntry::init(&Board::read_fen(FEN_START), 40_000, 0, 100_000, 0.5),
+        ];
+        assert_eq!(entries[1].score, i16::MAX);
+        assert_eq!(entries[1].result(), 0.5);
+
+        let mut writer = DatasetWriter::init(Vec::new()).unwrap();
+        entries.iter().for_each(|entry| writer.write(entry).unwrap());
+        assert_eq!(writer.count, 2);
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len(), 8 + 2 * RECORD_SIZE);
+
+        let read: Result<Vec<_>, _> = DatasetReader::init(bytes.as_slice()).unwrap().collect();
+        assert_eq!(read, Ok(entries.to_vec()));
+        assert_eq!(read.unwrap()[0].board.decode().unwrap().to_fen(), FEN_POS_FOUR);
+
+        // A truncated record ends the dataset, an invalid one is an error
+        let mut truncated = bytes[..bytes.len() - 1].to_vec();
+        assert_eq!(DatasetReader::init(truncated.as_slice()).unwrap().count(), 1);
+        truncated[8 + 38] = 3;
+        assert!(DatasetReader::init(truncated.as_slice()).unwrap().next().unwrap().is_err());
+        assert!(DatasetReader::init(&b"FRNN\x01\x00\x00\x00"[..]).is_err());
+    }
+}
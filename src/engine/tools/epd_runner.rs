@@ -0,0 +1,281 @@
+use std::time::{Duration, Instant};
+
+use crate::engine::board::moves::Move;
+use crate::engine::board::san::SanTrait;
+use crate::engine::protocols::epd::EpdPosition;
+use crate::engine::protocols::options::UCIOptions;
+use crate::engine::protocols::uci::UCITime;
+use crate::engine::search::iter_deepening::{Iteration, Search};
+
+// Search time of a position, if no depth or node limit is given
+const DEFAULT_MOVETIME: u64 = 1000;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EpdLimits {
+    pub depth: Option<i8>,
+    pub nodes: Option<usize>,
+    // Milliseconds per position
+    pub movetime: Option<u64>,
+}
+
+impl EpdLimits {
+    pub fn init() -> Self {
+        Self { depth: None, nodes: None, movetime: None }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EpdResult {
+    pub id: String,
+    // SAN of the best moves, or of the avoid moves prefixed with "!"
+    pub expected: Vec<String>,
+    pub mv: Option<Move>,
+    pub san: String,
+    pub solved: bool,
+    // Depth and time from which the search kept a correct move until the end
+    pub solve_depth: Option<i8>,
+    pub solve_time: Option<Duration>,
+    pub depth: i8,
+    pub score: isize,
+    pub nodes: usize,
+    pub time: Duration,
+    // STS points of the played move and the maximum points of the position (0 without points)
+    pub points: u32,
+    pub max_points: u32,
+}
+
+///
+/// Searches the positions of an EPD test suite (WAC, ECM, STS, ...) one by one
+///
+pub struct EpdRunner {
+    pub limits: EpdLimits,
+    pub options: UCIOptions,
+    pub results: Vec<EpdResult>,
+}
+
+impl EpdRunner {
+    pub fn init(limits: EpdLimits) -> Self {
+        Self { limits, options: UCIOptions::init(), results: Vec::new() }
+    }
+
+    ///
+    /// Searches the position and adds its result. The position needs a "bm" or "am"
+    /// operation or STS points, otherwise it can't be solved
+    ///
+    pub fn run(&mut self, epd: &EpdPosition) -> Result<&EpdResult, String> {
+        let best_moves = epd.best_moves()?;
+        let avoid_moves = epd.avoid_moves()?;
+        let points = epd.points();
+        if best_moves.is_empty() && avoid_moves.is_empty() && points.is_empty() {
+            return Err(format!("Nothing to solve: {}", epd.to_epd()));
+        }
+
+        let correct = |mv: &Move| match best_moves.is_empty() && avoid_moves.is_empty() {
+            true => points.iter().any(|(p_mv, p)| p_mv == mv && *p > 0),
+            false => {
+                (best_moves.is_empty() || best_moves.contains(mv)) && !avoid_moves.contains(mv)
+            }
+        };
+
+        let iterations = self.search(epd);
+        let last = iterations.last();
+        let mv = last.map(|it| it.best_mv);
+
+        let solved = mv.as_ref().is_some_and(correct);
+        let solved_from = iterations.iter().rev().take_while(|it| correct(&it.best_mv)).last();
+
+        let mut board = epd.board();
+        let mut expected: Vec<String> = best_moves.iter().map(|mv| board.move_to_san(mv)).collect();
+        expected.extend(avoid_moves.iter().map(|mv| format!("!{}", board.move_to_san(mv))));
+        if expected.is_empty() {
+            let points = points.iter().map(|(mv, p)| format!("{}={}", board.move_to_san(mv), p));
+            expected = points.collect();
+        }
+
+        self.results.push(EpdResult {
+            id: epd.id().map(String::from).unwrap_or(format!("#{}", self.results.len() + 1)),
+            expected,
+            mv,
+            san: mv.map(|mv| board.move_to_san(&mv)).unwrap_or("-".to_string()),
+            solved,
+            solve_depth: solved_from.map(|it| it.depth),
+            solve_time: solved_from.map(|it| it.time),
+            depth: last.map_or(0, |it| it.depth),
+            score: last.map_or(0, |it| it.score),
+            nodes: last.map_or(0, |it| it.nodes),
+            time: last.map_or(Duration::ZERO, |it| it.time),
+            points: points.iter().find(|(p_mv, _)| Some(*p_mv) == mv).map_or(0, |(_, p)| *p),
+            max_points: points.iter().map(|(_, p)| *p).max().unwrap_or(0),
+        });
+        Ok(self.results.last().unwrap())
+    }
+
+    pub fn solved(&self) -> usize {
+        self.results.iter().filter(|r| r.solved).count()
+    }
+
+    ///
+    /// Total STS points and the maximum points of the suite
+    ///
+    pub fn points(&self) -> (u32, u32) {
+        let points = self.results.iter().map(|r| r.points).sum();
+        let max_points = self.results.iter().map(|r| r.max_points).sum();
+        (points, max_points)
+    }
+
+    pub fn print_result(&self, result: &EpdResult) {
+        let solve = match (result.solve_depth, result.solve_time) {
+            (Some(depth), Some(time)) => format!("{:>3} {:>8}", depth, time.as_millis()),
+            _ => format!("{:>3} {:>8}", "-", "-"),
+        };
+        println!(
+            "{:<24} {:<20} {:<8} {:<4} {:>3} {} {:>7} {:>12} {:>6}",
+            truncate(&result.id, 24),
+            truncate(&result.expected.join(" "), 20),
+            result.san,
+            if result.solved { "ok" } else { "FAIL" },
+            result.depth,
+            solve,
+            result.score,
+            result.nodes,
+            format!("{}/{}", result.points, result.max_points),
+        );
+    }
+
+    pub fn print_header(&self) {
+        println!(
+            "{:<24} {:<20} {:<8} {:<4} {:>3} {:>3} {:>8} {:>7} {:>12} {:>6}",
+            "Id", "Expected", "Move", "", "Dep", "Sol", "Sol(ms)", "Score", "Nodes", "Points"
+        );
+    }
+
+    pub fn print_summary(&self) {
+        let total = self.results.len();
+        let time: Duration = self.results.iter().map(|r| r.time).sum();
+        let nodes: usize = self.results.iter().map(|r| r.nodes).sum();
+        println!(
+            "Solved {}/{} ({:.1}%), nodes {}, time {} ms",
+            self.solved(),
+            total,
+            100.0 * self.solved() as f64 / total.max(1) as f64,
+            nodes,
+            time.as_millis()
+        );
+
+        let (points, max_points) = self.points();
+        if max_points > 0 {
+            println!(
+                "Points {}/{} ({:.1}%)",
+                points,
+                max_points,
+                100.0 * points as f64 / max_points as f64
+            );
+        }
+    }
+
+    ///
+    /// The results as CSV, one line per position, so that the versions can be compared
+    ///
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "id,expected,move,solved,depth,solve_depth,solve_time_ms,score,nodes,time_ms,points,max_points\n",
+        );
+        for r in &self.results {
+            csv.push_str(&format!(
+                "\"{}\",\"{}\",{},{},{},{},{},{},{},{},{},{}\n",
+                r.id.replace('"', "\"\""),
+                r.expected.join(" "),
+                r.san,
+                r.solved as u8,
+                r.depth,
+                r.solve_depth.map(|d| d.to_string()).unwrap_or_default(),
+                r.solve_time.map(|t| t.as_millis().to_string()).unwrap_or_default(),
+                r.score,
+                r.nodes,
+                r.time.as_millis(),
+                r.points,
+                r.max_points,
+            ));
+        }
+        csv
+    }
+
+    // Every position is searched from an empty transposition table, the depth is taken
+    // from the "acd" operation if no limit was given
+    fn search(&self, epd: &EpdPosition) -> Vec<Iteration> {
+        let limits = self.limits;
+        let depth = limits.depth.or(epd.acd());
+        let movetime = match (depth, limits.nodes, limits.movetime) {
+            (None, None, None) => Some(DEFAULT_MOVETIME),
+            (_, _, movetime) => movetime,
+        };
+
+        let mut uci = UCITime::init();
+        uci.max_depth = depth.unwrap_or(63).clamp(1, 63);
+        uci.max_nodes = limits.nodes;
+        uci.time_limit = Some(Duration::from_millis(movetime.unwrap_or(u64::MAX)));
+
+        let mut search = Search::init(epd.board(), uci);
+        search.board.tt.write().unwrap().clear();
+        search.options = self.options.clone();
+        search.silent = true;
+        search.uci.start_time = Instant::now();
+        search.iterative_deepening();
+        search.iterations
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    match text.chars().count() > width {
+        true => text.chars().take(width - 1).chain(['~']).collect(),
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epd_runner() {
+        let positions = [
+            // Mate in 1
+            r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id "mate";"#,
+            r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - am Kf1; id "avoid";"#,
+            r#"6k1/5ppp/8/8/8/8/8/R5K1 w - - c0 "Ra8=10, Ra7=1";"#,
+        ];
+
+        let mut runner = EpdRunner::init(EpdLimits { depth: Some(3), nodes: None, movetime: None });
+        for epd in positions {
+            let epd = EpdPosition::parse(epd).unwrap();
+            let result = runner.run(&epd).unwrap();
+            assert!(result.solved, "{:?}", result);
+            assert_eq!(result.depth, 3);
+        }
+
+        let mate = &runner.results[0];
+        assert_eq!(mate.san, "Ra8#");
+        assert!(mate.solve_depth.is_some_and(|depth| depth <= mate.depth));
+        assert!(mate.solve_time.is_some_and(|time| time <= mate.time));
+        assert_eq!(runner.results[2].id, "#3");
+        assert_eq!(runner.solved(), 3);
+        assert_eq!(runner.points(), (10, 10));
+        assert_eq!(runner.to_csv().lines().count(), 4);
+
+        let epd = EpdPosition::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - id \"none\";").unwrap();
+        assert!(runner.run(&epd).is_err());
+    }
+
+    #[test]
+    fn test_epd_node_limit() {
+        let epd = EpdPosition::parse(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - bm O-O;",
+        )
+        .unwrap();
+        let mut runner =
+            EpdRunner::init(EpdLimits { depth: None, nodes: Some(5000), movetime: None });
+        let result = runner.run(&epd).unwrap();
+        assert!(result.mv.is_some());
+        assert!(result.depth < 63);
+    }
+}
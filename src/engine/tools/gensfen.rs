@@ -0,0 +1,399 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::dataset::{DatasetWriter, PackedEntry};
+use super::match_runner::{Adjudication, Opening, ResignStreaks, Tables, game_over};
+use crate::engine::board::board::Board;
+use crate::engine::board::color::ColorTrait;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::board::moves::Move;
+use crate::engine::board::packed::pack_move;
+use crate::engine::board::san::SanTrait;
+use crate::engine::misc::display::display_moves::move_notation;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+use crate::engine::protocols::options::UCIOptions;
+use crate::engine::protocols::uci::UCITime;
+use crate::engine::search::iter_deepening::Search;
+use crate::engine::tablebase::syzygy::TB_WIN_IN_MAX_PLY;
+
+// NOTE: Training data generator (gensfen)
+//
+// Plays self-play games at a fixed depth or node count, starting from the openings (or the
+// start position) followed by a few random moves. The quiet positions of every game are
+// labeled with the search score, the best move and the final result of the game.
+//
+// Text format, one position per line, score and result from white's point of view (the
+// tuner reads it as is):
+//     <fen> [<result: 1.0, 0.5 or 0.0>] <score> <best move> <ply>
+// Output files ending with ".bin" are written as a dataset of packed positions instead.
+
+// Mate and tablebase win scores
+const MAX_SCORE: isize = TB_WIN_IN_MAX_PLY - 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfenEntry {
+    pub fen: String,
+    // Search score from white's point of view
+    pub score: isize,
+    pub best: Move,
+    // Plies played since the start of the game (including the opening)
+    pub ply: usize,
+    // Result of the game for white: 1.0 win, 0.5 draw, 0.0 loss
+    pub result: f64,
+}
+
+impl SfenEntry {
+    pub fn to_text(&self) -> String {
+        let best = move_notation(self.best.from, self.best.to, self.best.flag.get_promo_piece());
+        format!("{} [{:.1}] {} {} {}", self.fen, self.result, self.score, best, self.ply)
+    }
+
+    pub fn to_packed(&self) -> PackedEntry {
+        let board = Board::read_fen(&self.fen);
+        PackedEntry::init(&board, self.score, pack_move(&self.best), self.ply, self.result)
+    }
+}
+
+enum SfenWriter {
+    Text(BufWriter<File>),
+    Binary(DatasetWriter<BufWriter<File>>),
+}
+
+impl SfenWriter {
+    fn create(path: &Path) -> io::Result<Self> {
+        match path.extension().is_some_and(|ext| ext == "bin") {
+            true => Ok(Self::Binary(DatasetWriter::create(path)?)),
+            false => Ok(Self::Text(BufWriter::new(File::create(path)?))),
+        }
+    }
+
+    fn write(&mut self, entry: &SfenEntry) -> io::Result<()> {
+        match self {
+            Self::Text(writer) => writeln!(writer, "{}", entry.to_text()),
+            Self::Binary(writer) => writer.write(&entry.to_packed()),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Text(mut writer) => writer.flush(),
+            Self::Binary(writer) => writer.finish().map(|_| ()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SfenFilters {
+    pub skip_in_check: bool,
+    pub skip_captures: bool,
+    pub skip_mates: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GenOptions {
+    // Number of positions to write
+    pub positions: usize,
+    pub depth: i8,
+    // Fixed nodes per move instead of the depth
+    pub nodes: Option<usize>,
+    pub threads: usize,
+    pub openings: Vec<Opening>,
+    // Random moves played after the opening
+    pub random_moves: usize,
+    pub filters: SfenFilters,
+    pub adjudication: Adjudication,
+    // Seed of the random moves, random games if none
+    pub seed: Option<u64>,
+    pub output: PathBuf,
+    pub engine: UCIOptions,
+    pub verbose: bool,
+}
+
+impl GenOptions {
+    pub fn init() -> Self {
+        Self {
+            positions: 100_000,
+            depth: 6,
+            nodes: None,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            openings: Vec::new(),
+            random_moves: 8,
+            filters: SfenFilters { skip_in_check: true, skip_captures: true, skip_mates: true },
+            adjudication: Adjudication::init(),
+            seed: None,
+            output: PathBuf::from("sfens.txt"),
+            engine: UCIOptions::init(),
+            verbose: true,
+        }
+    }
+}
+
+pub struct GenSfen {
+    pub options: GenOptions,
+    pub games: usize,
+    pub written: usize,
+}
+
+impl GenSfen {
+    pub fn init(options: GenOptions) -> Self {
+        Self { options, games: 0, written: 0 }
+    }
+
+    ///
+    /// Plays games until enough positions are written, returns the number of positions
+    ///
+    pub fn run(&mut self) -> Result<usize, String> {
+        let mut writer = SfenWriter::create(&self.options.output)
+            .map_err(|err| format!("{}: {}", self.options.output.display(), err))?;
+
+        let next_game = AtomicUsize::new(0);
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let start = Instant::now();
+
+        let options = &self.options;
+        let result: Result<(), String> = thread::scope(|scope| {
+            for _ in 0..options.threads.max(1) {
+                let sender = sender.clone();
+                let (next_game, stop) = (&next_game, Arc::clone(&stop));
+
+                scope.spawn(move || {
+                    let tables = Tables::init();
+                    loop {
+                        let game = next_game.fetch_add(1, Ordering::Relaxed);
+                        if stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let mut rng = match options.seed {
+                            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(game as u64)),
+                            None => StdRng::from_rng(&mut rand::rng()),
+                        };
+
+                        match play_game(options, &tables, game, &mut rng, &stop) {
+                            Some(entries) => {
+                                if sender.send(entries).is_err() {
+                                    break;
+                                }
+                            }
+                            None => break,
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            for entries in receiver {
+                self.games += 1;
+                let remaining = options.positions - self.written;
+                for entry in entries.iter().take(remaining) {
+                    // On an error the receiver is dropped, which stops the workers as well
+                    writer.write(entry).map_err(|e| e.to_string())?;
+                }
+                self.written += entries.len().min(remaining);
+
+                if options.verbose && self.games.is_multiple_of(100) {
+                    println!(
+                        "{} games, {} positions, {:.0} positions/s",
+                        self.games,
+                        self.written,
+                        self.written as f64 / start.elapsed().as_secs_f64()
+                    );
+                }
+                if self.written >= options.positions {
+                    stop.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+            Ok(())
+        });
+
+        result?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(self.written)
+    }
+}
+
+// Plays the game, returns its positions or None if the generator was stopped
+fn play_game(
+    options: &GenOptions,
+    tables: &Tables,
+    game: usize,
+    rng: &mut StdRng,
+    stop: &Arc<AtomicBool>,
+) -> Option<Vec<SfenEntry>> {
+    let opening = match options.openings.is_empty() {
+        true => Opening::start(),
+        false => options.openings[game % options.openings.len()].clone(),
+    };
+    let adjudication = &options.adjudication;
+    tables.clear();
+
+    let mut board = Board::read_fen(&opening.fen);
+    let mut ply = 0;
+    for mv in &opening.moves {
+        board.play_root_move(mv);
+        ply += 1;
+    }
+
+    for _ in 0..options.random_moves {
+        let moves = board.legal_moves();
+        if moves.is_empty() {
+            break;
+        }
+        board.play_root_move(&moves[rng.random_range(0..moves.len())]);
+        ply += 1;
+    }
+
+    let mut entries = Vec::new();
+    let mut resign = ResignStreaks::default();
+    let mut draw_count = 0;
+    let start_ply = ply;
+
+    // Result from white's point of view
+    let result: f64 = loop {
+        if let Some((result, _)) = game_over(&mut board) {
+            break match result {
+                "1-0" => 1.0,
+                "0-1" => 0.0,
+                _ => 0.5,
+            };
+        }
+        if ply - start_ply >= adjudication.max_ply {
+            break 0.5;
+        }
+
+        let mut uci = UCITime::init();
+        uci.stopped = Arc::clone(stop);
+        uci.max_depth = if options.nodes.is_some() { 63 } else { options.depth.clamp(1, 63) };
+        uci.max_nodes = options.nodes;
+        uci.time_limit = Some(Duration::from_millis(u64::MAX));
+
+        let mut search = Search::init(board.clone(), uci);
+        search.board.tt = Arc::clone(&tables.tt);
+        search.board.pawn_tt = Arc::clone(&tables.pawn_tt);
+        search.board.eval_tt = Arc::clone(&tables.eval_tt);
+        search.options = options.engine.clone();
+        search.silent = true;
+
+        search.uci.start_time = Instant::now();
+        let best_mv = search.iterative_deepening();
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let (Some(mv), Some(iteration)) = (best_mv, search.iterations.last()) else {
+            break 0.5;
+        };
+        let score = iteration.score;
+        let sign = board.color().sign();
+        let side = board.color().is_black() as usize;
+
+        let filters = &options.filters;
+        let in_check = board.sq_attack(board.king_sq(board.color()), board.color()) != 0;
+        let skip = (filters.skip_in_check && in_check)
+            || (filters.skip_captures && mv.flag.is_capture())
+            || (filters.skip_mates && score.abs() >= MAX_SCORE);
+        if !skip {
+            entries.push(SfenEntry {
+                fen: board.to_fen(),
+                score: score * sign,
+                best: mv,
+                ply,
+                result: 0.5,
+            });
+        }
+
+        board.play_root_move(&mv);
+        ply += 1;
+
+        // Adjudication with the scores of both sides, the one that just moved updates its own
+        if let Some(loser) = resign.update(adjudication, side, score) {
+            break if loser == 0 { 0.0 } else { 1.0 };
+        }
+
+        let played = ply - start_ply;
+        draw_count =
+            match played >= adjudication.draw_min_ply && score.abs() <= adjudication.draw_score {
+                true => draw_count + 1,
+                false => 0,
+            };
+        if draw_count >= 2 * adjudication.draw_moves {
+            break 0.5;
+        }
+    };
+
+    entries.iter_mut().for_each(|entry| entry.result = result);
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::packed::unpack_move;
+    use crate::engine::misc::display::display_moves::from_move_notation;
+    use crate::engine::tools::dataset::DatasetReader;
+    use crate::engine::tools::tuner::TuneEntry;
+
+    #[test]
+    fn test_gensfen() {
+        let path = std::env::temp_dir().join("fri_challenger_gensfen.txt");
+        let mut options = GenOptions::init();
+        options.positions = 30;
+        options.depth = 2;
+        options.threads = 2;
+        options.seed = Some(1);
+        options.adjudication.max_ply = 20;
+        options.output = path.clone();
+        options.verbose = false;
+
+        let mut generator = GenSfen::init(options);
+        assert_eq!(generator.run(), Ok(30));
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 30);
+        for line in text.lines() {
+            // The tuner reads the positions with their results
+            let entry = TuneEntry::parse(line).unwrap();
+            let mut board = Board::read_fen(&entry.fen);
+            assert_eq!(board.sq_attack(board.king_sq(board.color()), board.color()), 0);
+
+            let fields: Vec<&str> = line.split(']').nth(1).unwrap().split_whitespace().collect();
+            assert!(fields[0].parse::<isize>().unwrap().abs() < MAX_SCORE);
+            assert_eq!(fields[1].len(), 4);
+            let mv = from_move_notation(fields[1], &mut board);
+            assert!(!mv.flag.is_capture());
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_gensfen_binary() {
+        let path = std::env::temp_dir().join("fri_challenger_gensfen.bin");
+        let mut options = GenOptions::init();
+        options.positions = 10;
+        options.depth = 2;
+        options.threads = 1;
+        options.seed = Some(2);
+        options.adjudication.max_ply = 20;
+        options.output = path.clone();
+        options.verbose = false;
+        assert_eq!(GenSfen::init(options).run(), Ok(10));
+
+        let reader = DatasetReader::open(&path).unwrap();
+        for entry in reader {
+            let entry = entry.unwrap();
+            let mut board = entry.board.decode().unwrap();
+            let mv = unpack_move(entry.best, &mut board).unwrap();
+            assert!(!mv.flag.is_capture());
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
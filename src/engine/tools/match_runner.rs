@@ -0,0 +1,624 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::sprt::{MatchScore, Sprt, SprtStatus};
+use crate::engine::board::board::Board;
+use crate::engine::board::color::{BLACK, ColorTrait, WHITE};
+use crate::engine::board::fen::FenTrait;
+use crate::engine::board::moves::Move;
+use crate::engine::board::piece::*;
+use crate::engine::board::san::SanTrait;
+use crate::engine::misc::bitboard::BitboardTrait;
+use crate::engine::misc::const_utility::FEN_START;
+use crate::engine::move_generator::make_move::BoardMoveTrait;
+use crate::engine::move_generator::mv_gen::BoardGenMoveTrait;
+use crate::engine::protocols::epd::read_epd;
+use crate::engine::protocols::options::UCIOptions;
+use crate::engine::protocols::pgn::{PgnGame, PgnReader};
+use crate::engine::protocols::time::set_time_limit;
+use crate::engine::protocols::uci::UCITime;
+use crate::engine::search::eval_hash_table::{EvalHashTable, SharedEvalTT};
+use crate::engine::search::iter_deepening::Search;
+use crate::engine::search::pawn_hash_table::{PawnHashTable, SharedPawnTT};
+use crate::engine::search::transposition_table::{SharedTT, TTTable};
+
+// NOTE: Self-play matches between two configurations of the engine
+//
+// Every opening is played twice with swapped colors. The games run in parallel, every
+// game has its own hash tables for each engine, so that the two configurations never
+// share search results. The tablebases and the opening book are shared by both engines.
+
+// Moves to go used to split the clock between the moves
+const MOVES_TO_GO: usize = 30;
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    pub name: String,
+    pub options: UCIOptions,
+}
+
+impl EngineConfig {
+    pub fn init(name: &str) -> Self {
+        Self { name: name.to_string(), options: UCIOptions::init() }
+    }
+
+    ///
+    /// Sets the option the same way as "setoption name <name> value <value>"
+    ///
+    pub fn set_option(&mut self, name: &str, value: &str) {
+        let mut args = vec!["name"];
+        args.extend(name.split_whitespace());
+        args.push("value");
+        args.extend(value.split_whitespace());
+        self.options.set_option(&args);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub inc: Duration,
+}
+
+impl TimeControl {
+    ///
+    /// Parses "<base>+<increment>" in seconds, e.g. "10+0.1"
+    ///
+    pub fn parse(tc: &str) -> Option<Self> {
+        let (base, inc) = tc.split_once('+').unwrap_or((tc, "0"));
+        let seconds = |v: &str| v.trim().parse::<f64>().ok().filter(|v| *v >= 0.0);
+        Some(Self {
+            base: Duration::from_secs_f64(seconds(base)?),
+            inc: Duration::from_secs_f64(seconds(inc)?),
+        })
+    }
+
+    pub fn to_pgn(&self) -> String {
+        format!("{}+{}", self.base.as_secs_f64(), self.inc.as_secs_f64())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Opening {
+    pub fen: String,
+    pub moves: Vec<Move>,
+}
+
+impl Opening {
+    pub fn start() -> Self {
+        Self { fen: FEN_START.to_string(), moves: Vec::new() }
+    }
+}
+
+///
+/// Reads the openings from a PGN (the main line of every game) or from an EPD file
+///
+pub fn load_openings(path: &Path) -> Result<Vec<Opening>, String> {
+    let file = File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let reader = BufReader::new(file);
+
+    let openings: Result<Vec<Opening>, String> = match path.extension().and_then(|e| e.to_str()) {
+        Some("pgn") => PgnReader::init(reader)
+            .map(|game| {
+                let game = game?;
+                Ok(Opening { fen: game.start_fen().to_string(), moves: game.mainline() })
+            })
+            .collect(),
+        _ => read_epd(reader)
+            .into_iter()
+            .map(|epd| Ok(Opening { fen: epd?.fen, moves: Vec::new() }))
+            .collect(),
+    };
+    openings
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Adjudication {
+    // A side resigns when both engines agree for resign_moves moves each: its score is below
+    // -resign_score and the score of the opponent is above resign_score
+    pub resign_score: isize,
+    pub resign_moves: usize,
+    // Draw when both scores are within draw_score for draw_moves moves after draw_min_ply
+    pub draw_score: isize,
+    pub draw_moves: usize,
+    pub draw_min_ply: usize,
+    // Games longer than this are drawn
+    pub max_ply: usize,
+}
+
+impl Adjudication {
+    pub fn init() -> Self {
+        Self {
+            resign_score: 1000,
+            resign_moves: 3,
+            draw_score: 10,
+            draw_moves: 8,
+            draw_min_ply: 80,
+            max_ply: 400,
+        }
+    }
+}
+
+// Consecutive moves of each side (white 0, black 1) with a losing and with a winning score
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResignStreaks {
+    losing: [usize; 2],
+    winning: [usize; 2],
+}
+
+impl ResignStreaks {
+    ///
+    /// Adds the score of the side that just moved, returns the side that resigns once both
+    /// engines agree on it
+    ///
+    pub fn update(
+        &mut self,
+        adjudication: &Adjudication,
+        side: usize,
+        score: isize,
+    ) -> Option<usize> {
+        let streak = |count: usize, cond: bool| if cond { count + 1 } else { 0 };
+        self.losing[side] = streak(self.losing[side], score <= -adjudication.resign_score);
+        self.winning[side] = streak(self.winning[side], score >= adjudication.resign_score);
+
+        let agree = |loser: usize| {
+            self.losing[loser] >= adjudication.resign_moves
+                && self.winning[1 - loser] >= adjudication.resign_moves
+        };
+        [side, 1 - side].into_iter().find(|&loser| agree(loser))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchOptions {
+    // Maximum number of games, the openings are repeated if there are not enough of them
+    pub games: usize,
+    pub tc: TimeControl,
+    // Fixed nodes per move instead of the clock
+    pub nodes: Option<usize>,
+    pub concurrency: usize,
+    pub openings: Vec<Opening>,
+    pub adjudication: Adjudication,
+    pub sprt: Option<Sprt>,
+    pub pgn: Option<PathBuf>,
+    // Prints the result of every game and the running statistics
+    pub verbose: bool,
+}
+
+impl MatchOptions {
+    pub fn init() -> Self {
+        Self {
+            games: 100,
+            tc: TimeControl { base: Duration::from_secs(10), inc: Duration::from_millis(100) },
+            nodes: None,
+            concurrency: 1,
+            openings: Vec::new(),
+            adjudication: Adjudication::init(),
+            sprt: None,
+            pgn: None,
+            verbose: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    // Index of the game, even games are played by the first engine with white
+    pub round: usize,
+    pub game: PgnGame,
+    // Result for the first engine: 1.0 = win, 0.5 = draw, 0.0 = loss
+    pub score: f64,
+}
+
+pub struct Match {
+    pub engines: [EngineConfig; 2],
+    pub options: MatchOptions,
+    pub score: MatchScore,
+    pub status: SprtStatus,
+    pub games: Vec<GameRecord>,
+}
+
+impl Match {
+    pub fn init(first: EngineConfig, second: EngineConfig, options: MatchOptions) -> Self {
+        Self {
+            engines: [first, second],
+            options,
+            score: MatchScore::default(),
+            status: SprtStatus::Continue,
+            games: Vec::new(),
+        }
+    }
+
+    ///
+    /// Plays the games until all are done or the SPRT accepts one of the hypotheses
+    ///
+    pub fn run(&mut self) -> MatchScore {
+        let next_game = AtomicUsize::new(0);
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let mut pgn_file = self.options.pgn.as_ref().and_then(|path| {
+            let file = OpenOptions::new().create(true).append(true).open(path);
+            file.map_err(|err| eprintln!("[Match]: Can't open {}: {}", path.display(), err)).ok()
+        });
+
+        // The results are added to the match while the games are played
+        let (engines, options) = (self.engines.clone(), self.options.clone());
+        thread::scope(|scope| {
+            for _ in 0..options.concurrency.max(1) {
+                let sender = sender.clone();
+                let (next_game, stop) = (&next_game, Arc::clone(&stop));
+                let (engines, options) = (&engines, &options);
+
+                scope.spawn(move || {
+                    let tables = [Tables::init(), Tables::init()];
+                    loop {
+                        let round = next_game.fetch_add(1, Ordering::Relaxed);
+                        if round >= options.games || stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        match play_game(engines, &tables, options, round, &stop) {
+                            Some(record) => sender.send(record).unwrap(),
+                            None => break,
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            for record in receiver {
+                self.add_game(record, pgn_file.as_mut());
+                if self.status != SprtStatus::Continue {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+
+        self.score
+    }
+
+    pub fn print_summary(&self) {
+        let score = &self.score;
+        println!(
+            "Score of {} vs {}: {} - {} - {} [{:.3}] {}",
+            self.engines[0].name,
+            self.engines[1].name,
+            score.wins,
+            score.losses,
+            score.draws,
+            score.score(),
+            score.games()
+        );
+        println!(
+            "Elo difference: {:.1} +/- {:.1}, LOS: {:.1} %",
+            score.elo(),
+            score.elo_error(),
+            100.0 * score.los()
+        );
+
+        if let Some(sprt) = &self.options.sprt {
+            let (lower, upper) = sprt.bounds();
+            let status = match self.status {
+                SprtStatus::Continue => "no decision",
+                SprtStatus::AcceptH0 => "H0 accepted",
+                SprtStatus::AcceptH1 => "H1 accepted",
+            };
+            println!(
+                "SPRT: llr {:.2} ({:.2}, {:.2}) [{:.1}, {:.1}], {}",
+                sprt.llr(score),
+                lower,
+                upper,
+                sprt.elo0,
+                sprt.elo1,
+                status
+            );
+        }
+    }
+
+    fn add_game(&mut self, record: GameRecord, pgn_file: Option<&mut File>) {
+        self.score.add(record.score);
+        if let Some(sprt) = &self.options.sprt {
+            self.status = sprt.status(&self.score);
+        }
+
+        if let Some(file) = pgn_file
+            && let Err(err) = file.write_all(record.game.to_pgn().as_bytes())
+        {
+            eprintln!("[Match]: Failed to write the game: {}", err);
+        }
+
+        if self.options.verbose {
+            let game = &record.game;
+            println!(
+                "Finished game {} ({} vs {}): {} {{{}}}",
+                record.round + 1,
+                game.tag("White").unwrap_or("?"),
+                game.tag("Black").unwrap_or("?"),
+                game.result,
+                game.tag("Termination").unwrap_or("normal")
+            );
+            self.print_summary();
+        }
+        self.games.push(record);
+    }
+}
+
+// Hash tables of an engine, reused for all the games of a thread
+pub struct Tables {
+    pub tt: SharedTT,
+    pub pawn_tt: SharedPawnTT,
+    pub eval_tt: SharedEvalTT,
+}
+
+impl Tables {
+    pub fn init() -> Self {
+        Self {
+            tt: TTTable::shared(),
+            pawn_tt: PawnHashTable::shared(),
+            eval_tt: EvalHashTable::shared(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.tt.write().unwrap().clear();
+        self.pawn_tt.write().unwrap().clear();
+        self.eval_tt.clear();
+    }
+}
+
+// Plays the game, returns None if the match was stopped before the game was over
+fn play_game(
+    engines: &[EngineConfig; 2],
+    tables: &[Tables; 2],
+    options: &MatchOptions,
+    round: usize,
+    stop: &Arc<AtomicBool>,
+) -> Option<GameRecord> {
+    let opening = match options.openings.is_empty() {
+        true => Opening::start(),
+        false => options.openings[(round / 2) % options.openings.len()].clone(),
+    };
+    // The engine playing white: the first engine in the even rounds
+    let white = round % 2;
+    let adjudication = &options.adjudication;
+    tables.iter().for_each(Tables::clear);
+
+    let mut board = Board::read_fen(&opening.fen);
+    for mv in &opening.moves {
+        board.play_root_move(mv);
+    }
+
+    let mut moves = opening.moves.clone();
+    let mut comments = vec![None; moves.len()];
+    let mut clocks = [options.tc.base; 2];
+    let mut resign = ResignStreaks::default();
+    let mut draw_count = 0;
+
+    // Result from white's point of view and the reason the game ended
+    let (result, termination): (&str, &str) = loop {
+        let side = board.color().is_black() as usize;
+        let engine = (white + side) % 2;
+        let (loss, win) = if side == 0 { ("0-1", "1-0") } else { ("1-0", "0-1") };
+
+        if let Some(end) = game_over(&mut board) {
+            break end;
+        }
+        if moves.len() - opening.moves.len() >= adjudication.max_ply {
+            break ("1/2-1/2", "adjudication");
+        }
+
+        let mut uci = UCITime::init();
+        uci.stopped = Arc::clone(stop);
+        uci.max_nodes = options.nodes;
+        uci.time_limit = match options.nodes {
+            Some(_) => Some(Duration::from_millis(u64::MAX)),
+            None => {
+                let clock = clocks[side].as_millis() as usize;
+                let inc = options.tc.inc.as_millis() as usize;
+                Some(set_time_limit(MOVES_TO_GO, clock, inc))
+            }
+        };
+
+        let mut search = Search::init(board.clone(), uci);
+        search.board.tt = Arc::clone(&tables[engine].tt);
+        search.board.pawn_tt = Arc::clone(&tables[engine].pawn_tt);
+        search.board.eval_tt = Arc::clone(&tables[engine].eval_tt);
+        search.options = engines[engine].options.clone();
+        search.silent = true;
+
+        let start = Instant::now();
+        search.uci.start_time = start;
+        let best_mv = search.iterative_deepening();
+        let elapsed = start.elapsed();
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        if options.nodes.is_none() {
+            if elapsed > clocks[side] {
+                break (loss, "time forfeit");
+            }
+            clocks[side] = clocks[side] - elapsed + options.tc.inc;
+        }
+
+        let Some(mv) = best_mv else {
+            break (loss, "no move");
+        };
+        let (score, depth) = search.iterations.last().map_or((0, 0), |it| (it.score, it.depth));
+        comments.push(Some(format!(
+            "{:+.2}/{} {:.3}s",
+            score as f64 / 100.0,
+            depth,
+            elapsed.as_secs_f64()
+        )));
+
+        board.play_root_move(&mv);
+        moves.push(mv);
+
+        // Adjudication with the scores of both sides, the one that just moved updates its own
+        if let Some(loser) = resign.update(adjudication, side, score) {
+            break (if loser == side { loss } else { win }, "adjudication");
+        }
+
+        let played = moves.len() - opening.moves.len();
+        draw_count =
+            match played >= adjudication.draw_min_ply && score.abs() <= adjudication.draw_score {
+                true => draw_count + 1,
+                false => 0,
+            };
+        if draw_count >= 2 * adjudication.draw_moves {
+            break ("1/2-1/2", "adjudication");
+        }
+    };
+
+    let mut game = PgnGame::from_moves(&opening.fen, &moves, &comments).ok()?;
+    game.set_tag("Event", "FRI Challenger match");
+    game.set_tag("Round", &(round + 1).to_string());
+    game.set_tag("White", &engines[white].name);
+    game.set_tag("Black", &engines[1 - white].name);
+    game.set_tag("Result", result);
+    game.set_tag("Termination", termination);
+    match options.nodes {
+        Some(nodes) => game.set_tag("Nodes", &nodes.to_string()),
+        None => game.set_tag("TimeControl", &options.tc.to_pgn()),
+    }
+    game.result = result.to_string();
+
+    let white_score = match result {
+        "1-0" => 1.0,
+        "0-1" => 0.0,
+        _ => 0.5,
+    };
+    let score = if white == 0 { white_score } else { 1.0 - white_score };
+    Some(GameRecord { round, game, score })
+}
+
+// Result and reason if the game is over by the rules
+pub fn game_over(board: &mut Board) -> Option<(&'static str, &'static str)> {
+    if board.legal_moves().is_empty() {
+        let in_check = board.sq_attack(board.king_sq(board.color()), board.color()) != 0;
+        return match (in_check, board.color().is_white()) {
+            (true, true) => Some(("0-1", "checkmate")),
+            (true, false) => Some(("1-0", "checkmate")),
+            (false, _) => Some(("1/2-1/2", "stalemate")),
+        };
+    }
+
+    if board.half_move() >= 100 {
+        return Some(("1/2-1/2", "fifty moves"));
+    }
+
+    // The position occurred twice before since the last irreversible move
+    let start = board.history.len().saturating_sub(board.half_move() as usize);
+    let repetitions = board.history[start..].iter().filter(|s| s.key == board.key()).count();
+    if repetitions >= 2 {
+        return Some(("1/2-1/2", "threefold repetition"));
+    }
+
+    if insufficient_material(board) {
+        return Some(("1/2-1/2", "insufficient material"));
+    }
+    None
+}
+
+// Only the kings and at most one minor piece
+fn insufficient_material(board: &Board) -> bool {
+    let count = |kinds: &[Piece]| -> usize {
+        let pieces = kinds.iter().flat_map(|&kind| [kind | WHITE, kind | BLACK]);
+        pieces.map(|piece| board.bb(piece).count()).sum()
+    };
+    count(&[PAWN, ROOK, QUEEN]) == 0 && count(&[KNIGHT, BISHOP]) <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_control() {
+        let tc = TimeControl::parse("10+0.1").unwrap();
+        assert_eq!(tc.base, Duration::from_secs(10));
+        assert_eq!(tc.inc, Duration::from_millis(100));
+        assert_eq!(TimeControl::parse("60").unwrap().inc, Duration::ZERO);
+        assert_eq!(TimeControl::parse("a+1"), None);
+    }
+
+    #[test]
+    fn test_game_over() {
+        let mut board = Board::read_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(game_over(&mut board), Some(("1-0", "checkmate")));
+
+        let mut board = Board::read_fen("7k/8/6QK/8/8/8/8/8 b - - 0 1");
+        assert_eq!(game_over(&mut board), Some(("1/2-1/2", "stalemate")));
+
+        let mut board = Board::read_fen("7k/8/6NK/8/8/8/8/8 b - - 0 1");
+        assert_eq!(game_over(&mut board), Some(("1/2-1/2", "insufficient material")));
+
+        let mut board = Board::read_fen("7k/8/8/8/8/8/8/R5K1 b - - 100 80");
+        assert_eq!(game_over(&mut board), Some(("1/2-1/2", "fifty moves")));
+
+        let mut board = Board::read_fen(FEN_START);
+        for san in ["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"] {
+            assert_eq!(game_over(&mut board), None);
+            let mv = board.parse_san(san).unwrap();
+            board.make_move(&mv);
+        }
+        assert_eq!(game_over(&mut board), None);
+        let mv = board.parse_san("Ng8").unwrap();
+        board.make_move(&mv);
+        assert_eq!(game_over(&mut board), Some(("1/2-1/2", "threefold repetition")));
+    }
+
+    #[test]
+    fn test_resign_streaks() {
+        let adjudication = Adjudication::init();
+        let mut resign = ResignStreaks::default();
+
+        // White sees itself lost from the start, black sees the win only from its second move
+        for black_score in [0, 1200, 1100] {
+            assert_eq!(resign.update(&adjudication, 0, -1500), None);
+            assert_eq!(resign.update(&adjudication, 1, black_score), None);
+        }
+        assert_eq!(resign.update(&adjudication, 0, -1500), None);
+        assert_eq!(resign.update(&adjudication, 1, 1300), Some(0));
+
+        // A score below the margin starts the streak again
+        let mut resign = ResignStreaks::default();
+        for score in [-1500, -1500, -900, -1500, -1500] {
+            assert_eq!(resign.update(&adjudication, 1, score), None);
+            assert_eq!(resign.update(&adjudication, 0, 1500), None);
+        }
+        assert_eq!(resign.update(&adjudication, 1, -1500), Some(1));
+    }
+
+    #[test]
+    fn test_match() {
+        let mut options = MatchOptions::init();
+        options.games = 4;
+        options.nodes = Some(2000);
+        options.concurrency = 2;
+        options.verbose = false;
+        options.adjudication.max_ply = 20;
+        options.openings = vec![Opening::start()];
+
+        let mut first = EngineConfig::init("First");
+        first.set_option("SyzygyProbeLimit", "0");
+        let mut runner = Match::init(first, EngineConfig::init("Second"), options);
+        let score = runner.run();
+
+        assert_eq!(score.games(), 4);
+        assert_eq!(runner.games.len(), 4);
+        for record in &runner.games {
+            let white = if record.round % 2 == 0 { "First" } else { "Second" };
+            assert_eq!(record.game.tag("White"), Some(white));
+            assert!(record.game.moves.len() <= 20);
+            assert!(record.game.board().is_ok());
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod dataset;
+pub mod epd_runner;
+pub mod gensfen;
+pub mod match_runner;
+pub mod sprt;
+pub mod symmetry;
+pub mod tuner;
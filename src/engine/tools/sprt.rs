@@ -0,0 +1,185 @@
+// NOTE: Match statistics, always from the point of view of the first engine
+//
+// The SPRT uses the normal approximation of the log-likelihood ratio (as in Fishtest), with
+// the hypotheses given in logistic Elo: H0 = the change is worth elo0, H1 = it is worth elo1.
+
+// Quantile of the normal distribution for the 95% confidence interval
+const Z_95: f64 = 1.959964;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    ///
+    /// Adds the result of a game: 1.0 = win, 0.5 = draw, 0.0 = loss
+    ///
+    pub fn add(&mut self, result: f64) {
+        match result {
+            r if r > 0.75 => self.wins += 1,
+            r if r < 0.25 => self.losses += 1,
+            _ => self.draws += 1,
+        }
+    }
+
+    ///
+    /// Average score per game in [0, 1]
+    ///
+    pub fn score(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            games => (self.wins as f64 + self.draws as f64 / 2.0) / games as f64,
+        }
+    }
+
+    ///
+    /// Variance of the score of a single game
+    ///
+    pub fn variance(&self) -> f64 {
+        let games = self.games().max(1) as f64;
+        let score = self.score();
+        let (w, d, l) = (self.wins as f64, self.draws as f64, self.losses as f64);
+        (w * (1.0 - score).powi(2) + d * (0.5 - score).powi(2) + l * score.powi(2)) / games
+    }
+
+    pub fn elo(&self) -> f64 {
+        score_to_elo(self.score())
+    }
+
+    ///
+    /// Half width of the 95% confidence interval of the Elo difference
+    ///
+    pub fn elo_error(&self) -> f64 {
+        let margin = Z_95 * (self.variance() / self.games().max(1) as f64).sqrt();
+        let score = self.score();
+        (score_to_elo(score + margin) - score_to_elo(score - margin)) / 2.0
+    }
+
+    ///
+    /// Likelihood of superiority: the probability that the first engine is stronger
+    ///
+    pub fn los(&self) -> f64 {
+        let decisive = (self.wins + self.losses) as f64;
+        if decisive == 0.0 {
+            return 0.5;
+        }
+        0.5 * (1.0 + erf((self.wins as f64 - self.losses as f64) / (2.0 * decisive).sqrt()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtStatus {
+    Continue,
+    // The change is not better than elo0
+    AcceptH0,
+    // The change is better than elo1
+    AcceptH1,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    // False positive and false negative rates
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    pub fn init(elo0: f64, elo1: f64) -> Self {
+        Self { elo0, elo1, alpha: 0.05, beta: 0.05 }
+    }
+
+    ///
+    /// Lower and upper bounds of the log-likelihood ratio
+    ///
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    pub fn llr(&self, score: &MatchScore) -> f64 {
+        // Not enough information until both sides won a game
+        let variance = score.variance();
+        if score.wins == 0 || score.losses == 0 || variance <= 0.0 {
+            return 0.0;
+        }
+
+        let (s0, s1) = (elo_to_score(self.elo0), elo_to_score(self.elo1));
+        score.games() as f64 * (s1 - s0) * (2.0 * score.score() - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn status(&self, score: &MatchScore) -> SprtStatus {
+        let (lower, upper) = self.bounds();
+        let llr = self.llr(score);
+        match llr {
+            llr if llr >= upper => SprtStatus::AcceptH1,
+            llr if llr <= lower => SprtStatus::AcceptH0,
+            _ => SprtStatus::Continue,
+        }
+    }
+}
+
+pub fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+pub fn score_to_elo(score: f64) -> f64 {
+    let score = score.clamp(1e-6, 1.0 - 1e-6);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+// Abramowitz and Stegun 7.1.26, the error is below 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x >= 0.0 { y } else { -y }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_score() {
+        let score = MatchScore { wins: 60, draws: 20, losses: 20 };
+        assert_eq!(score.games(), 100);
+        assert!((score.score() - 0.7).abs() < 1e-9);
+        assert!((score.elo() - 147.2).abs() < 0.1);
+        assert!(score.elo_error() > 0.0 && score.elo_error() < score.elo());
+        assert!(score.los() > 0.99);
+
+        let even = MatchScore { wins: 10, draws: 5, losses: 10 };
+        assert!(even.elo().abs() < 1e-9);
+        assert!((even.los() - 0.5).abs() < 1e-9);
+        assert!((elo_to_score(score_to_elo(0.3)) - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sprt() {
+        let sprt = Sprt::init(0.0, 10.0);
+        let (lower, upper) = sprt.bounds();
+        assert!((lower + 2.944).abs() < 1e-3 && (upper - 2.944).abs() < 1e-3);
+
+        let mut score = MatchScore::default();
+        assert_eq!(sprt.status(&score), SprtStatus::Continue);
+
+        score = MatchScore { wins: 400, draws: 200, losses: 200 };
+        assert_eq!(sprt.status(&score), SprtStatus::AcceptH1);
+
+        score = MatchScore { wins: 200, draws: 200, losses: 400 };
+        assert_eq!(sprt.status(&score), SprtStatus::AcceptH0);
+
+        score = MatchScore { wins: 11, draws: 10, losses: 10 };
+        assert_eq!(sprt.status(&score), SprtStatus::Continue);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::engine::board::board::Board;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::evaluation::breakdown_eval::{Score, term_scores};
+use crate::engine::evaluation::evaluation::EvaluationTrait;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asymmetry {
+    pub term: &'static str,
+    // (mg, eg) of white and black, in the position and in the mirrored one
+    pub score: [Score; 2],
+    pub mirrored: [Score; 2],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymmetryResult {
+    pub fen: String,
+    pub eval: isize,
+    pub mirror_eval: isize,
+    pub terms: Vec<Asymmetry>,
+}
+
+impl SymmetryResult {
+    pub fn is_symmetric(&self) -> bool {
+        self.eval == self.mirror_eval && self.terms.is_empty()
+    }
+}
+
+///
+/// Evaluates the position and its color flipped mirror, which have to get the same score
+/// (from the side to move's point of view), and lists the terms that see them differently
+///
+pub fn check_symmetry(board: &Board) -> SymmetryResult {
+    let mut board = board.clone();
+    let fen = board.to_fen();
+    let eval = board.evaluation();
+    let scores = term_scores(&mut board);
+
+    board.mirror();
+    let mirror_eval = board.evaluation();
+    let mirrored = term_scores(&mut board);
+
+    let terms = scores
+        .into_iter()
+        .zip(mirrored)
+        .filter(|((_, score), (_, mirrored))| score[0] != mirrored[1] || score[1] != mirrored[0])
+        .map(|((term, score), (_, mirrored))| Asymmetry { term, score, mirrored })
+        .collect();
+
+    SymmetryResult { fen, eval, mirror_eval, terms }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluation::test_evaluation::SF_EVAL;
+    use crate::engine::misc::const_utility::{
+        FEN_CASTLE_ONE, FEN_MATE_IN_5, FEN_POS_FIVE, FEN_POS_THREE,
+    };
+
+    #[test]
+    fn test_symmetry() {
+        let fens = SF_EVAL.iter().map(|obj| obj.fen);
+        for fen in fens.chain([FEN_CASTLE_ONE, FEN_MATE_IN_5, FEN_POS_THREE, FEN_POS_FIVE]) {
+            let result = check_symmetry(&Board::read_fen(fen));
+            assert!(result.is_symmetric(), "{:?}", result);
+        }
+    }
+}
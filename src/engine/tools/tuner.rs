@@ -0,0 +1,352 @@
+// NOTE: Texel tuning of the evaluation weights
+//
+// The error of a weight set is the mean squared difference between the game results and the
+// sigmoid of the static evaluation: E = 1/N * sum (R - 1 / (1 + 10^(-K * q / 400)))^2.
+// K is fitted once for the default weights, then every weight is moved by a step up or down
+// as long as the error decreases (local search).
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use crate::engine::board::board::Board;
+use crate::engine::board::color::ColorTrait;
+use crate::engine::board::fen::FenTrait;
+use crate::engine::evaluation::eval_params::{EvalParams, SharedEvalParams};
+use crate::engine::evaluation::evaluation::EvaluationTrait;
+use crate::engine::tools::dataset::{DatasetReader, is_dataset};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneEntry {
+    pub fen: String,
+    // Result of the game for white: 1.0 win, 0.5 draw, 0.0 loss
+    pub result: f64,
+}
+
+impl TuneEntry {
+    ///
+    /// Parses a FEN (the move counters are optional) followed by the result, either as
+    /// "[1.0]", "[0.5]", "[0.0]" or as "1-0", "1/2-1/2", "0-1" (e.g. in a c9 EPD operation)
+    ///
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let result = match (line.find('['), line.find(']')) {
+            (Some(start), Some(end)) if start < end => line[start + 1..end].trim().parse().ok(),
+            _ if line.contains("1/2-1/2") => Some(0.5),
+            _ if line.contains("1-0") => Some(1.0),
+            _ if line.contains("0-1") => Some(0.0),
+            _ => None,
+        };
+        let Some(result) = result.filter(|r| (0.0..=1.0).contains(r)) else {
+            return Err(format!("Missing result: {}", line));
+        };
+
+        let mut fields: Vec<&str> = line.split_whitespace().take(4).collect();
+        let counters = line.split_whitespace().skip(4).take(2).map(|c| c.trim_end_matches(';'));
+        fields.extend(counters.take_while(|c| c.parse::<u32>().is_ok()));
+        match fields.len() {
+            4 => fields.extend(["0", "1"]),
+            5 => fields.push("1"),
+            _ => (),
+        }
+
+        let fen = fields.join(" ");
+        match Board::try_read_fen(&fen) {
+            Some(_) => Ok(Self { fen, result }),
+            None => Err(format!("Invalid FEN: {}", line)),
+        }
+    }
+}
+
+///
+/// Reads the labeled positions of a dataset, one per line (or a dataset of packed positions).
+/// The positions should be quiet, as they are scored by the static evaluation
+///
+pub fn load_dataset(path: &Path) -> Result<Vec<TuneEntry>, String> {
+    if is_dataset(path) {
+        let mut entries = Vec::new();
+        for (idx, entry) in DatasetReader::open(path)?.enumerate() {
+            let entry = entry.and_then(|entry| Ok((entry.board.decode()?, entry.result())));
+            let (board, result) = entry.map_err(|e| format!("Position {}: {}", idx + 1, e))?;
+            entries.push(TuneEntry { fen: board.to_fen(), result });
+        }
+        return Ok(entries);
+    }
+
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        entries.push(TuneEntry::parse(&line).map_err(|e| format!("Line {}: {}", idx + 1, e))?);
+    }
+    Ok(entries)
+}
+
+#[derive(Debug, Clone)]
+pub struct TuneOptions {
+    // Scaling constant of the sigmoid, fitted to the dataset if none
+    pub k: Option<f64>,
+    pub passes: usize,
+    pub step: isize,
+    // Only the weights whose names start with one of the prefixes, all if empty
+    pub filter: Vec<String>,
+    pub threads: usize,
+    // Weights written after every pass, as Rust source if the file ends with ".rs",
+    // otherwise as a parameter file for the EvalParamsFile option
+    pub output: Option<String>,
+    pub verbose: bool,
+}
+
+impl TuneOptions {
+    pub fn init() -> Self {
+        Self {
+            k: None,
+            passes: 100,
+            step: 1,
+            filter: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            output: None,
+            verbose: false,
+        }
+    }
+}
+
+pub struct Tuner {
+    pub entries: Vec<TuneEntry>,
+    pub options: TuneOptions,
+    pub params: EvalParams,
+    pub k: f64,
+}
+
+impl Tuner {
+    pub fn init(entries: Vec<TuneEntry>, options: TuneOptions) -> Self {
+        let k = options.k.unwrap_or(1.0);
+        Self { entries, options, params: EvalParams::default(), k }
+    }
+
+    ///
+    /// Static evaluations of the positions from white's point of view
+    ///
+    pub fn evaluations(&self, params: &EvalParams) -> Vec<isize> {
+        let params: SharedEvalParams = Arc::new(params.clone());
+        let chunk = self.entries.len().div_ceil(self.options.threads.max(1)).max(1);
+
+        thread::scope(|s| {
+            let workers: Vec<_> = self
+                .entries
+                .chunks(chunk)
+                .map(|entries| {
+                    let params = Arc::clone(&params);
+                    s.spawn(move || {
+                        entries.iter().map(|entry| evaluate(entry, &params)).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|w| w.join().expect("Tuner thread panicked")).collect()
+        })
+    }
+
+    pub fn error(&self, params: &EvalParams) -> f64 {
+        self.error_of(&self.evaluations(params), self.k)
+    }
+
+    ///
+    /// Fits K to the evaluations of the current weights (golden section search)
+    ///
+    pub fn fit_k(&mut self) -> f64 {
+        let evals = self.evaluations(&self.params);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut lo, mut hi) = (0.0, 5.0);
+        while hi - lo > 1e-4 {
+            let k1 = hi - ratio * (hi - lo);
+            let k2 = lo + ratio * (hi - lo);
+            match self.error_of(&evals, k1) < self.error_of(&evals, k2) {
+                true => hi = k2,
+                false => lo = k1,
+            }
+        }
+        self.k = (lo + hi) / 2.0;
+        self.k
+    }
+
+    ///
+    /// Optimizes the weights with a local search, returns the final error
+    ///
+    pub fn tune(&mut self) -> f64 {
+        if self.options.k.is_none() {
+            let k = self.fit_k();
+            println!("Fitted K = {:.4}", k);
+        }
+
+        let names = self.params.names();
+        let mut vec = self.params.to_vec();
+        let mut active: Vec<usize> = (0..vec.len())
+            .filter(|&idx| {
+                let filter = &self.options.filter;
+                filter.is_empty() || filter.iter().any(|prefix| names[idx].starts_with(prefix))
+            })
+            .collect();
+
+        let mut best = self.error(&self.params);
+        println!(
+            "Tuning {} weights on {} positions, initial error {:.6}",
+            active.len(),
+            self.entries.len(),
+            best
+        );
+
+        let step = self.options.step;
+        for pass in 1..=self.options.passes {
+            let start = Instant::now();
+            let mut improved = 0;
+            let mut unused = Vec::new();
+
+            for &idx in &active {
+                let mut changed = false;
+                let mut no_effect = true;
+                for delta in [step, -step] {
+                    vec[idx] += delta;
+                    let mut params = self.params.clone();
+                    params.from_vec(&vec);
+                    let error = self.error(&params);
+                    no_effect &= error == best;
+
+                    if error < best {
+                        best = error;
+                        self.params = params;
+                        changed = true;
+                        break;
+                    }
+                    vec[idx] -= delta;
+                }
+
+                if changed {
+                    improved += 1;
+                    if self.options.verbose {
+                        println!("{} = {}, error {:.6}", names[idx], vec[idx], best);
+                    }
+                } else if no_effect {
+                    // Weights that never change the evaluation (e.g. pawns on the 1st rank)
+                    unused.push(idx);
+                }
+            }
+
+            if pass == 1 {
+                active.retain(|idx| !unused.contains(idx));
+            }
+
+            println!(
+                "Pass {}: error {:.6}, improved {} weights, {} ms",
+                pass,
+                best,
+                improved,
+                start.elapsed().as_millis()
+            );
+            self.export();
+
+            if improved == 0 {
+                break;
+            }
+        }
+        best
+    }
+
+    ///
+    /// Writes the tuned weights to the output file
+    ///
+    pub fn export(&self) {
+        let Some(output) = &self.options.output else {
+            return;
+        };
+        let contents = match output.ends_with(".rs") {
+            true => self.params.to_rust(),
+            false => self.params.to_text(),
+        };
+        if let Err(err) = std::fs::write(output, contents) {
+            eprintln!("[TUNER]: Failed to write {}: {}", output, err);
+        }
+    }
+
+    fn error_of(&self, evals: &[isize], k: f64) -> f64 {
+        let sum: f64 = self
+            .entries
+            .iter()
+            .zip(evals)
+            .map(|(entry, &eval)| (entry.result - sigmoid(eval, k)).powi(2))
+            .sum();
+        sum / self.entries.len().max(1) as f64
+    }
+}
+
+// Expected score of white for the evaluation
+pub fn sigmoid(eval: isize, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval as f64 / 400.0))
+}
+
+fn evaluate(entry: &TuneEntry, params: &SharedEvalParams) -> isize {
+    let mut board = Board::read_fen(&entry.fen);
+    board.set_params(Arc::clone(params));
+    board.evaluation() * board.color().sign()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = TuneEntry::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - [1.0]").unwrap();
+        assert_eq!(entry.fen, "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        assert_eq!(entry.result, 1.0);
+
+        let entry = TuneEntry::parse(
+            r#"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - c9 "1/2-1/2";"#,
+        )
+        .unwrap();
+        assert_eq!(entry.result, 0.5);
+
+        let entry = TuneEntry::parse("8/8/8/8/8/5k2/8/6K1 b - - 12 40; 0-1").unwrap();
+        assert_eq!(entry.fen, "8/8/8/8/8/5k2/8/6K1 b - - 12 40");
+        assert_eq!(entry.result, 0.0);
+
+        assert!(TuneEntry::parse("8/8/8/8/8/5k2/8/6K1 b - -").is_err());
+        assert!(TuneEntry::parse("invalid [0.5]").is_err());
+    }
+
+    #[test]
+    fn test_tuner() {
+        // White is a rook up and wins, black is a knight up and only draws
+        let entries = vec![
+            TuneEntry::parse("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - [1.0]").unwrap(),
+            TuneEntry::parse("6k1/5ppp/8/8/8/8/5PPP/3R2K1 b - - [1.0]").unwrap(),
+            TuneEntry::parse("6k1/5ppp/8/4n3/8/8/5PPP/6K1 w - - [0.5]").unwrap(),
+            TuneEntry::parse("6k1/5ppp/3n4/8/8/8/5PPP/6K1 b - - [0.5]").unwrap(),
+        ];
+
+        let mut options = TuneOptions::init();
+        options.passes = 2;
+        options.step = 10;
+        options.filter = vec!["material.knight".to_string()];
+        let mut tuner = Tuner::init(entries, options);
+
+        let k = tuner.fit_k();
+        assert!(k > 0.0 && k < 5.0);
+
+        let initial = tuner.error(&EvalParams::default());
+        let error = tuner.tune();
+        assert!(error < initial);
+        // Only the knight was tuned, and drawing a knight up makes it worth less
+        let default = EvalParams::default();
+        assert!(
+            tuner.params.material[1].0 + tuner.params.material[1].1
+                < default.material[1].0 + default.material[1].1
+        );
+        assert_eq!(tuner.params.material[4], default.material[4]);
+        assert_eq!(tuner.params.psqt, default.psqt);
+    }
+}
@@ -1,21 +1,30 @@
 pub mod engine {
 
     pub mod board {
+        pub mod attack_map;
         pub mod board;
         pub mod castling;
         pub mod color;
         pub mod fen;
         pub mod moves;
+        pub mod packed;
         pub mod piece;
+        pub mod san;
         pub mod square;
         pub mod state;
         pub mod zobrist;
     }
     pub mod generated;
+    pub mod book {
+        pub mod builder;
+        pub mod native;
+        pub mod polyglot;
+    }
     pub mod move_generator {
         pub mod make_move;
         pub mod mv_gen;
         pub mod mv_oredering;
+        pub mod mv_picker;
         pub mod perft;
     }
     pub mod attacks {
@@ -29,26 +38,53 @@ pub mod engine {
         pub mod utility;
     }
     pub mod protocols {
+        pub mod cli;
+        pub mod epd;
+        pub mod options;
+        pub mod pgn;
         pub mod time;
         pub mod uci;
+        pub mod uci_client;
     }
 
     pub mod search {
         pub mod alpha_beta;
+        pub mod eval_hash_table;
+        pub mod extensions;
+        pub mod history;
         pub mod iter_deepening;
         pub mod pawn_hash_table;
         pub mod quiescence;
         pub mod transposition_table;
     }
 
+    pub mod tablebase {
+        pub mod dtm;
+        pub mod generator;
+        pub mod syzygy;
+    }
+
+    pub mod tools {
+        pub mod dataset;
+        pub mod epd_runner;
+        pub mod gensfen;
+        pub mod match_runner;
+        pub mod sprt;
+        pub mod symmetry;
+        pub mod tuner;
+    }
+
     pub mod evaluation {
+        pub mod breakdown_eval;
         pub mod common_eval;
+        pub mod eval_params;
         pub mod evaluation;
         pub mod imbalance_eval;
         pub mod init_eval;
         pub mod king_eval;
         pub mod material_eval;
         pub mod mobility_eval;
+        pub mod nnue;
         pub mod passed_pawn_eval;
         pub mod pawn_eval;
         pub mod piece_eval;
@@ -58,6 +94,7 @@ pub mod engine {
         pub mod test_evaluation;
         pub mod threats_eval;
         pub mod trace_eval;
+        pub mod winnable_eval;
     }
     pub mod misc {
         pub mod bit_pos_utility;
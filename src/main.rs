@@ -1,5 +1,6 @@
 use std::env;
 
+use crate::engine::protocols::cli::run_cli;
 use crate::engine::protocols::uci::UCI;
 
 #[cfg(feature = "dhat-heap")]
@@ -14,6 +15,12 @@ fn main() {
 
     // FIXME: Needed to backtrace the call stack
     unsafe { env::set_var("RUST_BACKTRACE", "1") };
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if run_cli(&args) {
+        return;
+    }
+
     let mut uci = UCI::init();
     uci.main();
 
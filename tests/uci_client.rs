@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use fri_challenger::engine::protocols::uci_client::{ClientError, GoParams, UciClient};
+
+const ENGINE: &str = env!("CARGO_BIN_EXE_FRI-Challenger");
+
+#[test]
+fn test_handshake_and_search() {
+    let mut engine = UciClient::spawn(ENGINE, &[]).unwrap();
+    assert!(engine.name.starts_with("FRI Challenger"));
+    assert!(engine.options.iter().any(|option| option.contains("SyzygyPath")));
+
+    engine.set_option("SyzygyProbeLimit", "0").unwrap();
+    engine.new_game().unwrap();
+    engine.position(None, &["e2e4".to_string(), "e7e5".to_string()]).unwrap();
+
+    let params = GoParams { movetime: Some(200), ..Default::default() };
+    let result = engine.go(&params, Duration::from_secs(5)).unwrap();
+    assert_eq!(result.best.mv.len(), 4);
+    assert!(result.infos.iter().any(|info| info.depth.is_some() && !info.pv.is_empty()));
+    assert!(result.score().is_some());
+
+    // With a clock
+    engine.position(Some("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"), &[]).unwrap();
+    let params = GoParams { wtime: Some(2000), btime: Some(2000), ..Default::default() };
+    let result = engine.go(&params, Duration::from_secs(5)).unwrap();
+    assert_eq!(result.best.mv, "a1a8");
+
+    assert!(engine.quit(Duration::from_secs(5)).unwrap().success());
+}
+
+#[test]
+fn test_timeout_and_invalid_position() {
+    let mut engine = UciClient::spawn(ENGINE, &[]).unwrap();
+    engine.position(None, &[]).unwrap();
+
+    let params = GoParams { infinite: true, ..Default::default() };
+    let timeout = engine.go(&params, Duration::from_millis(200));
+    assert!(matches!(timeout, Err(ClientError::Timeout(_))));
+    let stopped = engine.stop(Duration::from_secs(5)).unwrap();
+    assert_eq!(stopped.best.mv.len(), 4);
+
+    // An invalid position is reported and leaves the last one unchanged
+    engine.position(Some("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"), &[]).unwrap();
+    engine.position(Some("invalid"), &[]).unwrap();
+    let params = GoParams { depth: Some(3), movetime: Some(1000), ..Default::default() };
+    let result = engine.go(&params, Duration::from_secs(5)).unwrap();
+    assert_eq!(result.best.mv, "a1a8");
+
+    // The moves are played until the illegal one
+    engine.position(None, &["e2e4".to_string(), "e2e5".to_string()]).unwrap();
+    engine.is_ready().unwrap();
+    assert!(engine.is_alive());
+    assert!(engine.quit(Duration::from_secs(5)).unwrap().success());
+}
+
+// Engine that answers the handshake and exits on the next command
+#[cfg(unix)]
+#[test]
+fn test_crash() {
+    let script = "read cmd; echo 'id name Crash'; echo uciok; read cmd; exit 3";
+    let mut engine = UciClient::spawn("sh", &["-c", script]).unwrap();
+    assert_eq!(engine.name, "Crash");
+
+    let crashed = engine.is_ready();
+    assert!(
+        matches!(&crashed, Err(ClientError::Crashed(Some(status))) if status.code() == Some(3)),
+        "{:?}",
+        crashed
+    );
+    assert!(!engine.is_alive());
+}